@@ -1,15 +1,24 @@
 use yew::prelude::*;
+use yew_hooks::prelude::*;
 use yew_router::prelude::*;
-use crate::components::delete_comment::DeleteComment;
 
+use crate::components::comment_history::CommentHistory;
+use crate::components::delete_comment::DeleteComment;
+use crate::components::edit_comment::EditComment;
+use crate::components::moderation_actions::ModerationActions;
+use crate::components::new_comment::NewComment;
+use crate::components::report_button::ReportButton;
 use crate::middlewares::context::use_user_context;
 use crate::routes::AppRoute;
+use crate::services::comment::replies;
+use crate::services::moderation::ModerationTarget;
 use crate::types::comment::CommentDto;
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
-    pub slug: String,
+    pub post_id: String,
     pub comment: CommentDto,
+    /// Emitted with the comment id after a reply is added or a comment is edited or deleted.
     pub callback: Callback<String>,
 }
 
@@ -17,36 +26,294 @@ pub struct Props {
 pub fn comment(props: &Props) -> Html {
     let user_ctx = use_user_context();
     let comment = &props.comment;
-    let can_delete = user_ctx.is_authenticated() && user_ctx.display_name == comment.author.display_name;
+    let replying = use_state(|| false);
+    let editing = use_state(|| false);
+    let showing_history = use_state(|| false);
+    let more_replies = use_state(Vec::<CommentDto>::new);
+    let replies_cursor = use_state(|| comment.replies_cursor.clone());
+    let replies_exhausted = use_state(|| false);
+
+    let load_replies = {
+        let post_id = props.post_id.clone();
+        let comment_id = comment.id.clone();
+        let cursor = (*replies_cursor).clone();
+        use_async(async move { replies(post_id, comment_id, cursor).await })
+    };
+
+    {
+        let more_replies = more_replies.clone();
+        let replies_cursor = replies_cursor.clone();
+        let replies_exhausted = replies_exhausted.clone();
+        use_effect_with(
+            props.comment.clone(),
+            move |comment| {
+                more_replies.set(Vec::new());
+                replies_cursor.set(comment.replies_cursor.clone());
+                replies_exhausted.set(false);
+                || ()
+            },
+        );
+    }
+
+    {
+        let more_replies = more_replies.clone();
+        let replies_cursor = replies_cursor.clone();
+        let replies_exhausted = replies_exhausted.clone();
+        use_effect_with(
+            load_replies.clone(),
+            move |load_replies| {
+                if let (false, Some(resp)) = (load_replies.loading, &load_replies.data) {
+                    let mut loaded = (*more_replies).clone();
+                    loaded.extend(resp.data.comments.iter().cloned());
+                    more_replies.set(loaded);
+                    replies_exhausted.set(resp.data.next_cursor.is_none());
+                    replies_cursor.set(resp.data.next_cursor.clone());
+                }
+                || ()
+            },
+        );
+    }
+
+    let on_toggle_reply = {
+        let replying = replying.clone();
+        Callback::from(move |_| replying.set(!*replying))
+    };
+
+    let on_toggle_edit = {
+        let editing = editing.clone();
+        Callback::from(move |_| editing.set(!*editing))
+    };
+
+    let on_cancel_edit = {
+        let editing = editing.clone();
+        Callback::from(move |_| editing.set(false))
+    };
+
+    let on_toggle_history = {
+        let showing_history = showing_history.clone();
+        Callback::from(move |_| showing_history.set(!*showing_history))
+    };
+
+    let callback_edited = {
+        let editing = editing.clone();
+        let showing_history = showing_history.clone();
+        let callback = props.callback.clone();
+        let comment_id = comment.id.clone();
+        Callback::from(move |_: CommentDto| {
+            editing.set(false);
+            showing_history.set(false);
+            callback.emit(comment_id.clone());
+        })
+    };
+
+    let on_load_replies = {
+        let load_replies = load_replies.clone();
+        Callback::from(move |_| load_replies.run())
+    };
+
+    let callback_replied = {
+        let replying = replying.clone();
+        let callback = props.callback.clone();
+        let comment_id = comment.id.clone();
+        Callback::from(move |_: CommentDto| {
+            replying.set(false);
+            callback.emit(comment_id.clone());
+        })
+    };
+
+    let moderated = {
+        let callback = props.callback.clone();
+        let comment_id = comment.id.clone();
+        Callback::from(move |_: String| callback.emit(comment_id.clone()))
+    };
+
+    let shown_replies = comment.replies.len() + more_replies.len();
+    let has_more_replies = !*replies_exhausted && comment.reply_count as usize > shown_replies;
 
     html! {
-        <div class="p-4 border border-gray-200 rounded-md shadow-md mb-4">
-            <p class="text-gray-800 mb-4">{ &comment.body }</p>
-            <div class="flex items-center space-x-2 text-gray-600">
-                <img
-                    src={ comment.author.profile_image_url.clone() }
-                    class="w-8 h-8 rounded-full"
-                    alt={ comment.author.display_name.clone() }
-                />
-                <Link<AppRoute> classes="font-semibold hover:text-indigo-600" to={AppRoute::Profile { display_name: comment.author.display_name.clone() }}>
-                    { &comment.author.display_name }
-                </Link<AppRoute>>
-                <span class="text-xs">
-                    { format!("{}", &comment.created_at.format("%B %e, %Y %H:%M")) }
-                </span>
-                { if can_delete {
+        <div class="mb-4">
+            {
+                match &comment.author {
+                    Some(author) if !comment.deleted && !comment.hidden => {
+                        let can_delete = user_ctx.is_authenticated() && user_ctx.display_name == author.display_name;
+                        let can_moderate = !can_delete && user_ctx.is_moderator();
+                        html! {
+                            <div class="p-4 border border-gray-200 rounded-md shadow-md">
+                                {
+                                    if *editing {
+                                        html! {
+                                            <div class="mb-4">
+                                                <EditComment
+                                                    post_id={props.post_id.clone()}
+                                                    comment={comment.clone()}
+                                                    callback={callback_edited}
+                                                    oncancel={on_cancel_edit} />
+                                            </div>
+                                        }
+                                    } else {
+                                        html! { <p class="text-gray-800 mb-4">{ &comment.body }</p> }
+                                    }
+                                }
+                                <div class="flex items-center space-x-2 text-gray-600">
+                                    <img
+                                        src={ author.profile_image_url.clone() }
+                                        class="w-8 h-8 rounded-full"
+                                        alt={ author.display_name.clone() }
+                                    />
+                                    <Link<AppRoute> classes="font-semibold hover:text-indigo-600" to={AppRoute::Profile { display_name: author.display_name.clone() }}>
+                                        { &author.display_name }
+                                    </Link<AppRoute>>
+                                    <span class="text-xs">
+                                        { format!("{}", &comment.created_at.format("%B %e, %Y %H:%M")) }
+                                    </span>
+                                    {
+                                        match comment.edited_at {
+                                            Some(edited_at) => html! {
+                                                <button
+                                                    class="text-xs italic hover:text-indigo-600"
+                                                    title={ format!("Edited {}", edited_at.format("%B %e, %Y %H:%M")) }
+                                                    onclick={on_toggle_history}>
+                                                    { if *showing_history { "(hide history)" } else { "(edited)" } }
+                                                </button>
+                                            },
+                                            None => html! {},
+                                        }
+                                    }
+                                    {
+                                        if user_ctx.is_authenticated() {
+                                            html! {
+                                                <button class="text-xs hover:text-indigo-600" onclick={on_toggle_reply}>
+                                                    { if *replying { "Cancel" } else { "Reply" } }
+                                                </button>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                    { if can_delete {
+                                        html! {
+                                            <button class="text-xs hover:text-indigo-600" onclick={on_toggle_edit}>
+                                                { if *editing { "Cancel edit" } else { "Edit" } }
+                                            </button>
+                                        }
+                                    } else {
+                                        html! { }
+                                    }}
+                                    { if can_delete {
+                                        html! {
+                                            <DeleteComment
+                                                post_id={props.post_id.clone()}
+                                                comment_id={comment.id.clone()}
+                                                callback={props.callback.clone()}
+                                                />
+                                        }
+                                    } else {
+                                        html! { }
+                                    }}
+                                    { if can_moderate {
+                                        html! {
+                                            <ModerationActions
+                                                target={ModerationTarget::Comment(comment.id.clone())}
+                                                callback={moderated.clone()} />
+                                        }
+                                    } else {
+                                        html! { }
+                                    }}
+                                    { if user_ctx.is_authenticated() && !can_delete {
+                                        html! {
+                                            <ReportButton
+                                                post_id={props.post_id.clone()}
+                                                comment_id={Some(comment.id.clone())} />
+                                        }
+                                    } else {
+                                        html! { }
+                                    }}
+                                </div>
+                                {
+                                    if *showing_history {
+                                        html! {
+                                            <CommentHistory
+                                                post_id={props.post_id.clone()}
+                                                comment_id={comment.id.clone()} />
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                            </div>
+                        }
+                    }
+                    _ if comment.hidden => html! {
+                        <div class="p-4 border border-gray-200 rounded-md flex items-center justify-between">
+                            <p class="text-gray-400 italic">{ "This comment was hidden by a moderator." }</p>
+                            {
+                                if user_ctx.is_moderator() {
+                                    html! {
+                                        <ModerationActions
+                                            target={ModerationTarget::Comment(comment.id.clone())}
+                                            hidden={true}
+                                            callback={moderated} />
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
+                        </div>
+                    },
+                    _ => html! {
+                        <div class="p-4 border border-gray-200 rounded-md">
+                            <p class="text-gray-400 italic">{ "This comment has been deleted." }</p>
+                        </div>
+                    },
+                }
+            }
+            {
+                if *replying {
                     html! {
-                        <DeleteComment
-                            post_id={props.slug.clone()}
-                            comment_id={comment.id.clone()}
-                            callback={props.callback.clone()}
-                            />
+                        <div class="ml-6">
+                            <NewComment
+                                post_id={props.post_id.clone()}
+                                parent_id={Some(comment.id.clone())}
+                                callback={callback_replied} />
+                        </div>
                     }
                 } else {
-                    html! { }
-                }}
-            </div>
+                    html! {}
+                }
+            }
+            {
+                if shown_replies > 0 || has_more_replies {
+                    html! {
+                        <div class="ml-6 mt-4 pl-4 border-l border-gray-200">
+                            {for comment.replies.iter().chain(more_replies.iter()).map(|reply| {
+                                html! {
+                                    <Comment
+                                        key={reply.id.clone()}
+                                        post_id={props.post_id.clone()}
+                                        comment={reply.clone()}
+                                        callback={props.callback.clone()} />
+                                }
+                            })}
+                            {
+                                if has_more_replies {
+                                    html! {
+                                        <button
+                                            class="text-sm text-indigo-600 hover:underline disabled:opacity-50"
+                                            onclick={on_load_replies}
+                                            disabled={load_replies.loading}>
+                                            { format!("Show more replies ({})", comment.reply_count as usize - shown_replies) }
+                                        </button>
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
         </div>
     }
 }
-
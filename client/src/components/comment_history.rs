@@ -0,0 +1,54 @@
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::show_error::ShowError;
+use crate::services::comment::revisions;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub post_id: String,
+    pub comment_id: String,
+}
+
+/// Earlier bodies of an edited comment, newest first.
+#[function_component(CommentHistory)]
+pub fn comment_history(props: &Props) -> Html {
+    let comment_revisions = {
+        let post_id = props.post_id.clone();
+        let comment_id = props.comment_id.clone();
+        use_async_with_options(
+            async move { revisions(post_id, comment_id).await },
+            UseAsyncOptions::enable_auto(),
+        )
+    };
+
+    html! {
+        <div class="mt-4 pl-4 border-l-2 border-gray-200">
+            <ShowError error={comment_revisions.error.clone()} />
+            {
+                if comment_revisions.loading {
+                    html! { <p class="text-sm text-gray-400">{ "Loading history..." }</p> }
+                } else if let Some(resp) = &comment_revisions.data {
+                    html! {
+                        {for resp.data.revisions.iter().map(|revision| {
+                            html! {
+                                <div key={revision.id.clone()} class="mb-2">
+                                    <p class="text-xs text-gray-500">
+                                        { format!(
+                                            "Written {}, replaced {}",
+                                            revision.written_at.format("%B %e, %Y %H:%M"),
+                                            revision.replaced_at.format("%B %e, %Y %H:%M"),
+                                        ) }
+                                    </p>
+                                    <p class="text-gray-600 whitespace-pre-wrap">{ &revision.body }</p>
+                                </div>
+                            }
+                        })}
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
@@ -7,6 +7,9 @@ use crate::components::new_comment::NewComment;
 use crate::middlewares::context::use_user_context;
 use crate::routes::AppRoute;
 use crate::services::comment::get;
+use crate::services::event::subscribe_post;
+use crate::types::comment::CommentDto;
+use crate::types::event::LiveEvent;
 
 #[derive(Properties, Clone, PartialEq, Eq)]
 pub struct Props {
@@ -16,68 +19,177 @@ pub struct Props {
 #[function_component(CommentList)]
 pub fn comment_list(props: &Props) -> Html {
     let user_ctx = use_user_context();
+    let comments = use_state(Vec::<CommentDto>::new);
+    let cursor = use_state(|| None::<String>);
+    let next_cursor = use_state(|| None::<String>);
+    // Comments others added since the list was loaded; shown on request so paging isn't lost.
+    let new_comments = use_counter(0);
     let comment_list = {
         let post_id = props.post_id.clone();
-        use_async_with_options(
-            async move { get(post_id).await },
-            UseAsyncOptions::enable_auto(),
-        )
+        let cursor = (*cursor).clone();
+        use_async(async move { get(post_id, cursor).await })
     };
 
-    let callback_added = {
+    {
         let comment_list = comment_list.clone();
-        Callback::from(move |_| {
-            comment_list.run();
-        })
-    };
+        use_effect_with(
+            (props.post_id.clone(), (*cursor).clone()),
+            move |_| {
+                comment_list.run();
+                || ()
+            },
+        );
+    }
+
+    {
+        let new_comments = new_comments.clone();
+        let display_name = user_ctx.display_name.clone();
+        use_effect_with(
+            props.post_id.clone(),
+            move |post_id| {
+                new_comments.reset();
+                let subscription = subscribe_post(post_id.clone(), Callback::from(move |event| {
+                    if let LiveEvent::CommentAdded { author_display_name, .. } = event {
+                        if author_display_name != display_name {
+                            new_comments.increase();
+                        }
+                    }
+                }));
+                move || subscription.abort()
+            },
+        );
+    }
 
-    let callback_deleted = {
+    {
+        let comments = comments.clone();
+        let cursor = cursor.clone();
+        let next_cursor = next_cursor.clone();
+        let new_comments = new_comments.clone();
+        use_effect_with(
+            comment_list.clone(),
+            move |comment_list| {
+                if let (false, Some(resp)) = (comment_list.loading, &comment_list.data) {
+                    if cursor.is_none() {
+                        comments.set(resp.data.comments.clone());
+                    } else {
+                        let mut loaded = (*comments).clone();
+                        loaded.extend(resp.data.comments.iter().cloned());
+                        comments.set(loaded);
+                    }
+                    next_cursor.set(resp.data.next_cursor.clone());
+                    if cursor.is_none() {
+                        new_comments.reset();
+                    }
+                }
+                || ()
+            },
+        );
+    }
+
+    // Threads can change anywhere in the tree, so start over from the first page.
+    let reload = {
         let comment_list = comment_list.clone();
+        let cursor = cursor.clone();
         Callback::from(move |_| {
-            comment_list.run();
+            if cursor.is_none() {
+                comment_list.run();
+            } else {
+                cursor.set(None);
+            }
         })
     };
 
-    if let Some(comment_list) = &comment_list.data {
-        html! {
-            <div class="mt-4">
-                <div>
-                    {for comment_list.data.comments.iter().map(|comment_dto| {
-                        html! {
-                            <Comment
-                                slug={props.post_id.clone()}
-                                comment={comment_dto.clone()}
-                                callback={callback_deleted.clone()} />
-                        }
-                    })}
-                </div>
+    let callback_added = {
+        let reload = reload.clone();
+        Callback::from(move |_: CommentDto| reload.emit(()))
+    };
+
+    let callback_changed = {
+        let reload = reload.clone();
+        Callback::from(move |_: String| reload.emit(()))
+    };
+
+    let on_show_new = {
+        let reload = reload.clone();
+        Callback::from(move |_| reload.emit(()))
+    };
+
+    let on_load_more = {
+        let cursor = cursor.clone();
+        let next_cursor = next_cursor.clone();
+        Callback::from(move |_| cursor.set((*next_cursor).clone()))
+    };
+
+    html! {
+        <div class="mt-4">
+            {
+                if *new_comments > 0 {
+                    html! {
+                        <button
+                            class="mb-4 w-full py-2 text-sm text-indigo-600 bg-indigo-50 rounded-md hover:bg-indigo-100"
+                            onclick={on_show_new}>
+                            {
+                                if *new_comments == 1 {
+                                    "Show 1 new comment".to_string()
+                                } else {
+                                    format!("Show {} new comments", *new_comments)
+                                }
+                            }
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            <div>
+                {for comments.iter().map(|comment_dto| {
+                    html! {
+                        <Comment
+                            key={comment_dto.id.clone()}
+                            post_id={props.post_id.clone()}
+                            comment={comment_dto.clone()}
+                            callback={callback_changed.clone()} />
+                    }
+                })}
                 {
-                    if user_ctx.is_authenticated() {
+                    if next_cursor.is_some() {
                         html! {
-                            <div>
-                                <NewComment
-                                    post_id={props.post_id.clone()}
-                                    callback={callback_added} />
-                            </div>
+                            <button
+                                class="mb-4 text-sm text-indigo-600 hover:underline disabled:opacity-50"
+                                onclick={on_load_more}
+                                disabled={comment_list.loading}>
+                                { "Load more comments" }
+                            </button>
                         }
                     } else {
-                        html! {
-                            <p>
-                                <Link<AppRoute> to={AppRoute::Login} classes="nav-link">
-                                    { "Login" }
-                                </Link<AppRoute>>
-                                { " or " }
-                                <Link<AppRoute> to={AppRoute::SignUp} classes="nav-link">
-                                    { "Sign up" }
-                                </Link<AppRoute>>
-                                { " to add comments on this post." }
-                            </p>
-                        }
+                        html! {}
                     }
                 }
             </div>
-        }
-    } else {
-        html! {}
+            {
+                if user_ctx.is_authenticated() {
+                    html! {
+                        <div>
+                            <NewComment
+                                post_id={props.post_id.clone()}
+                                callback={callback_added} />
+                        </div>
+                    }
+                } else {
+                    html! {
+                        <p>
+                            <Link<AppRoute> to={AppRoute::Login} classes="nav-link">
+                                { "Login" }
+                            </Link<AppRoute>>
+                            { " or " }
+                            <Link<AppRoute> to={AppRoute::SignUp} classes="nav-link">
+                                { "Sign up" }
+                            </Link<AppRoute>>
+                            { " to add comments on this post." }
+                        </p>
+                    }
+                }
+            }
+        </div>
     }
 }
@@ -0,0 +1,94 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::field_error::FieldError;
+use crate::components::show_error::ShowError;
+use crate::services::comment::update;
+use crate::types::comment::{CommentDto, UpdateCommentDto};
+use crate::types::Wrapper;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub post_id: String,
+    pub comment: CommentDto,
+    pub callback: Callback<CommentDto>,
+    pub oncancel: Callback<()>,
+}
+
+#[function_component(EditComment)]
+pub fn edit_comment(props: &Props) -> Html {
+    let update_comment_dto = use_state(|| UpdateCommentDto {
+        body: props.comment.body.clone(),
+    });
+    let update_comment = {
+        let post_id = props.post_id.clone();
+        let comment_id = props.comment.id.clone();
+        let update_comment_dto = update_comment_dto.clone();
+        use_async(async move {
+            let req = Wrapper::<UpdateCommentDto> { data: (*update_comment_dto).clone() };
+            update(post_id, comment_id, req).await
+        })
+    };
+
+    let onsubmit = {
+        let update_comment = update_comment.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            update_comment.run();
+        })
+    };
+
+    let oninput = {
+        let update_comment_dto = update_comment_dto.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            update_comment_dto.set(UpdateCommentDto { body: input.value() });
+        })
+    };
+
+    let on_cancel = {
+        let oncancel = props.oncancel.clone();
+        Callback::from(move |_| oncancel.emit(()))
+    };
+
+    {
+        let callback = props.callback.clone();
+        use_effect_with(
+            update_comment.clone(),
+            move |update_comment| {
+                if let Some(comment_dto) = &update_comment.data {
+                    callback.emit(comment_dto.data.clone());
+                }
+                || ()
+            }
+        )
+    };
+
+    let button_disabled = update_comment.loading
+        || update_comment_dto.body.is_empty()
+        || update_comment_dto.body == props.comment.body;
+
+    html! {
+        <div>
+            <ShowError error={update_comment.error.clone()} />
+            <form onsubmit={onsubmit}>
+                <textarea
+                    class="w-full p-3 border rounded-md resize-none focus:outline-none focus:border-indigo-500"
+                    rows="3"
+                    value={update_comment_dto.body.clone()}
+                    oninput={oninput} >
+                </textarea>
+                <FieldError error={update_comment.error.clone()} field="body" />
+                <div class="flex justify-end space-x-2 mt-2">
+                    <button class="px-3 py-1 text-sm border rounded-md hover:bg-gray-100" type="button" onclick={on_cancel}>
+                        { "Cancel" }
+                    </button>
+                    <button class="px-3 py-1 text-sm bg-indigo-600 text-white rounded-md hover:bg-indigo-700 disabled:opacity-50" type="submit" disabled={button_disabled}>
+                        { "Save" }
+                    </button>
+                </div>
+            </form>
+        </div>
+    }
+}
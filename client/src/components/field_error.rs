@@ -0,0 +1,30 @@
+use yew::prelude::*;
+
+use crate::middlewares::error::Error;
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub error: Option<Error>,
+    pub field: AttrValue,
+}
+
+/// Messages the server reported for one form field, rendered below its input.
+#[function_component(FieldError)]
+pub fn field_error(props: &Props) -> Html {
+    let Some(Error::UnprocessableEntity(error_info)) = &props.error else {
+        return html! {};
+    };
+    let Some(messages) = error_info.errors.get(props.field.as_str()) else {
+        return html! {};
+    };
+
+    html! {
+        <ul class="mt-1 text-sm text-red-600">
+            {
+                for messages.iter().map(|message| {
+                    html! { <li>{ message }</li> }
+                })
+            }
+        </ul>
+    }
+}
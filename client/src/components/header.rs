@@ -1,6 +1,8 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::notification_bell::NotificationBell;
+use crate::components::search_box::SearchBox;
 use crate::middlewares::context::{use_user_context, UserUseStateHandle};
 use crate::routes::AppRoute;
 
@@ -13,6 +15,7 @@ pub fn header() -> Html {
                 <Link<AppRoute> to={ AppRoute::Home } classes="text-black text-2xl font-bold">
                     { "Negatiview" }
                 </Link<AppRoute >>
+                <SearchBox />
                 {
                     if user_ctx.is_authenticated() {
                         logged_in_view(&user_ctx)
@@ -65,6 +68,35 @@ fn logged_in_view(user_ctx: &UserUseStateHandle) -> Html {
                     { "Settings" }
                 </Link<AppRoute>>
             </li>
+            {
+                if user_ctx.is_moderator() {
+                    html! {
+                        <li>
+                            <Link<AppRoute> to={AppRoute::Moderation} classes="text-black hover:underline">
+                                { "Reports" }
+                            </Link<AppRoute>>
+                        </li>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if user_ctx.is_admin() {
+                    html! {
+                        <li>
+                            <Link<AppRoute> to={AppRoute::Admin} classes="text-black hover:underline">
+                                { "Admin" }
+                            </Link<AppRoute>>
+                        </li>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            <li>
+                <NotificationBell />
+            </li>
             <li>
                 <Link<AppRoute> to={AppRoute::Profile { display_name: user_ctx.display_name.clone()}} classes="text-black hover:underline font-semibold">
                     { &user_ctx.display_name }
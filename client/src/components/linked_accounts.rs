@@ -0,0 +1,93 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::show_error::ShowError;
+use crate::middlewares::error::Error;
+use crate::services::oauth::{identities, providers, redirect_to_provider, unlink};
+
+/// The sign-in providers linked to the current user, with buttons to link the others.
+#[function_component(LinkedAccounts)]
+pub fn linked_accounts() -> Html {
+    let providers = use_async_with_options(
+        async move { providers().await },
+        UseAsyncOptions::enable_auto(),
+    );
+    let identities = use_async_with_options(
+        async move { identities().await },
+        UseAsyncOptions::enable_auto(),
+    );
+    let error = use_state(|| None::<Error>);
+
+    let (Some(providers), Some(linked)) = (&providers.data, &identities.data) else {
+        return html! { <ShowError error={identities.error.clone()} /> };
+    };
+    if providers.data.is_empty() {
+        return html! {};
+    }
+
+    let only_way_in = !linked.data.has_password && linked.data.identities.len() == 1;
+
+    html! {
+        <div class="mt-8">
+            <h2 class="text-lg font-semibold mb-2">{ "Linked Accounts" }</h2>
+            <ShowError error={(*error).clone()} />
+            <ul class="divide-y border rounded-md">
+                {for providers.data.iter().map(|provider| {
+                    let identity = linked.data.identities.iter().find(|identity| identity.provider == provider.name);
+                    let onclick = {
+                        let identities = identities.clone();
+                        let error = error.clone();
+                        let name = provider.name.clone();
+                        let linked = identity.is_some();
+                        Callback::from(move |_| {
+                            let identities = identities.clone();
+                            let error = error.clone();
+                            let name = name.clone();
+                            spawn_local(async move {
+                                let result = if linked {
+                                    unlink(name).await.map(|_| identities.run())
+                                } else {
+                                    redirect_to_provider(name, true).await
+                                };
+                                error.set(result.err());
+                            });
+                        })
+                    };
+                    html! {
+                        <li class="p-3 flex justify-between items-center" key={provider.name.clone()}>
+                            <div>
+                                <p class="text-sm font-medium text-gray-800">{ &provider.display_name }</p>
+                                {
+                                    if let Some(identity) = identity {
+                                        html! {
+                                            <p class="text-xs text-gray-500">
+                                                { identity.email.clone().unwrap_or_default() }
+                                                { format!(" · linked {}", identity.created_at.format("%B %e, %Y")) }
+                                            </p>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                            </div>
+                            {
+                                match identity {
+                                    Some(_) if only_way_in => html! {
+                                        <span class="text-xs text-gray-500" title="Set a password first">{ "Only way to log in" }</span>
+                                    },
+                                    Some(_) => html! {
+                                        <button class="text-sm text-red-600 hover:underline" onclick={onclick}>{ "Unlink" }</button>
+                                    },
+                                    None => html! {
+                                        <button class="text-sm text-blue-500 hover:underline" onclick={onclick}>{ "Link" }</button>
+                                    },
+                                }
+                            }
+                        </li>
+                    }
+                })}
+            </ul>
+        </div>
+    }
+}
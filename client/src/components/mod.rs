@@ -1,15 +1,31 @@
 pub mod banner;
 pub mod comment;
+pub mod comment_history;
 pub mod comment_list;
 pub mod delete_comment;
+pub mod edit_comment;
+pub mod field_error;
+pub mod linked_accounts;
 pub mod show_error;
 pub mod footer;
 pub mod header;
+pub mod moderation_actions;
 pub mod new_comment;
+pub mod notification_bell;
+pub mod notification_preferences;
+pub mod oauth_buttons;
 pub mod pagination;
 pub mod post_action;
+pub mod post_diff;
 pub mod post_list;
 pub mod post_meta;
 pub mod post_preview;
+pub mod related_user_list;
+pub mod report_button;
+pub mod revoke_session;
+pub mod search_box;
+pub mod session_list;
 pub mod tag;
+pub mod two_factor_prompt;
+pub mod two_factor_settings;
 pub mod user_context_provider;
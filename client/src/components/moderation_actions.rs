@@ -0,0 +1,97 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::middlewares::error::Error;
+use crate::services::moderation::{hide, remove, unhide, ModerationTarget};
+use crate::types::Wrapper;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Hide,
+    Unhide,
+    Remove,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub target: ModerationTarget,
+    #[prop_or_default]
+    pub hidden: bool,
+    /// Emitted with the action's name once it has been applied.
+    pub callback: Callback<String>,
+}
+
+/// Hide, unhide and remove buttons for moderators. Each asks for the reason that goes into the
+/// audit log.
+#[function_component(ModerationActions)]
+pub fn moderation_actions(props: &Props) -> Html {
+    let error = use_state(|| None::<String>);
+
+    let on_action = |action: Action| {
+        let target = props.target.clone();
+        let callback = props.callback.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| {
+            let label = match action {
+                Action::Hide => "hide",
+                Action::Unhide => "unhide",
+                Action::Remove => "remove",
+            };
+            let Some(reason) = web_sys::window()
+                .and_then(|window| window.prompt_with_message(&format!("Reason to {} this:", label)).ok())
+                .flatten()
+                .map(|reason| reason.trim().to_string())
+                .filter(|reason| !reason.is_empty())
+            else {
+                return;
+            };
+
+            let target = target.clone();
+            let callback = callback.clone();
+            let error = error.clone();
+            spawn_local(async move {
+                let result: Result<Wrapper<String>, Error> = match action {
+                    Action::Hide => hide(target, reason).await,
+                    Action::Unhide => unhide(target, reason).await,
+                    Action::Remove => remove(target, reason).await,
+                };
+                match result {
+                    Ok(_) => {
+                        error.set(None);
+                        callback.emit(label.to_string());
+                    }
+                    Err(err) => error.set(Some(err.to_string())),
+                }
+            });
+        })
+    };
+
+    html! {
+        <span class="inline-flex items-center space-x-2 text-xs">
+            {
+                if props.hidden {
+                    html! {
+                        <button class="text-amber-600 hover:underline" onclick={on_action(Action::Unhide)}>
+                            { "Unhide" }
+                        </button>
+                    }
+                } else {
+                    html! {
+                        <button class="text-amber-600 hover:underline" onclick={on_action(Action::Hide)}>
+                            { "Hide" }
+                        </button>
+                    }
+                }
+            }
+            <button class="text-red-600 hover:underline" onclick={on_action(Action::Remove)}>
+                { "Remove" }
+            </button>
+            {
+                match &*error {
+                    Some(error) => html! { <span class="text-red-500">{ error }</span> },
+                    None => html! {},
+                }
+            }
+        </span>
+    }
+}
@@ -2,15 +2,19 @@ use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_hooks::prelude::*;
 
+use crate::components::field_error::FieldError;
 use crate::components::show_error::ShowError;
 use crate::middlewares::context::use_user_context;
-use crate::services::comment::create;
+use crate::services::comment::{create, reply};
 use crate::types::comment::{CommentDto, NewCommentDto};
 use crate::types::Wrapper;
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     pub post_id: String,
+    /// Set when the new comment is a reply.
+    #[prop_or_default]
+    pub parent_id: Option<String>,
     pub callback: Callback<CommentDto>,
 }
 
@@ -20,12 +24,14 @@ pub fn new_comment(props: &Props) -> Html {
     let new_comment_dto = use_state(NewCommentDto::default);
     let new_comment = {
         let post_id = props.post_id.clone();
+        let parent_id = props.parent_id.clone();
         let new_comment_dto = new_comment_dto.clone();
         use_async(async move {
-            create(
-                post_id,
-                Wrapper::<NewCommentDto> { data: (*new_comment_dto).clone() }
-            ).await
+            let req = Wrapper::<NewCommentDto> { data: (*new_comment_dto).clone() };
+            match parent_id {
+                Some(parent_id) => reply(post_id, parent_id, req).await,
+                None => create(post_id, req).await,
+            }
         })
     };
 
@@ -87,10 +93,11 @@ pub fn new_comment(props: &Props) -> Html {
                     <textarea
                         class="w-full p-3 border rounded-md resize-none focus:outline-none focus:border-indigo-500"
                         rows="3"
-                        placeholder="Write a comment..."
+                        placeholder={ if props.parent_id.is_some() { "Write a reply..." } else { "Write a comment..." } }
                         value={new_comment_dto.body.clone()}
                         oninput={oninput} >
                     </textarea>
+                    <FieldError error={new_comment.error.clone()} field="body" />
                     <button class="p-1 bg-indigo-600 text-white rounded-md hover:bg-indigo-700 disabled:opacity-50 absolute right-2 bottom-2" type="submit" disabled={button_disabled}>
                         <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke-width="1.5" stroke="currentColor" class="w-5 h-5">
                             <path stroke-linecap="round" stroke-linejoin="round" d="M6 12L3.269 3.126A59.768 59.768 0 0121.485 12 59.77 59.77 0 013.27 20.876L5.999 12zm0 0h7.5" />
@@ -0,0 +1,204 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::routes::AppRoute;
+use crate::services::event::subscribe_notifications;
+use crate::services::notification::{latest, mark_all_read, mark_read, unread_count};
+use crate::types::event::LiveEvent;
+use crate::types::notification::{NotificationDto, NotificationKind};
+
+/// How often the unread count is refreshed in case the notification stream is down.
+const POLL_INTERVAL_MS: u32 = 60_000;
+
+/// A bell with the unread count; clicking it opens the latest notifications.
+#[function_component(NotificationBell)]
+pub fn notification_bell() -> Html {
+    let navigator = use_navigator().unwrap();
+    let open = use_state(|| false);
+    let unread = use_state(|| 0_i64);
+
+    let count = use_async_with_options(
+        async move { unread_count().await },
+        UseAsyncOptions::enable_auto(),
+    );
+    let notifications = use_async(async move { latest().await });
+
+    {
+        let count = count.clone();
+        use_interval(move || count.run(), POLL_INTERVAL_MS);
+    }
+
+    {
+        let unread = unread.clone();
+        use_effect_with(
+            (),
+            move |_| {
+                let subscription = subscribe_notifications(Callback::from(move |event| {
+                    if let LiveEvent::Notifications { unread_count } = event {
+                        unread.set(unread_count);
+                    }
+                }));
+                move || subscription.abort()
+            },
+        );
+    }
+
+    {
+        let unread = unread.clone();
+        use_effect_with(
+            count.data.clone(),
+            move |count| {
+                if let Some(count) = count {
+                    unread.set(count.data.unread_count);
+                }
+                || ()
+            },
+        );
+    }
+
+    {
+        let unread = unread.clone();
+        use_effect_with(
+            notifications.data.clone(),
+            move |notifications| {
+                if let Some(notifications) = notifications {
+                    unread.set(notifications.data.unread_count);
+                }
+                || ()
+            },
+        );
+    }
+
+    let on_toggle = {
+        let open = open.clone();
+        let notifications = notifications.clone();
+        Callback::from(move |_| {
+            if !*open {
+                notifications.run();
+            }
+            open.set(!*open);
+        })
+    };
+
+    let on_mark_all_read = {
+        let notifications = notifications.clone();
+        Callback::from(move |_| {
+            let notifications = notifications.clone();
+            spawn_local(async move {
+                if mark_all_read().await.is_ok() {
+                    notifications.run();
+                }
+            });
+        })
+    };
+
+    let on_select = {
+        let open = open.clone();
+        let unread = unread.clone();
+        Callback::from(move |notification: NotificationDto| {
+            open.set(false);
+            if !notification.read {
+                let unread = unread.clone();
+                let notification_id = notification.id.clone();
+                spawn_local(async move {
+                    if let Ok(count) = mark_read(notification_id).await {
+                        unread.set(count.data.unread_count);
+                    }
+                });
+            }
+            navigator.push(&target(&notification));
+        })
+    };
+
+    html! {
+        <div class="relative">
+            <button class="relative p-1 text-black hover:text-indigo-600" onclick={on_toggle} title="Notifications">
+                <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke-width="1.5" stroke="currentColor" class="w-6 h-6">
+                    <path stroke-linecap="round" stroke-linejoin="round" d="M14.857 17.082a23.848 23.848 0 005.454-1.31A8.967 8.967 0 0118 9.75v-.7V9A6 6 0 006 9v.75a8.967 8.967 0 01-2.312 6.022c1.733.64 3.56 1.085 5.455 1.31m5.714 0a24.255 24.255 0 01-5.714 0m5.714 0a3 3 0 11-5.714 0" />
+                </svg>
+                {
+                    if *unread > 0 {
+                        html! {
+                            <span class="absolute -top-1 -right-1 px-1 min-w-[1.25rem] text-xs text-white bg-red-600 rounded-full">
+                                { if *unread > 99 { "99+".to_string() } else { unread.to_string() } }
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </button>
+            {
+                if *open {
+                    html! {
+                        <div class="absolute right-0 z-20 mt-2 w-80 bg-white border rounded-md shadow-lg">
+                            <div class="flex justify-between items-center px-3 py-2 border-b">
+                                <span class="font-semibold text-sm">{ "Notifications" }</span>
+                                <button class="text-xs text-indigo-600 hover:underline" onclick={on_mark_all_read}>
+                                    { "Mark all as read" }
+                                </button>
+                            </div>
+                            {
+                                match &notifications.data {
+                                    Some(resp) if !resp.data.notifications.is_empty() => html! {
+                                        <ul class="max-h-96 overflow-y-auto divide-y">
+                                            { for resp.data.notifications.iter().map(|notification| view_notification(notification, &on_select)) }
+                                        </ul>
+                                    },
+                                    Some(_) => html! { <p class="p-3 text-sm text-gray-500">{ "Nothing new." }</p> },
+                                    None => html! { <p class="p-3 text-sm text-gray-500">{ "Loading" }</p> },
+                                }
+                            }
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
+
+fn view_notification(notification: &NotificationDto, on_select: &Callback<NotificationDto>) -> Html {
+    let onclick = {
+        let on_select = on_select.clone();
+        let notification = notification.clone();
+        Callback::from(move |_| on_select.emit(notification.clone()))
+    };
+    let title = notification.post_title.clone().unwrap_or_default();
+    let text = match notification.kind {
+        NotificationKind::Follow => "started following you".to_string(),
+        NotificationKind::Favorite => format!("favorited \"{}\"", title),
+        NotificationKind::Comment => format!("commented on \"{}\"", title),
+    };
+
+    html! {
+        <li
+            key={notification.id.clone()}
+            class={classes!("flex", "items-start", "space-x-2", "p-3", "cursor-pointer", "hover:bg-gray-50", (!notification.read).then_some("bg-indigo-50"))}
+            onclick={onclick}>
+            <img
+                src={notification.actor_profile_image_url.clone()}
+                class="w-8 h-8 rounded-full"
+                alt={notification.actor_display_name.clone()} />
+            <div class="text-sm">
+                <p>
+                    <span class="font-semibold">{ &notification.actor_display_name }</span>
+                    { " " }
+                    { text }
+                </p>
+                <p class="text-xs text-gray-500">{ notification.created_at.format("%B %e, %Y %H:%M").to_string() }</p>
+            </div>
+        </li>
+    }
+}
+
+/// Where clicking a notification goes: the post it is about, or the follower's profile.
+fn target(notification: &NotificationDto) -> AppRoute {
+    match &notification.post_slug {
+        Some(slug) if notification.kind != NotificationKind::Follow => AppRoute::Post { slug: slug.clone() },
+        _ => AppRoute::Profile { display_name: notification.actor_display_name.clone() },
+    }
+}
@@ -0,0 +1,96 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::show_error::ShowError;
+use crate::services::notification::{preferences, save_preferences};
+use crate::types::notification::{NotificationKind, NotificationPreferencesDto};
+use crate::types::Wrapper;
+
+/// Checkboxes for the kinds of notification the current user receives; each change is saved at once.
+#[function_component(NotificationPreferences)]
+pub fn notification_preferences() -> Html {
+    let current = use_state(NotificationPreferencesDto::default);
+    let loaded = use_async_with_options(
+        async move { preferences().await },
+        UseAsyncOptions::enable_auto(),
+    );
+    let save = {
+        let current = current.clone();
+        use_async(async move {
+            save_preferences(Wrapper { data: (*current).clone() }).await
+        })
+    };
+
+    {
+        let current = current.clone();
+        use_effect_with(
+            loaded.data.clone(),
+            move |loaded| {
+                if let Some(loaded) = loaded {
+                    current.set(loaded.data.clone());
+                }
+                || ()
+            },
+        );
+    }
+
+    // Saves after the state holding the change has been rendered, so `save` sees it.
+    let dirty = use_state(|| false);
+    {
+        let dirty = dirty.clone();
+        let save = save.clone();
+        use_effect_with(
+            (*current).clone(),
+            move |_| {
+                if *dirty {
+                    dirty.set(false);
+                    save.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    let checkbox = |kind: NotificationKind, label: &'static str| {
+        let checked = match kind {
+            NotificationKind::Follow => current.follow,
+            NotificationKind::Favorite => current.favorite,
+            NotificationKind::Comment => current.comment,
+        };
+        let onchange = {
+            let current = current.clone();
+            let dirty = dirty.clone();
+            Callback::from(move |e: Event| {
+                let input: HtmlInputElement = e.target_unchecked_into();
+                let mut preferences = (*current).clone();
+                match kind {
+                    NotificationKind::Follow => preferences.follow = input.checked(),
+                    NotificationKind::Favorite => preferences.favorite = input.checked(),
+                    NotificationKind::Comment => preferences.comment = input.checked(),
+                }
+                dirty.set(true);
+                current.set(preferences);
+            })
+        };
+        html! {
+            <label class="flex items-center space-x-2 p-3">
+                <input type="checkbox" checked={checked} onchange={onchange} disabled={loaded.loading} />
+                <span class="text-sm text-gray-800">{ label }</span>
+            </label>
+        }
+    };
+
+    html! {
+        <div class="mt-8">
+            <h2 class="text-lg font-semibold mb-2">{ "Notifications" }</h2>
+            <ShowError error={loaded.error.clone()} />
+            <ShowError error={save.error.clone()} />
+            <div class="divide-y border rounded-md">
+                { checkbox(NotificationKind::Follow, "Someone follows me") }
+                { checkbox(NotificationKind::Favorite, "Someone favorites one of my posts") }
+                { checkbox(NotificationKind::Comment, "Someone comments on one of my posts") }
+            </div>
+        </div>
+    }
+}
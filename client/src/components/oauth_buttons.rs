@@ -0,0 +1,50 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::show_error::ShowError;
+use crate::middlewares::error::Error;
+use crate::services::oauth::{providers, redirect_to_provider};
+
+/// A "Continue with …" button for every configured sign-in provider.
+#[function_component(OAuthButtons)]
+pub fn oauth_buttons() -> Html {
+    let providers = use_async_with_options(
+        async move { providers().await },
+        UseAsyncOptions::enable_auto(),
+    );
+    let error = use_state(|| None::<Error>);
+
+    let Some(providers) = providers.data.as_ref().filter(|providers| !providers.data.is_empty()) else {
+        return html! {};
+    };
+
+    html! {
+        <div class="mb-6">
+            <ShowError error={(*error).clone()} />
+            {for providers.data.iter().map(|provider| {
+                let onclick = {
+                    let error = error.clone();
+                    let name = provider.name.clone();
+                    Callback::from(move |_| {
+                        let error = error.clone();
+                        let name = name.clone();
+                        spawn_local(async move {
+                            if let Err(err) = redirect_to_provider(name, false).await {
+                                error.set(Some(err));
+                            }
+                        });
+                    })
+                };
+                html! {
+                    <button class="w-full mb-2 px-4 py-2 border rounded-md text-gray-700 hover:bg-gray-100 focus:ring focus:ring-indigo-300 focus:outline-none"
+                        type="button"
+                        onclick={onclick}>
+                        { format!("Continue with {}", provider.display_name) }
+                    </button>
+                }
+            })}
+            <p class="text-center text-sm text-gray-500">{ "or" }</p>
+        </div>
+    }
+}
@@ -2,48 +2,74 @@ use yew::prelude::*;
 
 const ITEMS_PER_PAGE: usize = 10;
 
+/// The page being shown and the cursor that fetches it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PageCursor {
+    pub page: usize,
+    pub cursor: Option<String>,
+}
+
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
-    pub total: usize,
+    pub total: Option<i64>,
     pub current_page: usize,
-    pub callback: Callback<usize>,
+    pub prev_cursor: Option<String>,
+    pub next_cursor: Option<String>,
+    pub callback: Callback<PageCursor>,
 }
 
 #[function_component(Pagination)]
 pub fn list_pagination(props: &Props) -> Html {
-    if props.total < ITEMS_PER_PAGE {
+    if props.prev_cursor.is_none() && props.next_cursor.is_none() {
         return html! {};
     }
 
-    let max_page = (props.total as f32 / ITEMS_PER_PAGE as f32).ceil() as usize;
-    let mut pages: Vec<usize> = vec![];
-    for page in 0..max_page {
-        pages.push(page);
-    }
+    let page_link = |label: &'static str, page: Option<usize>, cursor: &Option<String>| {
+        let enabled_class = "flex items-center justify-center px-3 h-8 leading-tight text-gray-500 bg-white border border-gray-300 hover:bg-gray-100 hover:text-gray-700";
+        let disabled_class = "flex items-center justify-center px-3 h-8 leading-tight text-gray-300 bg-white border border-gray-300 cursor-default";
 
-    html! {
-        <nav class="flex justify-center">
-            <ul class="inline-flex -space-x-px text-sm">
-            {for pages.iter().map(|page| {
-                let is_current = page == &props.current_page;
-                let page_item_class = if is_current {
-                    "flex items-center justify-center px-3 h-8 text-blue-600 border border-gray-300 bg-blue-50 hover:bg-blue-100 hover:text-blue-700"
-                } else {
-                    "flex items-center justify-center px-3 h-8 leading-tight text-gray-500 bg-white border border-gray-300 hover:bg-gray-100 hover:text-gray-700"
-                };
-                let page = *page;
+        match (page, cursor.clone()) {
+            (Some(page), Some(cursor)) => {
                 let callback = props.callback.clone();
                 let onclick = Callback::from(move |ev: MouseEvent| {
                     ev.prevent_default();
-                    callback.emit(page)
+                    callback.emit(PageCursor {
+                        page,
+                        cursor: Some(cursor.clone()),
+                    })
                 });
                 html! {
-                    <li
-                        onclick={onclick}>
-                        <a href="" class={page_item_class}>{page + 1}</a>
+                    <li onclick={onclick}>
+                        <a href="" class={enabled_class}>{ label }</a>
                     </li>
                 }
-            })}
+            }
+            _ => html! {
+                <li>
+                    <span class={disabled_class}>{ label }</span>
+                </li>
+            },
+        }
+    };
+
+    let page_label = match props.total {
+        Some(total) => {
+            let max_page = (total as f32 / ITEMS_PER_PAGE as f32).ceil().max(1.0) as usize;
+            format!("Page {} of {}", props.current_page + 1, max_page)
+        }
+        None => format!("Page {}", props.current_page + 1),
+    };
+
+    html! {
+        <nav class="flex justify-center">
+            <ul class="inline-flex -space-x-px text-sm">
+                { page_link("Previous", props.current_page.checked_sub(1), &props.prev_cursor) }
+                <li>
+                    <span class="flex items-center justify-center px-3 h-8 text-blue-600 border border-gray-300 bg-blue-50">
+                        { page_label }
+                    </span>
+                </li>
+                { page_link("Next", Some(props.current_page + 1), &props.next_cursor) }
             </ul>
         </nav>
     }
@@ -2,7 +2,11 @@ use yew::prelude::*;
 use yew_hooks::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::moderation_actions::ModerationActions;
+use crate::components::report_button::ReportButton;
+use crate::middlewares::context::use_user_context;
 use crate::routes::AppRoute;
+use crate::services::moderation::ModerationTarget;
 use crate::services::post::*;
 
 #[derive(Properties, Clone, PartialEq, Eq)]
@@ -13,6 +17,7 @@ pub struct Props {
 
 #[function_component(PostAction)]
 pub fn post_action(props: &Props) -> Html {
+    let user_ctx = use_user_context();
     let navigator = use_navigator().unwrap();
     let delete = {
         let post_id = props.post_id.clone();
@@ -26,6 +31,12 @@ pub fn post_action(props: &Props) -> Html {
         })
     };
 
+    // Hidden and removed posts are gone for everyone but their author.
+    let moderated = {
+        let navigator = navigator.clone();
+        Callback::from(move |_: String| navigator.push(&AppRoute::Home))
+    };
+
     use_effect_with(
         delete,
         move |delete| {
@@ -58,6 +69,19 @@ pub fn post_action(props: &Props) -> Html {
                 </button>
             </span>
         }
+    } else if user_ctx.is_moderator() {
+        html! {
+            <span class="flex items-center space-x-2">
+                <ModerationActions target={ModerationTarget::Post(props.post_id.clone())} callback={moderated} />
+                <ReportButton post_id={props.post_id.clone()} />
+            </span>
+        }
+    } else if user_ctx.is_authenticated() {
+        html! {
+            <span class="flex items-center text-gray-500">
+                <ReportButton post_id={props.post_id.clone()} />
+            </span>
+        }
     } else {
         html! {
             <span>
@@ -0,0 +1,60 @@
+use yew::prelude::*;
+
+use crate::types::post::{DiffChange, FieldDiffDto, PostDiffDto};
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub diff: PostDiffDto,
+}
+
+/// Line-by-line view of a post diff; unchanged fields are left out.
+#[function_component(PostDiff)]
+pub fn post_diff(props: &Props) -> Html {
+    let changed = props
+        .diff
+        .fields
+        .iter()
+        .filter(|field| field.lines.iter().any(|line| line.change != DiffChange::Equal))
+        .collect::<Vec<_>>();
+
+    if changed.is_empty() {
+        return html! { <p class="text-sm text-gray-500">{ "No differences" }</p> };
+    }
+
+    html! {
+        <div class="space-y-4">
+            { for changed.into_iter().map(view_field) }
+        </div>
+    }
+}
+
+fn view_field(field: &FieldDiffDto) -> Html {
+    html! {
+        <div key={field.field.clone()}>
+            <h3 class="text-sm font-semibold text-gray-700 capitalize mb-1">{ &field.field }</h3>
+            <table class="w-full text-sm font-mono border border-gray-200">
+                <tbody>
+                    { for field.lines.iter().map(|line| {
+                        let (class, marker) = match line.change {
+                            DiffChange::Equal => ("", " "),
+                            DiffChange::Insert => ("bg-green-50 text-green-800", "+"),
+                            DiffChange::Delete => ("bg-red-50 text-red-800", "-"),
+                        };
+                        html! {
+                            <tr class={class}>
+                                <td class="w-10 px-1 text-right text-gray-400 select-none">
+                                    { line.old_line.map(|n| n.to_string()).unwrap_or_default() }
+                                </td>
+                                <td class="w-10 px-1 text-right text-gray-400 select-none">
+                                    { line.new_line.map(|n| n.to_string()).unwrap_or_default() }
+                                </td>
+                                <td class="w-4 px-1 select-none">{ marker }</td>
+                                <td class="px-1 whitespace-pre-wrap break-all">{ &line.text }</td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+        </div>
+    }
+}
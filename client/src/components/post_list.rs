@@ -1,19 +1,39 @@
 use yew::prelude::*;
 use yew_hooks::prelude::*;
 
-use crate::components::pagination::Pagination;
+use crate::components::pagination::{PageCursor, Pagination};
 use crate::components::post_preview::PostPreview;
-use crate::services::post::{all, by_author, by_tag, favorited_by, feed};
+use crate::middlewares::prefetch::use_prefetched_async;
+use crate::services::post::{
+    all, all_url, by_author, by_author_url, by_status, by_status_url, by_tag, by_tag_url, favorited_by,
+    favorited_by_url, feed, feed_url,
+};
+use crate::types::post::PostStatus;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PostListFilter {
     All,
     ByAuthor(String),
+    ByStatus(String, PostStatus),
     ByTag(String),
     Feed,
     FavoritedBy(String),
 }
 
+impl PostListFilter {
+    /// Where a page of the list is fetched from.
+    pub fn url(&self, cursor: Option<&str>) -> String {
+        match self {
+            PostListFilter::All => all_url(cursor),
+            PostListFilter::ByAuthor(author) => by_author_url(author, cursor),
+            PostListFilter::ByStatus(author, status) => by_status_url(author, *status, cursor),
+            PostListFilter::ByTag(tag) => by_tag_url(tag, cursor),
+            PostListFilter::Feed => feed_url(cursor),
+            PostListFilter::FavoritedBy(author) => favorited_by_url(author, cursor),
+        }
+    }
+}
+
 #[derive(Properties, Clone, PartialEq, Eq)]
 pub struct Props {
     pub filter: PostListFilter,
@@ -21,18 +41,19 @@ pub struct Props {
 
 #[function_component(PostList)]
 pub fn post_list(props: &Props) -> Html {
-    let current_page = use_state(|| usize::default());
+    let current_page = use_state(PageCursor::default);
     let post_list = {
         let filter = props.filter.clone();
-        let current_page = current_page.clone();
+        let cursor = current_page.cursor.clone();
 
         use_async(async move {
             match filter {
-                PostListFilter::All => { all(*current_page).await }
-                PostListFilter::ByAuthor(author) => { by_author(author, *current_page).await }
-                PostListFilter::ByTag(tag) => { by_tag(tag, *current_page).await }
-                PostListFilter::FavoritedBy(author) => { favorited_by(author, *current_page).await }
-                PostListFilter::Feed => { feed().await }
+                PostListFilter::All => { all(cursor).await }
+                PostListFilter::ByAuthor(author) => { by_author(author, cursor).await }
+                PostListFilter::ByStatus(author, status) => { by_status(author, status, cursor).await }
+                PostListFilter::ByTag(tag) => { by_tag(tag, cursor).await }
+                PostListFilter::FavoritedBy(author) => { favorited_by(author, cursor).await }
+                PostListFilter::Feed => { feed(cursor).await }
             }
         })
     };
@@ -42,22 +63,17 @@ pub fn post_list(props: &Props) -> Html {
         use_effect_with(
             props.filter.clone(),
             move |_| {
-                current_page.set(0);
+                current_page.set(PageCursor::default());
                 || ()
             }
         );
     }
 
-    {
-        let post_list = post_list.clone();
-        use_effect_with(
-            (props.filter.clone(), *current_page),
-            move |_| {
-                post_list.run();
-                || ()
-            }
-        )
-    }
+    let posts = use_prefetched_async(
+        props.filter.url(current_page.cursor.as_deref()),
+        &post_list,
+        (props.filter.clone(), (*current_page).clone()),
+    );
 
     let callback = {
         let current_page = current_page.clone();
@@ -69,7 +85,7 @@ pub fn post_list(props: &Props) -> Html {
         )
     };
 
-    if let Some(resp) = &post_list.data {
+    if let Some(resp) = &posts {
         if !resp.data.posts.is_empty() {
             html! {
                 <div class="container px-4">
@@ -79,8 +95,10 @@ pub fn post_list(props: &Props) -> Html {
                         })
                     }
                     <Pagination
-                        total = { resp.data.count }
-                        current_page = { *current_page }
+                        total = { resp.data.total }
+                        current_page = { current_page.page }
+                        prev_cursor = { resp.data.prev_cursor.clone() }
+                        next_cursor = { resp.data.next_cursor.clone() }
                         callback = { callback } />
                 </div>
             }
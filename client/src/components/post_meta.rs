@@ -28,6 +28,9 @@ pub fn post_meta(props: &Props) -> Html {
                     <span class="text-gray-500 text-sm">
                         { &props.created_at }
                     </span>
+                    <Link<AppRoute> to={AppRoute::PostHistory { post_id: props.post_id.clone() }} classes="ml-2 text-gray-500 text-sm hover:underline">
+                        { "History" }
+                    </Link<AppRoute>>
                 </div>
             </div>
 
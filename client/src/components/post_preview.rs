@@ -87,7 +87,7 @@ impl Component for PostPreview {
                             </Link<AppRoute>>
                         </div>
                         <span class="text-gray-500 text-sm">
-                            { format!("{}", &post.created_at.format("%B %e, %Y")) }
+                            { format!("{}", &post.published_at.unwrap_or(post.created_at).format("%B %e, %Y")) }
                         </span>
                     </div>
                     <div class="absolute top-0 right-0">
@@ -107,14 +107,14 @@ impl Component for PostPreview {
                 </div>
                 <h1 class="text-2xl font-bold mb-2">
                     <div class="text-indigo-600 hover:underline">
-                        <Link<AppRoute> to={AppRoute::Post { post_id: post.id.clone() }}>
+                        <Link<AppRoute> to={AppRoute::Post { slug: post.slug.clone() }}>
                             { &post.title }
                         </Link<AppRoute >>
                     </div>
                 </h1>
                 <p class="text-gray-600 mb-2">{ &post.description }</p>
                 <span class="text-gray-500">
-                    <Link<AppRoute> to={AppRoute::Post { post_id: post.id.clone() }}>
+                    <Link<AppRoute> to={AppRoute::Post { slug: post.slug.clone() }}>
                         { "Read more..." }
                     </Link<AppRoute >>
                 </span>
@@ -0,0 +1,94 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::show_error::ShowError;
+use crate::routes::AppRoute;
+use crate::services::profile::{blocked_users, muted_users, unblock, unmute};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Blocked,
+    Muted,
+}
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub relation: Relation,
+}
+
+/// The users the current user has blocked or muted, each with a button to undo it.
+#[function_component(RelatedUserList)]
+pub fn related_user_list(props: &Props) -> Html {
+    let relation = props.relation;
+    let user_list = use_async_with_options(
+        async move {
+            match relation {
+                Relation::Blocked => blocked_users().await,
+                Relation::Muted => muted_users().await,
+            }
+        },
+        UseAsyncOptions::enable_auto(),
+    );
+
+    let (title, action, empty) = match relation {
+        Relation::Blocked => ("Blocked Users", "Unblock", "You haven't blocked anyone."),
+        Relation::Muted => ("Muted Users", "Unmute", "You haven't muted anyone."),
+    };
+
+    html! {
+        <div class="mt-8">
+            <h2 class="text-lg font-semibold mb-2">{ title }</h2>
+            <ShowError error={user_list.error.clone()} />
+            {
+                match &user_list.data {
+                    Some(resp) if !resp.data.users.is_empty() => html! {
+                        <ul class="divide-y border rounded-md">
+                            {for resp.data.users.iter().map(|user| {
+                                let onclick = {
+                                    let user_list = user_list.clone();
+                                    let display_name = user.display_name.clone();
+                                    Callback::from(move |_| {
+                                        let user_list = user_list.clone();
+                                        let display_name = display_name.clone();
+                                        spawn_local(async move {
+                                            let result = match relation {
+                                                Relation::Blocked => unblock(display_name).await,
+                                                Relation::Muted => unmute(display_name).await,
+                                            };
+                                            if result.is_ok() {
+                                                user_list.run();
+                                            }
+                                        });
+                                    })
+                                };
+                                html! {
+                                    <li class="p-3 flex justify-between items-center" key={user.display_name.clone()}>
+                                        <div class="flex items-center space-x-2">
+                                            <img
+                                                src={user.profile_image_url.clone()}
+                                                class="w-8 h-8 rounded-full"
+                                                alt={user.display_name.clone()} />
+                                            <Link<AppRoute> to={AppRoute::Profile { display_name: user.display_name.clone() }} classes="text-sm font-medium text-gray-800 hover:underline">
+                                                { &user.display_name }
+                                            </Link<AppRoute>>
+                                            <span class="text-xs text-gray-500">
+                                                { format!("since {}", user.since.format("%B %e, %Y")) }
+                                            </span>
+                                        </div>
+                                        <button class="text-sm text-red-600 hover:underline" onclick={onclick}>
+                                            { action }
+                                        </button>
+                                    </li>
+                                }
+                            })}
+                        </ul>
+                    },
+                    Some(_) => html! { <p class="text-sm text-gray-500">{ empty }</p> },
+                    None => html! {},
+                }
+            }
+        </div>
+    }
+}
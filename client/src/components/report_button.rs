@@ -0,0 +1,127 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::field_error::FieldError;
+use crate::components::show_error::ShowError;
+use crate::services::report::{report_comment, report_post};
+use crate::types::report::{NewReportDto, ReportCategory};
+use crate::types::Wrapper;
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub post_id: String,
+    /// Set when the report is about a comment on the post.
+    #[prop_or_default]
+    pub comment_id: Option<String>,
+}
+
+/// A "Report" link that opens a small form for flagging a post or comment to the moderators.
+#[function_component(ReportButton)]
+pub fn report_button(props: &Props) -> Html {
+    let open = use_state(|| false);
+    let report_dto = use_state(NewReportDto::default);
+    let report = {
+        let post_id = props.post_id.clone();
+        let comment_id = props.comment_id.clone();
+        let report_dto = report_dto.clone();
+        use_async(async move {
+            let req = Wrapper::<NewReportDto> { data: (*report_dto).clone() };
+            match comment_id {
+                Some(comment_id) => report_comment(post_id, comment_id, req).await,
+                None => report_post(post_id, req).await,
+            }
+        })
+    };
+
+    let on_toggle = {
+        let open = open.clone();
+        Callback::from(move |_| open.set(!*open))
+    };
+
+    let onsubmit = {
+        let report = report.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            report.run();
+        })
+    };
+
+    let oninput_details = {
+        let report_dto = report_dto.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut dto = (*report_dto).clone();
+            dto.details = input.value();
+            report_dto.set(dto);
+        })
+    };
+
+    if report.data.is_some() {
+        return html! {
+            <span class="text-xs text-gray-500">{ "Reported, thank you." }</span>
+        };
+    }
+
+    html! {
+        <span class="relative text-xs">
+            <button class="hover:text-red-600" onclick={on_toggle}>
+                { if *open { "Cancel report" } else { "Report" } }
+            </button>
+            {
+                if *open {
+                    html! {
+                        <form onsubmit={onsubmit} class="absolute right-0 z-10 mt-2 w-72 p-3 bg-white text-gray-800 border rounded-md shadow-lg">
+                            <ShowError error={report.error.clone()} />
+                            <p class="mb-2 font-semibold">{ "What's wrong with it?" }</p>
+                            <div class="flex flex-wrap gap-1 mb-2">
+                                {
+                                    for ReportCategory::ALL.iter().map(|category| {
+                                        let selected = report_dto.category == *category;
+                                        let onclick = {
+                                            let report_dto = report_dto.clone();
+                                            let category = *category;
+                                            Callback::from(move |e: MouseEvent| {
+                                                e.prevent_default();
+                                                let mut dto = (*report_dto).clone();
+                                                dto.category = category;
+                                                report_dto.set(dto);
+                                            })
+                                        };
+                                        html! {
+                                            <button
+                                                type="button"
+                                                class={classes!(
+                                                    "px-2", "py-1", "rounded-full", "border",
+                                                    if selected { "bg-red-600 text-white" } else { "hover:bg-gray-100" },
+                                                )}
+                                                onclick={onclick}>
+                                                { category.label() }
+                                            </button>
+                                        }
+                                    })
+                                }
+                            </div>
+                            <textarea
+                                class="w-full p-2 border rounded-md resize-none focus:outline-none focus:border-indigo-500"
+                                rows="3"
+                                placeholder="Anything the moderators should know (optional)"
+                                value={report_dto.details.clone()}
+                                oninput={oninput_details}>
+                            </textarea>
+                            <FieldError error={report.error.clone()} field="details" />
+                            <button
+                                class="mt-2 w-full px-2 py-1 bg-red-600 text-white rounded-md hover:bg-red-700 disabled:opacity-50"
+                                type="submit"
+                                disabled={report.loading}>
+                                { "Send report" }
+                            </button>
+                        </form>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </span>
+    }
+}
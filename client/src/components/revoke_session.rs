@@ -0,0 +1,44 @@
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::services::user::revoke_session;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub session_id: String,
+    pub callback: Callback<String>,
+}
+
+#[function_component(RevokeSession)]
+pub fn revoke_session_button(props: &Props) -> Html {
+    let revoke = {
+        let session_id = props.session_id.clone();
+        use_async(async move { revoke_session(session_id).await })
+    };
+
+    let onclick = {
+        let revoke = revoke.clone();
+        Callback::from(move |_| {
+            revoke.run();
+        })
+    };
+
+    {
+        let callback = props.callback.clone();
+        use_effect_with(
+            revoke.clone(),
+            move |revoke| {
+                if let Some(revoke) = &revoke.data {
+                    callback.emit(revoke.data.clone());
+                }
+                || ()
+            },
+        )
+    }
+
+    html! {
+        <button onclick={onclick} disabled={revoke.loading} class="text-sm text-red-500 hover:underline">
+            { "Revoke" }
+        </button>
+    }
+}
@@ -0,0 +1,55 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::routes::AppRoute;
+use crate::types::search::SearchQuery;
+
+#[function_component(SearchBox)]
+pub fn search_box() -> Html {
+    let navigator = use_navigator().unwrap();
+    let location = use_location();
+    let query = use_state(|| {
+        location
+            .and_then(|location| location.query::<SearchQuery>().ok())
+            .map(|query| query.q)
+            .unwrap_or_default()
+    });
+
+    let oninput = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+        })
+    };
+
+    let onsubmit = {
+        let query = query.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if query.trim().is_empty() {
+                return;
+            }
+            let search_query = SearchQuery {
+                q: query.trim().to_string(),
+                ..SearchQuery::default()
+            };
+            if let Err(err) = navigator.push_with_query(&AppRoute::Search, &search_query) {
+                log::error!("Failed to open search results: {:?}", err);
+            }
+        })
+    };
+
+    html! {
+        <form onsubmit={onsubmit} class="flex-grow max-w-md mx-6">
+            <input
+                class="w-full p-2 border rounded-md focus:ring focus:ring-indigo-300 focus:outline-none"
+                type="search"
+                placeholder="Search posts and comments"
+                value={(*query).clone()}
+                oninput={oninput}
+            />
+        </form>
+    }
+}
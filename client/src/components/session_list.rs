@@ -0,0 +1,65 @@
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::revoke_session::RevokeSession;
+use crate::components::show_error::ShowError;
+use crate::services::user::sessions;
+
+#[function_component(SessionList)]
+pub fn session_list() -> Html {
+    let session_list = use_async_with_options(
+        async move { sessions().await },
+        UseAsyncOptions::enable_auto(),
+    );
+
+    let callback_revoked = {
+        let session_list = session_list.clone();
+        Callback::from(move |_| {
+            session_list.run();
+        })
+    };
+
+    html! {
+        <div class="mt-8">
+            <h2 class="text-lg font-semibold mb-2">{ "Active Sessions" }</h2>
+            <ShowError error={session_list.error.clone()} />
+            {
+                if let Some(session_list) = &session_list.data {
+                    html! {
+                        <ul class="divide-y border rounded-md">
+                            {for session_list.data.sessions.iter().map(|session| {
+                                html! {
+                                    <li class="p-3 flex justify-between items-center">
+                                        <div>
+                                            <p class="text-sm font-medium text-gray-800">
+                                                { session.user_agent.clone().unwrap_or_else(|| "Unknown device".to_string()) }
+                                            </p>
+                                            <p class="text-xs text-gray-500">
+                                                { session.ip_address.clone().unwrap_or_default() }
+                                                { format!(" · signed in {}", session.created_at.format("%B %e, %Y %H:%M")) }
+                                                { format!(" · last seen {}", session.last_seen_at.format("%B %e, %Y %H:%M")) }
+                                            </p>
+                                        </div>
+                                        {
+                                            if session.current {
+                                                html! { <span class="text-sm text-green-600">{ "This device" }</span> }
+                                            } else {
+                                                html! {
+                                                    <RevokeSession
+                                                        session_id={session.id.clone()}
+                                                        callback={callback_revoked.clone()} />
+                                                }
+                                            }
+                                        }
+                                    </li>
+                                }
+                            })}
+                        </ul>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
@@ -18,19 +18,11 @@ pub fn show_error(props: &Props) -> Html {
                             Error::UnprocessableEntity(error_info) => {
                                 html! {
                                     <>
+                                        { &error_info.message }
                                         {
-                                            for error_info.errors.iter().map(|(key, value)| {
+                                            for error_info.errors.get("request").into_iter().flatten().map(|err| {
                                                 html! {
-                                                    <>
-                                                        { key }
-                                                        {
-                                                            for value.iter().map(|err| {
-                                                                html! {
-                                                                    <> { ": " } { err } </>
-                                                                }
-                                                            })
-                                                        }
-                                                    </>
+                                                    <> { ": " } { err } </>
                                                 }
                                             })
                                         }
@@ -1,6 +1,7 @@
 use yew::prelude::*;
 use yew_hooks::prelude::*;
-use crate::services::tag::get;
+use crate::middlewares::prefetch::use_prefetched_async;
+use crate::services::tag::{get, get_url};
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
@@ -9,12 +10,10 @@ pub struct Props {
 
 #[function_component(Tags)]
 pub fn tags(props: &Props) -> Html {
-    let tags = use_async_with_options(
-        async move { get().await },
-        UseAsyncOptions::enable_auto(),
-    );
+    let tags = use_async(async move { get().await });
+    let tags = use_prefetched_async(get_url(), &tags, ());
 
-    if let Some(resp) = &tags.data {
+    if let Some(resp) = &tags {
         html! {
             <div class="container mx-auto flex flex-wrap">
                 { for resp.data.tags.iter().map(|tag| {
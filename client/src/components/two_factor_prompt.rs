@@ -0,0 +1,95 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::field_error::FieldError;
+use crate::components::show_error::ShowError;
+use crate::services::two_factor::login_two_factor;
+use crate::types::two_factor::TwoFactorLoginDto;
+use crate::types::user::UserDto;
+use crate::types::Wrapper;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub pending_token: String,
+    pub on_signed_in: Callback<UserDto>,
+}
+
+/// The second sign-in step: asks for a code from the user's authenticator app or a recovery code.
+#[function_component(TwoFactorPrompt)]
+pub fn two_factor_prompt(props: &Props) -> Html {
+    let code = use_state(String::default);
+
+    let submit = {
+        let code = code.clone();
+        let pending_token = props.pending_token.clone();
+        use_async(async move {
+            login_two_factor(Wrapper {
+                data: TwoFactorLoginDto {
+                    pending_token,
+                    code: (*code).clone(),
+                },
+            })
+                .await
+        })
+    };
+
+    {
+        let on_signed_in = props.on_signed_in.clone();
+        use_effect_with(
+            submit.data.clone(),
+            move |user| {
+                if let Some(user) = user {
+                    on_signed_in.emit(user.data.clone());
+                }
+                || ()
+            },
+        );
+    }
+
+    let onsubmit = {
+        let submit = submit.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            submit.run();
+        })
+    };
+
+    let oninput = {
+        let code = code.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            code.set(input.value());
+        })
+    };
+
+    html! {
+        <form onsubmit={onsubmit}>
+            <ShowError error={submit.error.clone()} />
+            <div class="mb-4">
+                <label for="code" class="block text-sm font-medium text-gray-700">
+                    { "Authentication code" }
+                </label>
+                <input
+                    class="mt-1 p-2 border rounded w-full"
+                    type="text"
+                    autocomplete="one-time-code"
+                    placeholder="123456"
+                    value={(*code).clone()}
+                    oninput={oninput}
+                    />
+                <FieldError error={submit.error.clone()} field="code" />
+                <p class="mt-1 text-sm text-gray-500">
+                    { "Enter the code from your authenticator app. If you've lost it, enter one of your recovery codes instead." }
+                </p>
+            </div>
+            <div class="flex justify-center">
+                <button type="submit"
+                    class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-300 focus:outline-none"
+                    disabled={submit.loading}>
+                    { "Verify" }
+                </button>
+            </div>
+        </form>
+    }
+}
@@ -0,0 +1,218 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::field_error::FieldError;
+use crate::components::show_error::ShowError;
+use crate::middlewares::error::Error;
+use crate::services::two_factor::{confirm, disable, enroll, regenerate_recovery_codes, status};
+use crate::types::two_factor::{TwoFactorCodeDto, TwoFactorEnrollmentDto};
+use crate::types::Wrapper;
+
+/// Turning two-factor authentication on and off, and replacing recovery codes.
+#[function_component(TwoFactorSettings)]
+pub fn two_factor_settings() -> Html {
+    let status = use_async_with_options(
+        async move { status().await },
+        UseAsyncOptions::enable_auto(),
+    );
+    let code = use_state(String::default);
+    let recovery_codes = use_state(|| None::<Vec<String>>);
+
+    let enrollment = use_async(async move { enroll().await });
+    // Kept apart from `enrollment` so it can be cleared once confirmed.
+    let pending_enrollment = use_state(|| None::<TwoFactorEnrollmentDto>);
+    let code_dto = || Wrapper { data: TwoFactorCodeDto { code: (*code).clone() } };
+    let confirmation = {
+        let dto = code_dto();
+        use_async(async move { confirm(dto).await })
+    };
+    let disabling = {
+        let dto = code_dto();
+        use_async(async move { disable(dto).await })
+    };
+    let regeneration = {
+        let dto = code_dto();
+        use_async(async move { regenerate_recovery_codes(dto).await })
+    };
+
+    {
+        let pending_enrollment = pending_enrollment.clone();
+        use_effect_with(
+            enrollment.data.clone(),
+            move |enrollment| {
+                pending_enrollment.set(enrollment.as_ref().map(|enrollment| enrollment.data.clone()));
+                || ()
+            },
+        );
+    }
+
+    {
+        let status = status.clone();
+        let code = code.clone();
+        let recovery_codes = recovery_codes.clone();
+        let pending_enrollment = pending_enrollment.clone();
+        use_effect_with(
+            (confirmation.data.clone(), regeneration.data.clone()),
+            move |(confirmed, regenerated)| {
+                if let Some(codes) = confirmed.as_ref().or(regenerated.as_ref()) {
+                    recovery_codes.set(Some(codes.data.recovery_codes.clone()));
+                    pending_enrollment.set(None);
+                    code.set(String::default());
+                    status.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    {
+        let status = status.clone();
+        let code = code.clone();
+        use_effect_with(
+            disabling.data.clone(),
+            move |disabled| {
+                if disabled.is_some() {
+                    code.set(String::default());
+                    status.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    let oninput = {
+        let code = code.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            code.set(input.value());
+        })
+    };
+
+    let on_enroll = {
+        let enrollment = enrollment.clone();
+        Callback::from(move |_| enrollment.run())
+    };
+    let on_confirm = {
+        let confirmation = confirmation.clone();
+        Callback::from(move |_| confirmation.run())
+    };
+    let on_disable = {
+        let disabling = disabling.clone();
+        Callback::from(move |_| disabling.run())
+    };
+    let on_regenerate = {
+        let regeneration = regeneration.clone();
+        Callback::from(move |_| regeneration.run())
+    };
+    let on_recovery_codes_saved = {
+        let recovery_codes = recovery_codes.clone();
+        Callback::from(move |_| recovery_codes.set(None))
+    };
+
+    let code_input = |error: Option<Error>| html! {
+        <div class="mb-2">
+            <input
+                class="mt-1 p-2 border rounded w-full"
+                type="text"
+                autocomplete="one-time-code"
+                placeholder="Authentication code"
+                value={(*code).clone()}
+                oninput={oninput.clone()}
+                />
+            <FieldError error={error} field="code" />
+        </div>
+    };
+
+    html! {
+        <div class="mt-8">
+            <h2 class="text-lg font-semibold mb-2">{ "Two-Factor Authentication" }</h2>
+            <ShowError error={status.error.clone()} />
+            {
+                if let Some(codes) = &*recovery_codes {
+                    html! {
+                        <div class="bg-yellow-100 border border-yellow-400 text-yellow-800 px-4 py-3 rounded mb-4 text-sm">
+                            <p class="mb-2">
+                                { "Keep these recovery codes somewhere safe. Each one signs you in once if you lose your authenticator app, and they won't be shown again." }
+                            </p>
+                            <ul class="font-mono grid grid-cols-2 gap-1 mb-2">
+                                {for codes.iter().map(|code| html! { <li>{ code }</li> })}
+                            </ul>
+                            <button class="text-blue-500 hover:underline" onclick={on_recovery_codes_saved}>
+                                { "I've saved them" }
+                            </button>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                match &status.data {
+                    Some(current) if current.data.enabled => html! {
+                        <div class="border rounded-md p-3">
+                            <p class="text-sm text-gray-800 mb-2">
+                                { format!(
+                                    "Two-factor authentication is on. You have {} unused recovery code{}.",
+                                    current.data.recovery_codes_remaining,
+                                    if current.data.recovery_codes_remaining == 1 { "" } else { "s" },
+                                ) }
+                            </p>
+                            <ShowError error={disabling.error.clone().or(regeneration.error.clone())} />
+                            { code_input(disabling.error.clone().or(regeneration.error.clone())) }
+                            <div class="flex space-x-4 text-sm">
+                                <button class="text-red-600 hover:underline"
+                                    onclick={on_disable}
+                                    disabled={disabling.loading || code.is_empty()}>
+                                    { "Turn off" }
+                                </button>
+                                <button class="text-blue-500 hover:underline"
+                                    onclick={on_regenerate}
+                                    disabled={regeneration.loading || code.is_empty()}>
+                                    { "Get new recovery codes" }
+                                </button>
+                            </div>
+                        </div>
+                    },
+                    Some(_) => match &*pending_enrollment {
+                        Some(enrollment) => html! {
+                            <div class="border rounded-md p-3">
+                                <p class="text-sm text-gray-800 mb-2">
+                                    { "Scan this QR code with your authenticator app, then enter the code it shows." }
+                                </p>
+                                <div class="flex justify-center mb-2">
+                                    { Html::from_html_unchecked(enrollment.qr_code_svg.clone().into()) }
+                                </div>
+                                <p class="text-xs text-gray-500 mb-2 break-all">
+                                    { "Can't scan it? Enter this key instead: " }
+                                    <span class="font-mono">{ &enrollment.secret }</span>
+                                </p>
+                                <ShowError error={confirmation.error.clone()} />
+                                { code_input(confirmation.error.clone()) }
+                                <button class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-300 focus:outline-none"
+                                    onclick={on_confirm}
+                                    disabled={confirmation.loading || code.is_empty()}>
+                                    { "Turn on" }
+                                </button>
+                            </div>
+                        },
+                        None => html! {
+                            <div class="border rounded-md p-3">
+                                <p class="text-sm text-gray-800 mb-2">
+                                    { "Protect your account with a code from an authenticator app as well as your password." }
+                                </p>
+                                <ShowError error={enrollment.error.clone()} />
+                                <button class="text-blue-500 hover:underline text-sm"
+                                    onclick={on_enroll}
+                                    disabled={enrollment.loading}>
+                                    { "Set up two-factor authentication" }
+                                </button>
+                            </div>
+                        },
+                    },
+                    None => html! {},
+                }
+            }
+        </div>
+    }
+}
@@ -1,6 +1,28 @@
-use client::routes::App;
+use client::middlewares::prefetch::embedded_responses;
+use client::routes::{App, AppProps};
+
+/// Where `index.html` has the app rendered.
+const ROOT_ELEMENT_ID: &str = "app";
 
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
-    yew::Renderer::<App>::new().render();
+
+    let root = gloo::utils::document()
+        .get_element_by_id(ROOT_ELEMENT_ID)
+        .expect("index.html has no element for the app");
+
+    // Pages the server rendered come with the responses they were rendered from; anything else,
+    // including a page the server failed to render, is rendered here from scratch.
+    match embedded_responses() {
+        Some(prefetched) => {
+            let props = AppProps {
+                prefetched,
+                ..Default::default()
+            };
+            yew::Renderer::<App>::with_root_and_props(root, props).hydrate();
+        }
+        None => {
+            yew::Renderer::<App>::with_root(root).render();
+        }
+    }
 }
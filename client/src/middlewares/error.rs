@@ -5,6 +5,9 @@ use thiserror::Error;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct ErrorInfo {
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
     pub errors: HashMap<String, Vec<String>>,
 }
 
@@ -16,6 +19,10 @@ pub enum Error {
     Forbidden,
     #[error("Not Found")]
     NotFound,
+    #[error("Conflict: {}", .0.message)]
+    Conflict(ErrorInfo),
+    #[error("Too Many Requests: {}", .0.message)]
+    TooManyRequests(ErrorInfo),
     #[error("Unprocessable Entity: {0:?}")]
     UnprocessableEntity(ErrorInfo),
     #[error("Internal Server Error")]
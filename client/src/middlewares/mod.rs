@@ -1,4 +1,5 @@
 pub mod context;
 pub mod error;
 pub mod pagination;
+pub mod prefetch;
 pub mod request;
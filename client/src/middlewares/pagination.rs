@@ -1,4 +1,8 @@
-pub fn limit(count: usize, page: usize) -> String {
-    let offset = if page > 0 { page * count } else { 0 };
-    format!("limit={}&offset={}", count, offset)
+/// Query string for one page of a keyset-paginated list. `cursor` is the opaque token from a
+/// previous response; `None` requests the first page.
+pub fn limit(count: usize, cursor: Option<&str>) -> String {
+    match cursor {
+        Some(cursor) => format!("limit={}&total=true&cursor={}", count, cursor),
+        None => format!("limit={}&total=true", count),
+    }
 }
@@ -0,0 +1,82 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::middlewares::error::Error;
+use crate::middlewares::request::get_token;
+
+/// Id of the `<script>` a server-rendered page carries its prefetched responses in.
+pub const STATE_ELEMENT_ID: &str = "prefetched-state";
+
+/// API responses fetched by the server while rendering a page, by the URL they were fetched from,
+/// relative to `API_ROOT`.
+pub type PrefetchedResponses = HashMap<String, Value>;
+
+/// Provided by `App`; each response goes to the first component asking for it.
+#[derive(Clone)]
+pub struct PrefetchedContext(Rc<RefCell<PrefetchedResponses>>);
+
+impl PrefetchedContext {
+    pub fn new(responses: PrefetchedResponses) -> Self {
+        Self(Rc::new(RefCell::new(responses)))
+    }
+
+    fn take<T: DeserializeOwned>(&self, url: &str) -> Option<T> {
+        let value = self.0.borrow_mut().remove(url)?;
+        serde_json::from_value(value).ok()
+    }
+}
+
+impl PartialEq for PrefetchedContext {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The responses embedded in a server-rendered page, or `None` when the page was not rendered by
+/// the server and has to be rendered from scratch.
+pub fn embedded_responses() -> Option<PrefetchedResponses> {
+    let element = gloo::utils::document().get_element_by_id(STATE_ELEMENT_ID)?;
+    serde_json::from_str(&element.text_content()?).ok()
+}
+
+/// Runs `handle` whenever `deps` change, the way pages load their data, except when the component
+/// mounts with a response the server prefetched from `url`: that is shown as is, and only fetched
+/// again when someone is signed in, as the server fetched it signed out.
+///
+/// Returns the data to show, which is `handle`'s own as soon as it has some.
+#[hook]
+pub fn use_prefetched_async<T, D>(url: String, handle: &UseAsyncHandle<T, Error>, deps: D) -> Option<T>
+where
+    T: Clone + DeserializeOwned + 'static,
+    D: PartialEq + 'static,
+{
+    let context = use_context::<PrefetchedContext>();
+    let prefetched = use_state(move || context.and_then(|context| context.take::<T>(&url)));
+    let mounted = use_mut_ref(|| false);
+
+    {
+        let handle = handle.clone();
+        let prefetched = prefetched.clone();
+        use_effect_with(
+            deps,
+            move |_| {
+                let mounting = !mounted.replace(true);
+                if !mounting {
+                    prefetched.set(None);
+                }
+                if !(mounting && prefetched.is_some()) || get_token().is_some() {
+                    handle.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    handle.data.clone().or_else(|| (*prefetched).clone())
+}
@@ -1,12 +1,15 @@
 use std::sync::RwLock;
 
+use futures::lock::Mutex;
 use gloo::storage::{LocalStorage, Storage};
 use lazy_static::lazy_static;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::middlewares::error::{Error, ErrorInfo};
+use crate::types::user::UserDto;
+use crate::types::Wrapper;
 
-const API_ROOT: &str = "http://localhost:8081/api";
+pub const API_ROOT: &str = "http://localhost:8081/api";
 const TOKEN_KEY: &str = "access_token";
 
 lazy_static! {
@@ -17,6 +20,7 @@ lazy_static! {
             RwLock::new(None)
         }
     };
+    static ref REFRESH_LOCK: Mutex<()> = Mutex::new(());
 }
 
 pub fn get_token() -> Option<String> {
@@ -34,27 +38,122 @@ pub fn set_token(token: Option<String>) {
     *token_lock = token.clone();
 }
 
-async fn request<B, T>(method: reqwest::Method, url: String, body: B) -> Result<T, Error>
+/// Exchanges the refresh token for a new token pair. The refresh token never reaches the client
+/// app: it lives in an httpOnly cookie, which the browser sends along with the request.
+///
+/// Requests failing at the same time share a single refresh: whoever gets the lock second sees
+/// that the access token already changed and simply retries with it. Returns whether the caller
+/// should retry its request.
+async fn refresh_access_token(stale_token: Option<String>) -> bool {
+    let _guard = REFRESH_LOCK.lock().await;
+
+    let current_token = get_token();
+    if current_token != stale_token {
+        return current_token.is_some();
+    }
+
+    let response = with_credentials(reqwest::Client::new().post(format!("{}/user/refresh", API_ROOT)))
+        .send()
+        .await;
+
+    let refreshed = match response {
+        Ok(data) if data.status().is_success() => data.json::<Wrapper<UserDto>>().await.ok(),
+        _ => None,
+    };
+
+    if let Some(refreshed) = refreshed {
+        set_token(Some(refreshed.data.access_token));
+        true
+    } else {
+        set_token(None);
+        false
+    }
+}
+
+/// Lets the API set and read its cookies, the refresh token among them, across origins.
+fn with_credentials(builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    #[cfg(target_arch = "wasm32")]
+    let builder = builder.fetch_credentials_include();
+    builder
+}
+
+async fn send<B>(
+    method: reqwest::Method,
+    url: &str,
+    body: &B,
+    token: Option<&String>,
+) -> reqwest::Result<reqwest::Response>
 where
     B: Serialize + std::fmt::Debug,
-    T: DeserializeOwned + 'static + std::fmt::Debug,
 {
     let allow_body = method == reqwest::Method::POST || method == reqwest::Method::PUT;
-    let url = format!("{}{}", API_ROOT, url);
-    let mut builder = reqwest::Client::new()
-        .request(method, url)
-        .header("Content-Type", "application/json");
+    let mut builder = with_credentials(
+        reqwest::Client::new()
+            .request(method, url)
+            .header("Content-Type", "application/json"),
+    );
 
-    if let Some(token) = get_token() {
+    if let Some(token) = token {
         builder = builder.bearer_auth(token);
     }
 
     if allow_body {
-        builder = builder.json(&body);
+        builder = builder.json(body);
+    }
+
+    builder.send().await
+}
+
+async fn request<B, T>(method: reqwest::Method, url: String, body: B) -> Result<T, Error>
+where
+    B: Serialize + std::fmt::Debug,
+    T: DeserializeOwned + 'static + std::fmt::Debug,
+{
+    let url = format!("{}{}", API_ROOT, url);
+    let token = get_token();
+    let mut response = send(method.clone(), &url, &body, token.as_ref()).await;
+
+    let unauthorized = matches!(&response, Ok(data) if data.status() == reqwest::StatusCode::UNAUTHORIZED);
+    if unauthorized && token.is_some() && refresh_access_token(token).await {
+        response = send(method, &url, &body, get_token().as_ref()).await;
+    }
+
+    into_result(response).await
+}
+
+/// Uploads a file as the `file` field of a multipart form.
+pub async fn request_upload<T>(url: String, file_name: String, bytes: Vec<u8>) -> Result<T, Error>
+where
+    T: DeserializeOwned + 'static + std::fmt::Debug,
+{
+    let url = format!("{}{}", API_ROOT, url);
+    let send = |token: Option<String>| {
+        let form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(bytes.clone()).file_name(file_name.clone()),
+        );
+        let mut builder = with_credentials(reqwest::Client::new().post(&url).multipart(form));
+        if let Some(token) = token {
+            builder = builder.bearer_auth(token);
+        }
+        builder.send()
+    };
+
+    let token = get_token();
+    let mut response = send(token.clone()).await;
+
+    let unauthorized = matches!(&response, Ok(data) if data.status() == reqwest::StatusCode::UNAUTHORIZED);
+    if unauthorized && token.is_some() && refresh_access_token(token).await {
+        response = send(get_token()).await;
     }
 
-    let response = builder.send().await;
+    into_result(response).await
+}
 
+async fn into_result<T>(response: reqwest::Result<reqwest::Response>) -> Result<T, Error>
+where
+    T: DeserializeOwned + 'static + std::fmt::Debug,
+{
     if let Ok(data) = response {
         if data.status().is_success() {
             let data: Result<T, _> = data.json::<T>().await;
@@ -70,6 +169,22 @@ where
                 403 => Err(Error::Forbidden),
                 404 => Err(Error::NotFound),
                 500 => Err(Error::InternalServerError),
+                409 => {
+                    let data: Result<ErrorInfo, _> = data.json::<ErrorInfo>().await;
+                    if let Ok(data) = data {
+                        Err(Error::Conflict(data))
+                    } else {
+                        Err(Error::DeserializationError)
+                    }
+                }
+                429 => {
+                    let data: Result<ErrorInfo, _> = data.json::<ErrorInfo>().await;
+                    if let Ok(data) = data {
+                        Err(Error::TooManyRequests(data))
+                    } else {
+                        Err(Error::DeserializationError)
+                    }
+                }
                 422 => {
                     let data: Result<ErrorInfo, _> = data.json::<ErrorInfo>().await;
                     if let Ok(data) = data {
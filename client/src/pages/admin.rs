@@ -0,0 +1,246 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+
+use crate::components::field_error::FieldError;
+use crate::components::pagination::{PageCursor, Pagination};
+use crate::components::show_error::ShowError;
+use crate::middlewares::context::use_user_context;
+use crate::services::moderation::{audit_log, change_role};
+use crate::types::moderation::{AuditLogEntryDto, RoleUpdateDto};
+use crate::types::user::UserRole;
+use crate::types::Wrapper;
+
+const ROLES: [(UserRole, &str); 3] = [
+    (UserRole::User, "User"),
+    (UserRole::Moderator, "Moderator"),
+    (UserRole::Admin, "Admin"),
+];
+
+/// Role management and the audit log; only admins can load either.
+#[function_component(Admin)]
+pub fn admin() -> Html {
+    let user_ctx = use_user_context();
+    let display_name = use_state(String::default);
+    let role = use_state(|| UserRole::Moderator);
+    let reason = use_state(String::default);
+    let current_page = use_state(PageCursor::default);
+
+    let update = {
+        let display_name = display_name.clone();
+        let role = role.clone();
+        let reason = reason.clone();
+        use_async(async move {
+            let request = Wrapper {
+                data: RoleUpdateDto {
+                    role: *role,
+                    reason: (*reason).clone(),
+                },
+            };
+            change_role((*display_name).clone(), request).await
+        })
+    };
+
+    let log = {
+        let cursor = current_page.cursor.clone();
+        use_async(async move { audit_log(cursor).await })
+    };
+
+    {
+        let log = log.clone();
+        let is_admin = user_ctx.is_admin();
+        use_effect_with(
+            ((*current_page).clone(), update.data.clone(), is_admin),
+            move |(_, _, is_admin)| {
+                if *is_admin {
+                    log.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    {
+        let reason = reason.clone();
+        use_effect_with(
+            update.data.clone(),
+            move |updated| {
+                if updated.is_some() {
+                    reason.set(String::default());
+                }
+                || ()
+            },
+        );
+    }
+
+    let onsubmit = {
+        let update = update.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            update.run();
+        })
+    };
+
+    let oninput_display_name = {
+        let display_name = display_name.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            display_name.set(input.value());
+        })
+    };
+
+    let oninput_reason = {
+        let reason = reason.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            reason.set(input.value());
+        })
+    };
+
+    let callback = {
+        let current_page = current_page.clone();
+        use_callback(
+            (),
+            move |page, _| {
+                current_page.set(page);
+            },
+        )
+    };
+
+    if !user_ctx.is_admin() {
+        return html! {
+            <div class="text-center mt-12 text-gray-500">{ "Only administrators can see this page." }</div>
+        };
+    }
+
+    html! {
+        <div class="max-w-4xl mx-auto mt-12 mb-12 px-4">
+            <h1 class="text-center text-xl font-semibold">{ "Administration" }</h1>
+            <ShowError error={update.error.clone()} />
+            <form onsubmit={onsubmit} class="max-w-md mx-auto mt-4">
+                <div class="mb-4">
+                    <label for="display_name" class="block text-sm font-medium text-gray-700">
+                        { "Display name" }
+                    </label>
+                    <input
+                        class="mt-1 p-2 block w-full border rounded-md shadow-sm focus:ring focus:ring-indigo-300 focus:outline-none"
+                        type="text"
+                        id="display_name"
+                        value={(*display_name).clone()}
+                        oninput={oninput_display_name} />
+                </div>
+                <div class="mb-4 flex space-x-2">
+                    {
+                        for ROLES.iter().map(|(value, label)| {
+                            let selected = *role == *value;
+                            let onclick = {
+                                let role = role.clone();
+                                let value = *value;
+                                Callback::from(move |e: MouseEvent| {
+                                    e.prevent_default();
+                                    role.set(value);
+                                })
+                            };
+                            html! {
+                                <button
+                                    type="button"
+                                    class={classes!(
+                                        "px-3", "py-1", "rounded-full", "text-sm", "border",
+                                        if selected { "bg-indigo-600 text-white" } else { "text-gray-700 hover:bg-gray-100" },
+                                    )}
+                                    onclick={onclick}>
+                                    { *label }
+                                </button>
+                            }
+                        })
+                    }
+                </div>
+                <div class="mb-4">
+                    <label for="reason" class="block text-sm font-medium text-gray-700">
+                        { "Reason" }
+                    </label>
+                    <input
+                        class="mt-1 p-2 block w-full border rounded-md shadow-sm focus:ring focus:ring-indigo-300 focus:outline-none"
+                        type="text"
+                        id="reason"
+                        value={(*reason).clone()}
+                        oninput={oninput_reason} />
+                    <FieldError error={update.error.clone()} field="reason" />
+                </div>
+                <div class="flex justify-center">
+                    <button class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-300 focus:outline-none"
+                        type="submit"
+                        disabled={update.loading}>
+                        { "Change role" }
+                    </button>
+                </div>
+                {
+                    match &update.data {
+                        Some(updated) => html! {
+                            <p class="mt-2 text-center text-sm text-green-600">
+                                { format!("{} is now {:?}.", updated.data.display_name, updated.data.role) }
+                            </p>
+                        },
+                        None => html! {},
+                    }
+                }
+            </form>
+
+            <h2 class="mt-12 mb-4 text-lg font-semibold">{ "Audit log" }</h2>
+            <ShowError error={log.error.clone()} />
+            {
+                match &log.data {
+                    Some(resp) if !resp.data.entries.is_empty() => html! {
+                        <>
+                            <table class="w-full text-sm text-left mb-4">
+                                <thead class="text-gray-500 border-b">
+                                    <tr>
+                                        <th class="py-2">{ "When" }</th>
+                                        <th>{ "Who" }</th>
+                                        <th>{ "Action" }</th>
+                                        <th>{ "Target" }</th>
+                                        <th>{ "Reason" }</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    { for resp.data.entries.iter().map(view_entry) }
+                                </tbody>
+                            </table>
+                            <Pagination
+                                total={None::<i64>}
+                                current_page={current_page.page}
+                                prev_cursor={resp.data.prev_cursor.clone()}
+                                next_cursor={resp.data.next_cursor.clone()}
+                                callback={callback} />
+                        </>
+                    },
+                    Some(_) => html! { <div class="text-center mt-4">{ "Nothing has been moderated yet" }</div> },
+                    None => html! { <div class="text-center mt-4">{ "Loading" }</div> },
+                }
+            }
+        </div>
+    }
+}
+
+fn view_entry(entry: &AuditLogEntryDto) -> Html {
+    html! {
+        <tr class="border-b align-top" key={entry.id.clone()}>
+            <td class="py-2 pr-2 whitespace-nowrap">{ entry.created_at.format("%Y-%m-%d %H:%M").to_string() }</td>
+            <td class="pr-2">{ view_actor(entry) }</td>
+            <td class="pr-2 font-mono">{ &entry.action }</td>
+            <td class="pr-2 font-mono text-xs" title={entry.details.to_string()}>
+                { format!("{} {}", entry.target_type, entry.target_id) }
+            </td>
+            <td>{ &entry.reason }</td>
+        </tr>
+    }
+}
+
+/// Automatic actions have no actor; otherwise a missing actor has deleted their account.
+fn view_actor(entry: &AuditLogEntryDto) -> String {
+    match &entry.actor {
+        Some(actor) => actor.clone(),
+        None if entry.action.starts_with("auto_") => "(automatic)".to_string(),
+        None => "(deleted user)".to_string(),
+    }
+}
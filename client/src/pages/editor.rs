@@ -1,12 +1,19 @@
-use web_sys::HtmlInputElement;
+use chrono::{DateTime, Utc};
+use web_sys::js_sys::Date;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{ClipboardEvent, EventInit, FileList, HtmlInputElement, HtmlTextAreaElement};
+use yew::platform::spawn_local;
 use yew::prelude::*;
 use yew_hooks::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::field_error::FieldError;
 use crate::components::show_error::ShowError;
 use crate::routes::AppRoute;
+use crate::services::media::upload;
 use crate::services::post::{create, get, update};
-use crate::types::post::PostUpdateDto;
+use crate::types::media::MediaDto;
+use crate::types::post::{PostStatus, PostUpdateDto};
 use crate::types::Wrapper;
 
 #[derive(Properties, Clone, PartialEq, Eq)]
@@ -20,6 +27,10 @@ pub fn editor(props: &Props) -> Html {
     let error = use_state(|| None);
     let update_dto = use_state(PostUpdateDto::default);
     let tag_input = use_state(String::default);
+    let schedule_input = use_state(String::default);
+    let body_ref = use_node_ref();
+    let uploading = use_counter(0);
+    let upload_error = use_state(|| None);
 
     let post_get = {
         let slug = props.post_id.clone();
@@ -55,6 +66,7 @@ pub fn editor(props: &Props) -> Html {
 
     {
         let update_dto = update_dto.clone();
+        let schedule_input = schedule_input.clone();
         let error = error.clone();
         use_effect_with(
             post_get,
@@ -65,7 +77,10 @@ pub fn editor(props: &Props) -> Html {
                         description: resp.data.description.clone(),
                         body: resp.data.body.clone(),
                         tags: Some(resp.data.tags.clone()),
+                        status: Some(resp.data.status),
+                        publish_at: resp.data.publish_at,
                     });
+                    schedule_input.set(resp.data.publish_at.map(to_local_input).unwrap_or_default());
                     error.set(None);
                 }
                 if let Some(err) = &post_get.error {
@@ -85,7 +100,7 @@ pub fn editor(props: &Props) -> Html {
                 if let Some(resp) = &post_update.data {
                     error.set(None);
                     navigator.push(&AppRoute::Post {
-                        post_id: resp.data.id.clone(),
+                        slug: resp.data.slug.clone(),
                     });
                 }
                 if let Some(err) = &post_update.error {
@@ -131,6 +146,50 @@ pub fn editor(props: &Props) -> Html {
             update_dto.set(dto);
         })
     };
+    // Images dropped or pasted into the body are uploaded and linked where the cursor is.
+    let upload_images = {
+        let body_ref = body_ref.clone();
+        let uploading = uploading.clone();
+        let upload_error = upload_error.clone();
+        Callback::from(move |files: FileList| {
+            let images = (0..files.length())
+                .filter_map(|index| files.get(index))
+                .filter(|file| file.type_().starts_with("image/"));
+            for file in images {
+                let body_ref = body_ref.clone();
+                let uploading = uploading.clone();
+                let upload_error = upload_error.clone();
+                uploading.increase();
+                spawn_local(async move {
+                    match upload(file).await {
+                        Ok(media) => {
+                            upload_error.set(None);
+                            insert_at_cursor(&body_ref, &image_markdown(&media.data));
+                        }
+                        Err(err) => upload_error.set(Some(err)),
+                    }
+                    uploading.decrease();
+                });
+            }
+        })
+    };
+    let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+    let ondrop = {
+        let upload_images = upload_images.clone();
+        Callback::from(move |e: DragEvent| {
+            if let Some(files) = e.data_transfer().and_then(|data| data.files()).filter(|files| files.length() > 0) {
+                e.prevent_default();
+                upload_images.emit(files);
+            }
+        })
+    };
+    let onpaste = Callback::from(move |e: Event| {
+        let e: ClipboardEvent = e.unchecked_into();
+        if let Some(files) = e.clipboard_data().and_then(|data| data.files()).filter(|files| files.length() > 0) {
+            e.prevent_default();
+            upload_images.emit(files);
+        }
+    });
     let oninput_tag = {
         let tag_input = tag_input.clone();
         Callback::from(move |e: InputEvent| {
@@ -138,6 +197,17 @@ pub fn editor(props: &Props) -> Html {
             tag_input.set(input.value());
         })
     };
+    let oninput_schedule = {
+        let update_dto = update_dto.clone();
+        let schedule_input = schedule_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut dto = (*update_dto).clone();
+            dto.publish_at = from_local_input(&input.value());
+            update_dto.set(dto);
+            schedule_input.set(input.value());
+        })
+    };
     let onkeypress = Callback::from(|e: KeyboardEvent| {
         if e.key() == "Enter" {
             e.prevent_default();
@@ -179,6 +249,7 @@ pub fn editor(props: &Props) -> Html {
                             value={update_dto.title.clone()}
                             oninput={oninput_title}
                         />
+                        <FieldError error={(*error).clone()} field="title" />
                     </div>
                     <div class="form-group">
                         <label for="description" class="block text-sm font-medium text-gray-700">
@@ -190,6 +261,7 @@ pub fn editor(props: &Props) -> Html {
                             value={update_dto.description.clone()}
                             oninput={oninput_description}
                         />
+                        <FieldError error={(*error).clone()} field="description" />
                     </div>
                     <div class="form-group">
                         <label for="Content" class="block text-sm font-medium text-gray-700">
@@ -198,10 +270,24 @@ pub fn editor(props: &Props) -> Html {
                         <textarea
                             class="mt-1 w-full p-2 border rounded resize-none"
                             rows="8"
-                            placeholder="You can use markdown text here..."
+                            placeholder="You can use markdown text here, and drop or paste images in..."
+                            ref={body_ref}
                             value={update_dto.body.clone()}
-                            oninput={oninput_body} >
+                            oninput={oninput_body}
+                            {ondragover}
+                            {ondrop}
+                            {onpaste} >
                         </textarea>
+                        {
+                            if *uploading > 0 {
+                                html! { <p class="text-sm text-gray-500">{ "Uploading images..." }</p> }
+                            } else {
+                                html! {}
+                            }
+                        }
+                        <ShowError error={(*upload_error).clone()} />
+                        <FieldError error={(*upload_error).clone()} field="file" />
+                        <FieldError error={(*error).clone()} field="body" />
                     </div>
                     <div class="form-group">
                         <label for="tags" class="block text-sm font-medium text-gray-700">
@@ -215,6 +301,7 @@ pub fn editor(props: &Props) -> Html {
                             {onkeypress}
                             {onkeyup}
                         />
+                        <FieldError error={(*error).clone()} field="tags" />
                         <div class="mt-2 flex flex-wrap space-x-2">
                             {
                                 if let Some(tags) = &update_dto.tags.clone() {
@@ -248,14 +335,134 @@ pub fn editor(props: &Props) -> Html {
                             }
                         </div>
                     </div>
+                    <div class="form-group">
+                        <label class="block text-sm font-medium text-gray-700">
+                            { "Status" }
+                        </label>
+                        <div class="mt-1 inline-flex rounded border divide-x overflow-hidden">
+                            { for status_options(props.post_id.is_some()).into_iter().map(|(status, label)| {
+                                let selected = update_dto.status.unwrap_or_default() == status;
+                                let onclick = {
+                                    let update_dto = update_dto.clone();
+                                    Callback::from(move |_| {
+                                        let mut dto = (*update_dto).clone();
+                                        dto.status = Some(status);
+                                        update_dto.set(dto);
+                                    })
+                                };
+                                html! {
+                                    <button
+                                        type="button"
+                                        class={classes!("px-3", "py-1", "text-sm", if selected { "bg-indigo-600 text-white" } else { "hover:bg-gray-100" })}
+                                        {onclick}>
+                                        { label }
+                                    </button>
+                                }
+                            }) }
+                        </div>
+                        <FieldError error={(*error).clone()} field="status" />
+                    </div>
+                    {
+                        if update_dto.status == Some(PostStatus::Scheduled) {
+                            html! {
+                                <div class="form-group">
+                                    <label for="publish_at" class="block text-sm font-medium text-gray-700">
+                                        { "Publish at" }
+                                    </label>
+                                    <input
+                                        class="mt-1 p-2 border rounded"
+                                        type="datetime-local"
+                                        value={(*schedule_input).clone()}
+                                        oninput={oninput_schedule}
+                                    />
+                                    <FieldError error={(*error).clone()} field="publish_at" />
+                                </div>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
                     <button
                         class="mt-4 w-full p-2 bg-indigo-600 text-white rounded hover:bg-blue-700 disabled:opacity-50"
                         type="submit"
                         disabled={post_update.loading}>
-                        { "Publish" }
+                        {
+                            match update_dto.status.unwrap_or_default() {
+                                PostStatus::Draft => "Save draft",
+                                PostStatus::Scheduled => "Schedule",
+                                PostStatus::Published => "Publish",
+                                PostStatus::Archived => "Archive",
+                            }
+                        }
                     </button>
                 </form>
             </div>
         </div>
     }
 }
+
+/// Markdown showing an uploaded image at the size posts are read at.
+fn image_markdown(media: &MediaDto) -> String {
+    let alt = media
+        .original_filename
+        .as_deref()
+        .and_then(|name| name.rsplit_once('.').map(|(stem, _)| stem).or(Some(name)))
+        .unwrap_or_default()
+        .replace(['[', ']'], "");
+    format!("![{alt}]({})\n", media.medium_url)
+}
+
+/// Replaces the selection in the textarea behind `node_ref` with `text`, then lets `oninput`
+/// pick up the change as if it had been typed.
+fn insert_at_cursor(node_ref: &NodeRef, text: &str) {
+    let Some(textarea) = node_ref.cast::<HtmlTextAreaElement>() else {
+        return;
+    };
+    let end_of_text = textarea.value().encode_utf16().count() as u32;
+    let start = textarea.selection_start().ok().flatten().unwrap_or(end_of_text);
+    let end = textarea.selection_end().ok().flatten().unwrap_or(start);
+    if textarea.set_range_text_with_start_and_end_and_mode(text, start, end, "end").is_err() {
+        return;
+    }
+
+    let init = EventInit::new();
+    init.set_bubbles(true);
+    if let Ok(event) = Event::new_with_event_init_dict("input", &init) {
+        let _ = textarea.dispatch_event(&event);
+    }
+}
+
+/// Statuses a writer can pick; only existing posts can be archived.
+fn status_options(editing: bool) -> Vec<(PostStatus, &'static str)> {
+    let mut options = vec![
+        (PostStatus::Draft, "Draft"),
+        (PostStatus::Published, "Publish now"),
+        (PostStatus::Scheduled, "Schedule"),
+    ];
+    if editing {
+        options.push((PostStatus::Archived, "Archived"));
+    }
+    options
+}
+
+/// Formats a time for a `datetime-local` input, which works in the browser's time zone.
+fn to_local_input(at: DateTime<Utc>) -> String {
+    let date = Date::new(&JsValue::from_f64(at.timestamp_millis() as f64));
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes(),
+    )
+}
+
+fn from_local_input(value: &str) -> Option<DateTime<Utc>> {
+    let date = Date::new(&JsValue::from_str(value));
+    if date.get_time().is_nan() {
+        return None;
+    }
+    let iso: String = date.to_iso_string().into();
+    DateTime::parse_from_rfc3339(&iso).ok().map(|at| at.with_timezone(&Utc))
+}
@@ -0,0 +1,90 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::field_error::FieldError;
+use crate::components::show_error::ShowError;
+use crate::routes::AppRoute;
+use crate::services::user::forgot_password;
+use crate::types::user::ForgotPasswordDto;
+use crate::types::Wrapper;
+
+#[function_component(ForgotPassword)]
+pub fn forgot_password_page() -> Html {
+    let forgot_password_dto = use_state(ForgotPasswordDto::default);
+    let request = {
+        let forgot_password_dto = forgot_password_dto.clone();
+        use_async(async move {
+            forgot_password(Wrapper { data: (*forgot_password_dto).clone() }).await
+        })
+    };
+
+    let onsubmit = {
+        let request = request.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            request.run();
+        })
+    };
+
+    let oninput_email = {
+        let forgot_password_dto = forgot_password_dto.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            forgot_password_dto.set(ForgotPasswordDto { email: input.value() });
+        })
+    };
+
+    html! {
+        <div class="max-w-md mx-auto mt-12 mb-12">
+            <h1 class="text-center text-xl font-semibold mb-4">{ "Forgot your password?" }</h1>
+            {
+                if request.data.is_some() {
+                    html! {
+                        <p class="text-center">
+                            { format!("If an account uses {}, we've sent it a link to choose a new password.", forgot_password_dto.email) }
+                        </p>
+                    }
+                } else {
+                    html! {
+                        <>
+                            <ShowError error={request.error.clone()} />
+                            <form onsubmit={ onsubmit }>
+                                <p class="mb-4 text-sm text-gray-600">
+                                    { "Enter the email address of your account and we'll send you a link to reset your password." }
+                                </p>
+                                <div class="mb-4">
+                                    <label for="email" class="block text-sm font-medium text-gray-700">
+                                        { "Email" }
+                                    </label>
+                                    <input
+                                        class="mt-1 p-2 border rounded w-full"
+                                        type="email"
+                                        value={ forgot_password_dto.email.clone() }
+                                        oninput={ oninput_email }
+                                        />
+                                    <FieldError error={request.error.clone()} field="email" />
+                                </div>
+                                <div class="flex justify-center">
+                                    <button type="submit"
+                                        class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-300 focus:outline-none"
+                                        disabled={request.loading}>
+                                        { "Send reset link" }
+                                    </button>
+                                </div>
+                            </form>
+                        </>
+                    }
+                }
+            }
+            <p class="text-center text-sm mt-4">
+                <span class="text-blue-500 hover:underline">
+                    <Link<AppRoute> to={AppRoute::Login}>
+                        { "Back to login" }
+                    </Link<AppRoute>>
+                </span>
+            </p>
+        </div>
+    }
+}
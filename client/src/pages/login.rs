@@ -3,17 +3,22 @@ use yew::prelude::*;
 use yew_hooks::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::field_error::FieldError;
+use crate::components::oauth_buttons::OAuthButtons;
 use crate::components::show_error::ShowError;
+use crate::components::two_factor_prompt::TwoFactorPrompt;
 use crate::middlewares::context::use_user_context;
 use crate::routes::AppRoute;
 use crate::services::user::login;
-use crate::types::user::LoginDto;
+use crate::types::two_factor::LoginResultDto;
+use crate::types::user::{LoginDto, UserDto};
 use crate::types::Wrapper;
 
 #[function_component(Login)]
 pub fn login_page() -> Html {
     let user_ctx = use_user_context();
     let login_dto = use_state(LoginDto::default);
+    let pending_token = use_state(|| None::<String>);
 
     let login = {
         let login_dto = login_dto.clone();
@@ -22,15 +27,33 @@ pub fn login_page() -> Html {
         })
     };
 
-    use_effect_with(
-        login.clone(),
-        move |login| {
-            if let Some(resp) = &login.data {
-                user_ctx.login(resp.data.clone());
-            }
-            || ()
-        },
-    );
+    {
+        let user_ctx = user_ctx.clone();
+        let pending_token = pending_token.clone();
+        use_effect_with(
+            login.clone(),
+            move |login| {
+                match login.data.as_ref().map(|resp| &resp.data) {
+                    Some(LoginResultDto::User(user)) => user_ctx.login(user.clone()),
+                    Some(LoginResultDto::TwoFactorRequired(challenge)) => {
+                        pending_token.set(Some(challenge.pending_token.clone()));
+                    }
+                    None => {}
+                }
+                || ()
+            },
+        );
+    }
+
+    if let Some(pending_token) = &*pending_token {
+        let on_signed_in = Callback::from(move |user: UserDto| user_ctx.login(user));
+        return html! {
+            <div class="max-w-md mx-auto mt-12 mb-12">
+                <h1 class="text-center text-xl font-semibold mb-4">{ "Two-factor authentication" }</h1>
+                <TwoFactorPrompt pending_token={pending_token.clone()} on_signed_in={on_signed_in} />
+            </div>
+        };
+    }
 
     let onsubmit = {
         let login = login.clone();
@@ -64,6 +87,7 @@ pub fn login_page() -> Html {
         <div class="max-w-md mx-auto mt-12 mb-12">
             <h1 class="text-center text-xl font-semibold mb-4">{ "Login" }</h1>
             <ShowError error={login.error.clone()} />
+            <OAuthButtons />
             <form onsubmit={ onsubmit }>
                 <div class="mb-4">
                     <label for="email" class="block text-sm font-medium text-gray-700">
@@ -75,6 +99,7 @@ pub fn login_page() -> Html {
                         value={ login_dto.email.clone() }
                         oninput={ oninput_email }
                         />
+                    <FieldError error={login.error.clone()} field="email" />
                 </div>
                 <div class="mb-4">
                     <label for="password" class="block text-sm font-medium text-gray-700">
@@ -86,6 +111,14 @@ pub fn login_page() -> Html {
                         value={ login_dto.password.clone() }
                         oninput={ oninput_password }
                         />
+                    <FieldError error={login.error.clone()} field="password" />
+                    <p class="mt-1 text-right text-sm">
+                        <span class="text-blue-500 hover:underline">
+                            <Link<AppRoute> to={AppRoute::ForgotPassword}>
+                                { "Forgot your password?" }
+                            </Link<AppRoute>>
+                        </span>
+                    </p>
                 </div>
                 <div class="flex justify-center">
                     <button type="submit" class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-300 focus:outline-none">
@@ -1,9 +1,17 @@
+pub mod admin;
+pub mod forgot_password;
 pub mod health;
 pub mod home;
 pub mod login;
+pub mod moderation;
+pub mod oauth_callback;
 pub mod post;
+pub mod post_history;
 pub mod profile;
+pub mod reset_password;
+pub mod search;
 pub mod settings;
 pub mod sign_up;
 pub mod users;
+pub mod verify_email;
 pub mod editor;
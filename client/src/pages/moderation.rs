@@ -0,0 +1,188 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::pagination::{PageCursor, Pagination};
+use crate::components::show_error::ShowError;
+use crate::middlewares::context::use_user_context;
+use crate::middlewares::error::Error;
+use crate::routes::AppRoute;
+use crate::services::moderation::{dismiss_reports, report_queue, resolve_reports};
+use crate::types::moderation::ResolveReportsDto;
+use crate::types::report::{ReportGroupDto, ReportTarget};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Resolution {
+    Resolve { hide: bool },
+    Dismiss,
+}
+
+/// The queue of open reports, for moderators.
+#[function_component(Moderation)]
+pub fn moderation() -> Html {
+    let user_ctx = use_user_context();
+    let current_page = use_state(PageCursor::default);
+    // Bumped after each resolution so the page is fetched again.
+    let revision = use_state(|| 0_usize);
+    let action_error = use_state(|| None::<Error>);
+
+    let queue = {
+        let cursor = current_page.cursor.clone();
+        use_async(async move { report_queue(cursor).await })
+    };
+
+    {
+        let queue = queue.clone();
+        let is_moderator = user_ctx.is_moderator();
+        use_effect_with(
+            ((*current_page).clone(), *revision, is_moderator),
+            move |(_, _, is_moderator)| {
+                if *is_moderator {
+                    queue.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    let callback = {
+        let current_page = current_page.clone();
+        use_callback(
+            (),
+            move |page, _| {
+                current_page.set(page);
+            },
+        )
+    };
+
+    let on_resolution = {
+        let revision = revision.clone();
+        let action_error = action_error.clone();
+        Callback::from(move |(group, resolution): (ReportGroupDto, Resolution)| {
+            let Some(reason) = web_sys::window()
+                .and_then(|window| window.prompt_with_message("Reason for the audit log:").ok())
+                .flatten()
+                .map(|reason| reason.trim().to_string())
+                .filter(|reason| !reason.is_empty())
+            else {
+                return;
+            };
+
+            let revision = revision.clone();
+            let action_error = action_error.clone();
+            spawn_local(async move {
+                let result = match resolution {
+                    Resolution::Resolve { hide } => {
+                        resolve_reports(group.target_type, group.target_id, ResolveReportsDto { reason, hide }).await
+                    }
+                    Resolution::Dismiss => dismiss_reports(group.target_type, group.target_id, reason).await,
+                };
+                match result {
+                    Ok(_) => {
+                        action_error.set(None);
+                        revision.set(*revision + 1);
+                    }
+                    Err(err) => action_error.set(Some(err)),
+                }
+            });
+        })
+    };
+
+    if !user_ctx.is_moderator() {
+        return html! {
+            <div class="text-center mt-12 text-gray-500">{ "Only moderators can see this page." }</div>
+        };
+    }
+
+    html! {
+        <div class="max-w-4xl mx-auto mt-12 mb-12 px-4">
+            <h1 class="text-center text-xl font-semibold mb-4">{ "Reports" }</h1>
+            <ShowError error={queue.error.clone()} />
+            <ShowError error={(*action_error).clone()} />
+            {
+                match &queue.data {
+                    Some(resp) if !resp.data.groups.is_empty() => html! {
+                        <>
+                            {
+                                for resp.data.groups.iter().map(|group| view_group(group, &on_resolution))
+                            }
+                            <Pagination
+                                total={None::<i64>}
+                                current_page={current_page.page}
+                                prev_cursor={resp.data.prev_cursor.clone()}
+                                next_cursor={resp.data.next_cursor.clone()}
+                                callback={callback} />
+                        </>
+                    },
+                    Some(_) => html! { <div class="text-center mt-4">{ "No open reports" }</div> },
+                    None => html! { <div class="text-center mt-4">{ "Loading" }</div> },
+                }
+            }
+        </div>
+    }
+}
+
+fn view_group(group: &ReportGroupDto, on_resolution: &Callback<(ReportGroupDto, Resolution)>) -> Html {
+    let button = |label: &'static str, class: &'static str, resolution: Resolution| {
+        let on_resolution = on_resolution.clone();
+        let group = group.clone();
+        let onclick = Callback::from(move |_: MouseEvent| on_resolution.emit((group.clone(), resolution)));
+        html! {
+            <button class={classes!("px-2", "py-1", "border", "rounded", "text-xs", class)} onclick={onclick}>
+                { label }
+            </button>
+        }
+    };
+
+    let kind = match group.target_type {
+        ReportTarget::Post => "Post",
+        ReportTarget::Comment => "Comment",
+    };
+    let categories = group.categories.iter().map(|category| category.label()).collect::<Vec<_>>().join(", ");
+
+    html! {
+        <div class="mb-4 p-4 border border-gray-200 rounded-md shadow-sm" key={group.target_id.clone()}>
+            <div class="flex justify-between items-start">
+                <div>
+                    <span class="text-xs uppercase text-gray-500 mr-2">{ kind }</span>
+                    {
+                        if group.hidden {
+                            html! { <span class="text-xs px-2 rounded-full bg-amber-100 text-amber-800 mr-2">{ "hidden" }</span> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <Link<AppRoute> to={AppRoute::Post { slug: group.post_slug.clone() }} classes="font-semibold hover:underline">
+                        { &group.excerpt }
+                    </Link<AppRoute>>
+                    <p class="text-xs text-gray-500">
+                        { format!("by {} · {} report(s): {}", group.author, group.report_count, categories) }
+                    </p>
+                </div>
+                <div class="flex space-x-1 shrink-0">
+                    { button("Resolve & hide", "text-red-600 hover:bg-red-50", Resolution::Resolve { hide: true }) }
+                    { button("Resolve", "hover:bg-gray-100", Resolution::Resolve { hide: false }) }
+                    { button("Dismiss", "hover:bg-gray-100", Resolution::Dismiss) }
+                </div>
+            </div>
+            <ul class="mt-2 text-sm text-gray-700">
+                {
+                    for group.reports.iter().map(|report| html! {
+                        <li key={report.id.clone()} class="border-t py-1">
+                            <span class="font-semibold">{ &report.reporter }</span>
+                            { format!(" · {} · {}", report.category.label(), report.created_at.format("%Y-%m-%d %H:%M")) }
+                            {
+                                if report.details.is_empty() {
+                                    html! {}
+                                } else {
+                                    html! { <p class="text-gray-600">{ &report.details }</p> }
+                                }
+                            }
+                        </li>
+                    })
+                }
+            </ul>
+        </div>
+    }
+}
@@ -0,0 +1,111 @@
+use serde::Deserialize;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::show_error::ShowError;
+use crate::components::two_factor_prompt::TwoFactorPrompt;
+use crate::middlewares::context::use_user_context;
+use crate::routes::AppRoute;
+use crate::services::oauth::callback;
+use crate::types::oauth::{OAuthCallbackDto, OAuthCallbackOutcomeDto};
+use crate::types::user::UserDto;
+use crate::types::Wrapper;
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub provider: String,
+}
+
+/// What the provider appends to the redirect URI.
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+struct CallbackQuery {
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    state: String,
+    error: Option<String>,
+}
+
+/// Where a provider sends the user back to; finishes signing in with, or linking, the provider.
+#[function_component(OAuthCallback)]
+pub fn oauth_callback(props: &Props) -> Html {
+    let user_ctx = use_user_context();
+    let navigator = use_navigator().unwrap();
+    let query = yew_router::hooks::use_location()
+        .and_then(|location| location.query::<CallbackQuery>().ok())
+        .unwrap_or_default();
+
+    let finish = {
+        let provider = props.provider.clone();
+        let dto = OAuthCallbackDto {
+            code: query.code.clone(),
+            state: query.state.clone(),
+        };
+        use_async(async move { callback(provider, Wrapper { data: dto }).await })
+    };
+
+    {
+        let finish = finish.clone();
+        let denied = query.error.is_some();
+        use_effect_with(
+            (),
+            move |_| {
+                if !denied {
+                    finish.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    {
+        let user_ctx = user_ctx.clone();
+        use_effect_with(
+            finish.data.clone(),
+            move |outcome| {
+                if let Some(OAuthCallbackOutcomeDto::Done(result)) = outcome.as_ref().map(|resp| &resp.data) {
+                    if result.linked {
+                        user_ctx.login_without_redirection(result.user.clone());
+                        navigator.push(&AppRoute::Settings);
+                    } else {
+                        user_ctx.login(result.user.clone());
+                    }
+                }
+                || ()
+            },
+        );
+    }
+
+    if let Some(OAuthCallbackOutcomeDto::TwoFactorRequired(challenge)) = finish.data.as_ref().map(|resp| &resp.data) {
+        let on_signed_in = Callback::from(move |user: UserDto| user_ctx.login(user));
+        return html! {
+            <div class="max-w-md mx-auto mt-12 mb-12">
+                <h1 class="text-center text-xl font-semibold mb-4">{ "Two-factor authentication" }</h1>
+                <TwoFactorPrompt pending_token={challenge.pending_token.clone()} on_signed_in={on_signed_in} />
+            </div>
+        };
+    }
+
+    html! {
+        <div class="max-w-md mx-auto mt-12 mb-12 text-center">
+            <h1 class="text-xl font-semibold mb-4">{ "Signing in" }</h1>
+            <ShowError error={finish.error.clone()} />
+            {
+                if query.error.is_some() || finish.error.is_some() {
+                    html! {
+                        <p>
+                            { "That didn't work. Go back to " }
+                            <span class="text-blue-500 hover:underline">
+                                <Link<AppRoute> to={AppRoute::Login}>{ "the login page" }</Link<AppRoute>>
+                            </span>
+                            { " to try again." }
+                        </p>
+                    }
+                } else {
+                    html! { <p class="text-gray-500">{ "Just a moment..." }</p> }
+                }
+            }
+        </div>
+    }
+}
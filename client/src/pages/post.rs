@@ -1,41 +1,89 @@
-use web_sys::Node;
 use yew::prelude::*;
-use yew::virtual_dom::VNode;
 use yew_hooks::prelude::*;
+use yew_router::prelude::*;
 
 use crate::components::comment_list::CommentList;
 use crate::components::post_meta::PostMeta;
 use crate::middlewares::context::use_user_context;
-use crate::services::post::get;
+use crate::middlewares::prefetch::use_prefetched_async;
+use crate::routes::AppRoute;
+use crate::services::event::subscribe_post;
+use crate::services::post::{get_by_slug, get_by_slug_url};
+use crate::types::event::LiveEvent;
+use crate::types::post::{PostDto, PostStatus, TocEntryDto};
 
 #[derive(Properties, Clone, PartialEq, Eq)]
 pub struct Props {
-    pub post_id: String,
+    pub slug: String,
 }
 
 #[function_component(Post)]
 pub fn post(props: &Props) -> Html {
+    let navigator = use_navigator().unwrap();
     let post = {
-        let slug = props.post_id.clone();
-        use_async_with_options(
-            async move { get(slug).await },
-            UseAsyncOptions::enable_auto(),
-        )
+        let slug = props.slug.clone();
+        use_async(async move { get_by_slug(slug).await })
     };
 
+    let post = use_prefetched_async(get_by_slug_url(&props.slug), &post, props.slug.clone());
+
+    {
+        let slug = props.slug.clone();
+        use_effect_with(
+            post.as_ref().map(|resp| resp.data.slug.clone()),
+            move |current_slug| {
+                // Old slugs still resolve after a post is retitled; show the current one.
+                if let Some(current_slug) = current_slug {
+                    if *current_slug != slug {
+                        navigator.replace(&AppRoute::Post {
+                            slug: current_slug.clone(),
+                        });
+                    }
+                }
+                || ()
+            },
+        );
+    }
+
+    // Kept up to date while the post is open; `None` until someone (un)favorites it.
+    let live_favorites_count = use_state(|| None::<i64>);
+
+    {
+        let live_favorites_count = live_favorites_count.clone();
+        use_effect_with(
+            post.as_ref().map(|resp| resp.data.id.clone()),
+            move |post_id| {
+                live_favorites_count.set(None);
+                let subscription = post_id.clone().map(|post_id| {
+                    subscribe_post(post_id, Callback::from(move |event| {
+                        if let LiveEvent::FavoritesChanged { favorites_count, .. } = event {
+                            live_favorites_count.set(Some(favorites_count));
+                        }
+                    }))
+                });
+                move || {
+                    if let Some(subscription) = subscription {
+                        subscription.abort();
+                    }
+                }
+            },
+        );
+    }
+
     let user_ctx = use_user_context();
 
-    if let Some(resp) = &post.data {
+    if let Some(resp) = &post {
         let post = &resp.data;
         let can_edit =
             user_ctx.is_authenticated() && user_ctx.display_name == post.author.display_name;
-        let created_at = post.created_at.format("%B %e, %Y").to_string();
+        let created_at = post.published_at.unwrap_or(post.created_at).format("%B %e, %Y").to_string();
 
         html! {
             <div>
                 <div class="bg-gradient-to-r from-indigo-800 to-gray-800 text-white py-8">
                     <div class="lg:w-4/5 mx-auto">
                         <h1 class="px-4 mb-4 text-3xl font-bold">{&post.title}</h1>
+                        { view_status(post) }
                         <PostMeta
                             post_id={ post.id.clone() }
                             author={ post.author.clone() }
@@ -47,7 +95,14 @@ pub fn post(props: &Props) -> Html {
                 <div class="lg:w-4/5 mx-auto py-6 px-4">
                     <div class="row">
                         <div class="col-xs-12">
-                            { view_body(&post.body) }
+                            { view_toc(&post.toc) }
+                            { Html::from_html_unchecked(post.body_html.clone().into()) }
+                            <p class="mt-4 inline-flex items-center text-sm text-gray-500">
+                                <svg class="w-5 h-5 mr-1 fill-current text-red-500">
+                                    <path d="M9.653 16.915l-.005-.003-.019-.010a20.759 20.759 0 01-1.162-.682 22.045 22.045 0 01-2.582-1.900C4.045 12.733 2 10.352 2 7.500a4.5 4.5 0 018-2.828A4.5 4.5 0 0118 7.500c0 2.852-2.044 5.233-3.885 6.820a22.049 22.049 0 01-3.744 2.582l-.019.010-.005.003h-.002a.739.739 0 01-.690.001l-.002-.001z" />
+                                </svg>
+                                { live_favorites_count.unwrap_or(post.favorites_count) }
+                            </p>
                             <ul class="flex mt-4">
                                 {for post.tags.iter().map(|tag| {
                                     html! {
@@ -61,7 +116,7 @@ pub fn post(props: &Props) -> Html {
                     </div>
                     <div class="py-6">
                         <h3 class="text-xl font-bold">{ "Comments" }</h3>
-                        <CommentList post_id={ props.post_id.clone() } />
+                        <CommentList post_id={ post.id.clone() } />
                     </div>
                 </div>
             </div>
@@ -71,19 +126,43 @@ pub fn post(props: &Props) -> Html {
     }
 }
 
-fn view_body(body: &str) -> Html {
-    let parser = pulldown_cmark::Parser::new(body);
-    let mut html_text = String::new();
-    pulldown_cmark::html::push_html(&mut html_text, parser);
+/// Reminds the author that only they can see this post.
+fn view_status(post: &PostDto) -> Html {
+    let notice = match (post.status, post.publish_at) {
+        (PostStatus::Published, _) => return html! {},
+        (PostStatus::Draft, _) => "Draft - only you can see this post".to_string(),
+        (PostStatus::Scheduled, Some(publish_at)) => {
+            format!("Scheduled to publish on {}", publish_at.format("%B %e, %Y %H:%M UTC"))
+        }
+        (PostStatus::Scheduled, None) => "Scheduled".to_string(),
+        (PostStatus::Archived, _) => "Archived - only you can see this post".to_string(),
+    };
+
+    html! {
+        <p class="px-4 mb-4 inline-block text-sm bg-yellow-100 text-yellow-800 rounded">{ notice }</p>
+    }
+}
 
-    let div = web_sys::window()
-        .unwrap()
-        .document()
-        .unwrap()
-        .create_element("div")
-        .unwrap();
+/// Links to the post's headings, for posts long enough to have a few.
+fn view_toc(toc: &[TocEntryDto]) -> Html {
+    if toc.len() < 3 {
+        return html! {};
+    }
+    let top_level = toc.iter().map(|entry| entry.level).min().unwrap_or(1);
 
-    div.set_inner_html(html_text.as_str());
-    let node = Node::from(div);
-    VNode::VRef(node)
+    html! {
+        <nav class="mb-6 p-4 border rounded-md text-sm">
+            <p class="font-semibold mb-2">{ "Contents" }</p>
+            <ul>
+                {for toc.iter().map(|entry| {
+                    let indent = format!("padding-left: {}rem", entry.level - top_level);
+                    html! {
+                        <li style={indent}>
+                            <a class="text-blue-500 hover:underline" href={format!("#{}", entry.id)}>{ &entry.title }</a>
+                        </li>
+                    }
+                })}
+            </ul>
+        </nav>
+    }
 }
@@ -0,0 +1,164 @@
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::post_diff::PostDiff;
+use crate::components::show_error::ShowError;
+use crate::middlewares::context::use_user_context;
+use crate::routes::AppRoute;
+use crate::services::post::{diff, get, restore, revisions};
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub post_id: String,
+}
+
+/// Earlier versions of a post, each comparable with the current one and restorable by the author.
+#[function_component(PostHistory)]
+pub fn post_history(props: &Props) -> Html {
+    let user_ctx = use_user_context();
+    let navigator = use_navigator().unwrap();
+    let selected = use_state(|| None::<String>);
+
+    let post = {
+        let post_id = props.post_id.clone();
+        use_async_with_options(async move { get(post_id).await }, UseAsyncOptions::enable_auto())
+    };
+    let post_revisions = {
+        let post_id = props.post_id.clone();
+        use_async_with_options(async move { revisions(post_id).await }, UseAsyncOptions::enable_auto())
+    };
+    let post_diff = {
+        let post_id = props.post_id.clone();
+        let selected = selected.clone();
+        use_async(async move { diff(post_id, (*selected).clone(), None).await })
+    };
+    let restore_revision = {
+        let post_id = props.post_id.clone();
+        let selected = selected.clone();
+        use_async(async move { restore(post_id, (*selected).clone().unwrap_or_default()).await })
+    };
+
+    {
+        let post_diff = post_diff.clone();
+        use_effect_with(
+            (*selected).clone(),
+            move |selected| {
+                if selected.is_some() {
+                    post_diff.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    use_effect_with(
+        restore_revision.clone(),
+        move |restore_revision| {
+            if let Some(resp) = &restore_revision.data {
+                navigator.push(&AppRoute::Post {
+                    slug: resp.data.slug.clone(),
+                });
+            }
+            || ()
+        },
+    );
+
+    let can_restore = post.data.as_ref().is_some_and(|resp| {
+        user_ctx.is_authenticated() && user_ctx.display_name == resp.data.author.display_name
+    });
+
+    let on_restore = {
+        let restore_revision = restore_revision.clone();
+        Callback::from(move |_| restore_revision.run())
+    };
+
+    html! {
+        <div class="lg:w-2/3 mx-auto mt-12 mb-12 px-4">
+            <ShowError error={post.error.clone()} />
+            <ShowError error={post_revisions.error.clone()} />
+            <ShowError error={restore_revision.error.clone()} />
+            {
+                if let Some(resp) = &post.data {
+                    html! {
+                        <h1 class="text-xl font-semibold mb-4">
+                            { "History of " }
+                            <Link<AppRoute> to={AppRoute::Post { slug: resp.data.slug.clone() }} classes="text-indigo-600 hover:underline">
+                                { &resp.data.title }
+                            </Link<AppRoute>>
+                        </h1>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                match &post_revisions.data {
+                    Some(resp) if resp.data.revisions.is_empty() => html! {
+                        <p class="text-gray-500">{ "This post has not been edited." }</p>
+                    },
+                    Some(resp) => html! {
+                        <ul class="divide-y border rounded-md mb-6">
+                            { for resp.data.revisions.iter().map(|revision| {
+                                let is_selected = selected.as_deref() == Some(revision.id.as_str());
+                                let onclick = {
+                                    let selected = selected.clone();
+                                    let revision_id = revision.id.clone();
+                                    Callback::from(move |_| selected.set(Some(revision_id.clone())))
+                                };
+                                html! {
+                                    <li key={revision.id.clone()} class={classes!("flex", "justify-between", "items-center", "p-3", is_selected.then_some("bg-indigo-50"))}>
+                                        <div>
+                                            <p class="font-medium">{ &revision.title }</p>
+                                            <p class="text-xs text-gray-500">
+                                                { format!("Replaced {}", revision.replaced_at.format("%B %e, %Y %H:%M")) }
+                                            </p>
+                                        </div>
+                                        <button class="text-sm text-indigo-600 hover:underline" onclick={onclick}>
+                                            { "Compare with current" }
+                                        </button>
+                                    </li>
+                                }
+                            }) }
+                        </ul>
+                    },
+                    None => html! {},
+                }
+            }
+            {
+                if selected.is_some() {
+                    html! {
+                        <div>
+                            <div class="flex justify-between items-center mb-2">
+                                <h2 class="font-semibold">{ "Changes since this revision" }</h2>
+                                {
+                                    if can_restore {
+                                        html! {
+                                            <button
+                                                class="px-3 py-1 text-sm bg-indigo-600 text-white rounded-md hover:bg-indigo-700 disabled:opacity-50"
+                                                onclick={on_restore}
+                                                disabled={restore_revision.loading}>
+                                                { "Restore this revision" }
+                                            </button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                            </div>
+                            <ShowError error={post_diff.error.clone()} />
+                            {
+                                match &post_diff.data {
+                                    Some(resp) if !post_diff.loading => html! { <PostDiff diff={resp.data.clone()} /> },
+                                    _ => html! {},
+                                }
+                            }
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
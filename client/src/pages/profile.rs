@@ -4,13 +4,17 @@ use yew_router::prelude::*;
 
 use crate::components::post_list::{PostList, PostListFilter};
 use crate::middlewares::context::use_user_context;
+use crate::middlewares::prefetch::use_prefetched_async;
 use crate::routes::AppRoute;
-use crate::services::profile::{follow, get, unfollow};
+use crate::services::profile::{block, follow, get, get_url, mute, unblock, unfollow, unmute};
+use crate::types::post::PostStatus;
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum ProfileTab {
     ByAuthor,
     FavoritedBy,
+    /// The profile owner's unpublished posts; only offered on their own profile.
+    Drafts,
 }
 
 #[derive(Properties, Clone, PartialEq, Eq)]
@@ -37,17 +41,47 @@ pub fn profile(props: &Props) -> Html {
             follow(display_name).await
         })
     };
+    let mute_toggle = {
+        let profile = profile.clone();
+        let display_name = props.display_name.clone();
+        use_async(async move {
+            if let Some(resp) = &profile.data {
+                if resp.data.muting {
+                    return unmute(display_name).await;
+                }
+            }
+            mute(display_name).await
+        })
+    };
+    let block_toggle = {
+        let profile = profile.clone();
+        let display_name = props.display_name.clone();
+        use_async(async move {
+            if let Some(resp) = &profile.data {
+                if resp.data.blocking {
+                    return unblock(display_name).await;
+                }
+            }
+            block(display_name).await
+        })
+    };
+
+    let unpublished_status = use_state(|| PostStatus::Draft);
 
     let user_ctx = use_user_context();
     let is_current_user =
         user_ctx.is_authenticated() && user_ctx.display_name == props.display_name;
 
+    let profile_data = use_prefetched_async(get_url(&props.display_name), &profile, props.display_name.clone());
+
     {
         let profile = profile.clone();
         use_effect_with(
-            props.display_name.clone(),
-            move |_| {
-                profile.run();
+            follow.clone(),
+            move |follow| {
+                if let Some(resp) = &follow.data {
+                    profile.update(resp.clone());
+                }
                 || ()
             },
         );
@@ -56,9 +90,22 @@ pub fn profile(props: &Props) -> Html {
     {
         let profile = profile.clone();
         use_effect_with(
-            follow.clone(),
-            move |follow| {
-                if let Some(resp) = &follow.data {
+            mute_toggle.clone(),
+            move |mute_toggle| {
+                if let Some(resp) = &mute_toggle.data {
+                    profile.update(resp.clone());
+                }
+                || ()
+            },
+        );
+    }
+
+    {
+        let profile = profile.clone();
+        use_effect_with(
+            block_toggle.clone(),
+            move |block_toggle| {
+                if let Some(resp) = &block_toggle.data {
                     profile.update(resp.clone());
                 }
                 || ()
@@ -72,7 +119,19 @@ pub fn profile(props: &Props) -> Html {
         })
     };
 
-    if let Some(profile) = &profile.data {
+    let onclick_mute = {
+        Callback::from(move |_| {
+            mute_toggle.run();
+        })
+    };
+
+    let onclick_block = {
+        Callback::from(move |_| {
+            block_toggle.run();
+        })
+    };
+
+    if let Some(profile) = &profile_data {
         let profile = &profile.data;
         html! {
             <div>
@@ -104,9 +163,35 @@ pub fn profile(props: &Props) -> Html {
                                         }
                                     } else {
                                         html! {
-                                            <button class="text-center block mx-auto mt-4 bg-indigo-600 hover:bg-indigo-700 text-white py-2 px-4 rounded-full text-sm" onclick={onclick}>
-                                                { if profile.following { "Unfollow" } else { "Follow" } }
-                                            </button>
+                                            <div class="flex justify-center space-x-2 mt-4">
+                                                {
+                                                    if profile.blocking {
+                                                        html! {}
+                                                    } else {
+                                                        html! {
+                                                            <button class="bg-indigo-600 hover:bg-indigo-700 text-white py-2 px-4 rounded-full text-sm" onclick={onclick}>
+                                                                { if profile.following { "Unfollow" } else { "Follow" } }
+                                                            </button>
+                                                        }
+                                                    }
+                                                }
+                                                {
+                                                    if user_ctx.is_authenticated() {
+                                                        html! {
+                                                            <>
+                                                                <button class="border border-gray-400 hover:bg-gray-200 text-gray-700 py-2 px-4 rounded-full text-sm" onclick={onclick_mute}>
+                                                                    { if profile.muting { "Unmute" } else { "Mute" } }
+                                                                </button>
+                                                                <button class="border border-red-400 hover:bg-red-50 text-red-600 py-2 px-4 rounded-full text-sm" onclick={onclick_block}>
+                                                                    { if profile.blocking { "Unblock" } else { "Block" } }
+                                                                </button>
+                                                            </>
+                                                        }
+                                                    } else {
+                                                        html! {}
+                                                    }
+                                                }
+                                            </div>
                                         }
                                     }
                                 }
@@ -129,10 +214,25 @@ pub fn profile(props: &Props) -> Html {
                                     <li>
                                         <Link<AppRoute>
                                             to={AppRoute::ProfileFavorite { display_name: profile.display_name.clone() }}
-                                            classes={if props.tab != ProfileTab::ByAuthor { "text-indigo-600 font-semibold border-b-2 border-indigo-600" } else { "text-gray-400" }}>
+                                            classes={if props.tab == ProfileTab::FavoritedBy { "text-indigo-600 font-semibold border-b-2 border-indigo-600" } else { "text-gray-400" }}>
                                             { "Favorited Articles" }
                                         </Link<AppRoute>>
                                     </li>
+                                    {
+                                        if is_current_user {
+                                            html! {
+                                                <li>
+                                                    <Link<AppRoute>
+                                                        to={AppRoute::ProfileDrafts { display_name: profile.display_name.clone() }}
+                                                        classes={if props.tab == ProfileTab::Drafts { "text-indigo-600 font-semibold border-b-2 border-indigo-600" } else { "text-gray-400" }}>
+                                                        { "My Drafts" }
+                                                    </Link<AppRoute>>
+                                                </li>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
                                 </ul>
                             </div>
                             {
@@ -143,6 +243,31 @@ pub fn profile(props: &Props) -> Html {
                                     ProfileTab::FavoritedBy => {
                                         html! { <PostList filter={PostListFilter::FavoritedBy(profile.display_name.clone())} /> }
                                     }
+                                    ProfileTab::Drafts => {
+                                        html! {
+                                            <>
+                                                <div class="mt-4 px-4 flex space-x-2">
+                                                    { for [
+                                                        (PostStatus::Draft, "Drafts"),
+                                                        (PostStatus::Scheduled, "Scheduled"),
+                                                        (PostStatus::Archived, "Archived"),
+                                                    ].into_iter().map(|(status, label)| {
+                                                        let onclick = {
+                                                            let unpublished_status = unpublished_status.clone();
+                                                            Callback::from(move |_| unpublished_status.set(status))
+                                                        };
+                                                        let class = if *unpublished_status == status {
+                                                            "px-3 py-1 text-sm rounded-full bg-indigo-600 text-white"
+                                                        } else {
+                                                            "px-3 py-1 text-sm rounded-full bg-gray-100 text-gray-600 hover:bg-gray-200"
+                                                        };
+                                                        html! { <button {class} {onclick}>{ label }</button> }
+                                                    }) }
+                                                </div>
+                                                <PostList filter={PostListFilter::ByStatus(profile.display_name.clone(), *unpublished_status)} />
+                                            </>
+                                        }
+                                    }
                                 }
                             }
                         </div>
@@ -0,0 +1,108 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::field_error::FieldError;
+use crate::components::show_error::ShowError;
+use crate::middlewares::error::Error;
+use crate::routes::AppRoute;
+use crate::services::user::reset_password;
+use crate::types::user::ResetPasswordDto;
+use crate::types::Wrapper;
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub token: String,
+}
+
+/// Where the link in a password reset email leads.
+#[function_component(ResetPassword)]
+pub fn reset_password_page(props: &Props) -> Html {
+    let password = use_state(String::default);
+    let reset = {
+        let token = props.token.clone();
+        let password = password.clone();
+        use_async(async move {
+            reset_password(Wrapper {
+                data: ResetPasswordDto {
+                    token,
+                    password: (*password).clone(),
+                },
+            })
+                .await
+        })
+    };
+
+    let onsubmit = {
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            reset.run();
+        })
+    };
+
+    let oninput_password = {
+        let password = password.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            password.set(input.value());
+        })
+    };
+
+    html! {
+        <div class="max-w-md mx-auto mt-12 mb-12">
+            <h1 class="text-center text-xl font-semibold mb-4">{ "Choose a new password" }</h1>
+            {
+                if reset.data.is_some() {
+                    html! {
+                        <p class="text-center">
+                            { "Your password has been changed and you've been signed out everywhere. " }
+                            <span class="text-blue-500 hover:underline">
+                                <Link<AppRoute> to={AppRoute::Login}>{ "Log in" }</Link<AppRoute>>
+                            </span>
+                            { " with the new one." }
+                        </p>
+                    }
+                } else if reset.error == Some(Error::NotFound) {
+                    html! {
+                        <p class="text-center text-red-700">
+                            { "This link is invalid or has expired. " }
+                            <span class="text-blue-500 hover:underline">
+                                <Link<AppRoute> to={AppRoute::ForgotPassword}>{ "Ask for a new one" }</Link<AppRoute>>
+                            </span>
+                            { "." }
+                        </p>
+                    }
+                } else {
+                    html! {
+                        <>
+                            <ShowError error={reset.error.clone()} />
+                            <form onsubmit={ onsubmit }>
+                                <div class="mb-4">
+                                    <label for="password" class="block text-sm font-medium text-gray-700">
+                                        { "New Password" }
+                                    </label>
+                                    <input
+                                        class="mt-1 p-2 border rounded w-full"
+                                        type="password"
+                                        value={ (*password).clone() }
+                                        oninput={ oninput_password }
+                                        />
+                                    <FieldError error={reset.error.clone()} field="password" />
+                                </div>
+                                <div class="flex justify-center">
+                                    <button type="submit"
+                                        class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-300 focus:outline-none"
+                                        disabled={reset.loading}>
+                                        { "Change password" }
+                                    </button>
+                                </div>
+                            </form>
+                        </>
+                    }
+                }
+            }
+        </div>
+    }
+}
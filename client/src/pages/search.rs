@@ -0,0 +1,129 @@
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::show_error::ShowError;
+use crate::routes::AppRoute;
+use crate::services::search::{search, RESULTS_PER_PAGE};
+use crate::types::search::{SearchQuery, SearchResultDto};
+
+#[function_component(Search)]
+pub fn search_page() -> Html {
+    let location = yew_router::hooks::use_location();
+    let query = location
+        .and_then(|location| location.query::<SearchQuery>().ok())
+        .unwrap_or_default();
+    let current_page = use_state(usize::default);
+
+    let results = {
+        let query = query.clone();
+        let current_page = current_page.clone();
+        use_async(async move { search(query, *current_page).await })
+    };
+
+    {
+        let current_page = current_page.clone();
+        use_effect_with(
+            query.clone(),
+            move |_| {
+                current_page.set(0);
+                || ()
+            },
+        );
+    }
+
+    {
+        let results = results.clone();
+        use_effect_with(
+            (query.clone(), *current_page),
+            move |(query, _)| {
+                if !query.q.trim().is_empty() {
+                    results.run();
+                }
+                || ()
+            },
+        );
+    }
+
+    let on_prev = {
+        let current_page = current_page.clone();
+        Callback::from(move |_| current_page.set(current_page.saturating_sub(1)))
+    };
+
+    let on_next = {
+        let current_page = current_page.clone();
+        Callback::from(move |_| current_page.set(*current_page + 1))
+    };
+
+    html! {
+        <div class="lg:w-2/3 mx-auto mt-12 mb-12 px-4">
+            <h1 class="text-xl font-semibold mb-4">
+                { format!("Search results for \"{}\"", query.q) }
+            </h1>
+            <ShowError error={results.error.clone()} />
+            {
+                if let Some(resp) = &results.data {
+                    if resp.data.results.is_empty() {
+                        html! { <div class="text-center mt-4">{ "No matching posts" }</div> }
+                    } else {
+                        let has_next = ((*current_page + 1) * RESULTS_PER_PAGE) < resp.data.total as usize;
+                        html! {
+                            <>
+                                <p class="text-sm text-gray-500 mb-4">
+                                    { format!("{} matching posts", resp.data.total) }
+                                </p>
+                                { for resp.data.results.iter().map(view_result) }
+                                <div class="flex justify-center space-x-2 mt-4">
+                                    <button
+                                        class="px-3 h-8 border border-gray-300 text-gray-500 hover:bg-gray-100 disabled:opacity-50"
+                                        onclick={on_prev}
+                                        disabled={*current_page == 0}>
+                                        { "Previous" }
+                                    </button>
+                                    <button
+                                        class="px-3 h-8 border border-gray-300 text-gray-500 hover:bg-gray-100 disabled:opacity-50"
+                                        onclick={on_next}
+                                        disabled={!has_next}>
+                                        { "Next" }
+                                    </button>
+                                </div>
+                            </>
+                        }
+                    }
+                } else if results.loading {
+                    html! { <div class="text-center mt-4">{ "Loading" }</div> }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
+
+fn view_result(result: &SearchResultDto) -> Html {
+    let post = &result.post;
+
+    html! {
+        <div class="border-b py-4">
+            <Link<AppRoute> to={AppRoute::Post { slug: post.slug.clone() }}>
+                <h2 class="text-lg font-bold hover:underline">
+                    { Html::from_html_unchecked(result.title_highlight.clone().into()) }
+                </h2>
+            </Link<AppRoute>>
+            <p class="text-gray-700 mt-1">
+                { Html::from_html_unchecked(result.snippet.clone().into()) }
+            </p>
+            <div class="text-sm text-gray-500 mt-2">
+                <Link<AppRoute> to={AppRoute::Profile { display_name: post.author.display_name.clone() }} classes="hover:underline">
+                    { &post.author.display_name }
+                </Link<AppRoute>>
+                { format!(" · {}", post.created_at.format("%B %e, %Y")) }
+                {
+                    for post.tags.iter().map(|tag| html! {
+                        <span class="ml-2 text-indigo-400">{ format!("#{}", tag) }</span>
+                    })
+                }
+            </div>
+        </div>
+    }
+}
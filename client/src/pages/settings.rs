@@ -1,10 +1,18 @@
-use web_sys::HtmlInputElement;
+use web_sys::{File, HtmlInputElement};
 use yew::prelude::*;
 use yew_hooks::prelude::*;
 
+use crate::components::field_error::FieldError;
+use crate::components::linked_accounts::LinkedAccounts;
+use crate::components::notification_preferences::NotificationPreferences;
+use crate::components::related_user_list::{Relation, RelatedUserList};
+use crate::components::session_list::SessionList;
 use crate::components::show_error::ShowError;
+use crate::components::two_factor_settings::TwoFactorSettings;
 use crate::middlewares::context::use_user_context;
-use crate::services::user::{current, save};
+use crate::middlewares::error::Error;
+use crate::services::media::upload;
+use crate::services::user::{current, logout, logout_all, resend_verification_email, save};
 use crate::types::user::UserUpdateDto;
 use crate::types::Wrapper;
 
@@ -121,10 +129,84 @@ pub fn setting() -> Html {
         })
     };
 
-    let on_logout = {
+    let avatar_file = use_mut_ref(|| None::<File>);
+    let avatar_upload = {
+        let avatar_file = avatar_file.clone();
+        use_async(async move {
+            let file = avatar_file.borrow_mut().take();
+            match file {
+                Some(file) => upload(file).await,
+                None => Err(Error::BadRequest),
+            }
+        })
+    };
+
+    {
+        let update_dto = update_dto.clone();
+        use_effect_with(
+            avatar_upload.data.clone(),
+            move |uploaded| {
+                if let Some(media) = uploaded {
+                    let mut dto = (*update_dto).clone();
+                    dto.profile_image_url = media.data.thumbnail_url.clone();
+                    update_dto.set(dto);
+                }
+                || ()
+            },
+        );
+    }
+
+    let onchange_avatar = {
+        let avatar_upload = avatar_upload.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                *avatar_file.borrow_mut() = Some(file);
+                avatar_upload.run();
+            }
+            input.set_value("");
+        })
+    };
+
+    let resend_verification = use_async(async move { resend_verification_email().await });
+
+    let on_resend_verification = {
+        let resend_verification = resend_verification.clone();
+        Callback::from(move |_| {
+            resend_verification.run();
+        })
+    };
+
+    let logout = use_async(async move { logout().await });
+    let logout_all = use_async(async move { logout_all().await });
+
+    {
         let user_ctx = user_ctx.clone();
+        use_effect_with(
+            (logout.clone(), logout_all.clone()),
+            move |(logout, logout_all)| {
+                let finished = |state: &UseAsyncHandle<Wrapper<usize>, _>| {
+                    !state.loading && (state.data.is_some() || state.error.is_some())
+                };
+                if finished(logout) || finished(logout_all) {
+                    user_ctx.logout();
+                }
+                || ()
+            },
+        );
+    }
+
+    let on_logout = {
+        let logout = logout.clone();
+        Callback::from(move |_| {
+            logout.run();
+        })
+    };
+
+    let on_logout_all = {
+        let logout_all = logout_all.clone();
         Callback::from(move |_| {
-            user_ctx.logout();
+            logout_all.run();
         })
     };
 
@@ -133,6 +215,31 @@ pub fn setting() -> Html {
             <h1 class="text-center text-xl font-semibold">{ "My Settings" }</h1>
             <ShowError error={user.error.clone()} />
             <ShowError error={update.error.clone()} />
+            {
+                if user_ctx.is_authenticated() && !user_ctx.email_verified {
+                    html! {
+                        <div class="bg-yellow-100 border border-yellow-400 text-yellow-800 px-4 py-3 rounded mt-4 text-sm">
+                            { format!("{} isn't verified yet. Follow the link in the email we sent you. ", user_ctx.email) }
+                            {
+                                if resend_verification.data.is_some() {
+                                    html! { <span class="font-semibold">{ "A new link is on its way." }</span> }
+                                } else {
+                                    html! {
+                                        <button class="text-blue-500 hover:underline"
+                                            onclick={on_resend_verification}
+                                            disabled={resend_verification.loading}>
+                                            { "Send it again" }
+                                        </button>
+                                    }
+                                }
+                            }
+                            <ShowError error={resend_verification.error.clone()} />
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
             <form onsubmit={onsubmit} class="mt-4">
                 <div class="mb-4">
                     <label for="email" class="block text-sm font-medium text-gray-700">
@@ -145,6 +252,7 @@ pub fn setting() -> Html {
                         value={update_dto.email.clone()}
                         oninput={oninput_email}
                     />
+                    <FieldError error={update.error.clone()} field="email" />
                 </div>
                 <div class="mb-4">
                     <label for="password" class="block text-sm font-medium text-gray-700">
@@ -157,6 +265,7 @@ pub fn setting() -> Html {
                         value={(*password).clone()}
                         oninput={oninput_password}
                     />
+                    <FieldError error={update.error.clone()} field="password" />
                 </div>
                 <div class="mb-4">
                     <label for="display_name" class="block text-sm font-medium text-gray-700">
@@ -169,18 +278,43 @@ pub fn setting() -> Html {
                         value={update_dto.display_name.clone()}
                         oninput={oninput_display_name}
                     />
+                    <FieldError error={update.error.clone()} field="display_name" />
                 </div>
                 <div class="mb-4">
                     <label for="profile_image_url" class="block text-sm font-medium text-gray-700">
                         { "Profile Image" }
                     </label>
+                    <div class="mt-1 flex items-center space-x-4">
+                        {
+                            if update_dto.profile_image_url.is_empty() {
+                                html! {}
+                            } else {
+                                html! {
+                                    <img class="w-12 h-12 rounded-full object-cover" src={update_dto.profile_image_url.clone()} alt="" />
+                                }
+                            }
+                        }
+                        <label class="text-sm text-blue-500 hover:underline cursor-pointer">
+                            { if avatar_upload.loading { "Uploading..." } else { "Upload a picture" } }
+                            <input
+                                class="hidden"
+                                type="file"
+                                accept="image/jpeg,image/png,image/gif,image/webp"
+                                disabled={avatar_upload.loading}
+                                onchange={onchange_avatar}
+                            />
+                        </label>
+                    </div>
+                    <ShowError error={avatar_upload.error.clone()} />
+                    <FieldError error={avatar_upload.error.clone()} field="file" />
                     <input
                         class="mt-1 p-2 block w-full border rounded-md shadow-sm focus:ring focus:ring-indigo-300 focus:outline-none"
                         type="text"
-                        placeholder="URL to Profile Image"
+                        placeholder="Or a URL to Profile Image"
                         value={update_dto.profile_image_url.clone()}
                         oninput={oninput_profile_image_url}
                     />
+                    <FieldError error={update.error.clone()} field="profile_image_url" />
                 </div>
                 <div class="mb-4">
                     <label for="biography" class="block text-sm font-medium text-gray-700">
@@ -193,6 +327,7 @@ pub fn setting() -> Html {
                         value={update_dto.biography.clone()}
                         oninput={oninput_biography}
                     />
+                    <FieldError error={update.error.clone()} field="biography" />
                 </div>
                 <div class="flex justify-center">
                     <button class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-300 focus:outline-none"
@@ -206,9 +341,19 @@ pub fn setting() -> Html {
                     <span class="text-blue-500 hover:underline" onclick={on_logout}>
                         { "logout" }
                     </span>
+                    { " or " }
+                    <span class="text-blue-500 hover:underline" onclick={on_logout_all}>
+                        { "logout from all devices" }
+                    </span>
                     { "." }
                 </p>
             </form>
+            <NotificationPreferences />
+            <TwoFactorSettings />
+            <LinkedAccounts />
+            <SessionList />
+            <RelatedUserList relation={Relation::Blocked} />
+            <RelatedUserList relation={Relation::Muted} />
         </div>
     }
 }
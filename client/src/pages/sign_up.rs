@@ -3,6 +3,8 @@ use yew::prelude::*;
 use yew_hooks::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::field_error::FieldError;
+use crate::components::oauth_buttons::OAuthButtons;
 use crate::components::show_error::ShowError;
 use crate::middlewares::context::use_user_context;
 use crate::routes::AppRoute;
@@ -73,6 +75,7 @@ pub fn sign_up_page() -> Html {
         <div class="max-w-md mx-auto mt-12 mb-12">
             <h1 class="text-center text-xl font-semibold">{ "Sign Up" }</h1>
             <ShowError error={sign_up.error.clone()} />
+            <OAuthButtons />
             <form onsubmit={ onsubmit } class="mt-4">
                 <div class="mb-4">
                     <label for="email" class="block text-sm font-medium text-gray-700">
@@ -84,6 +87,7 @@ pub fn sign_up_page() -> Html {
                         value={ sign_up_dto.email.clone() }
                         oninput={ oninput_email }
                     />
+                    <FieldError error={sign_up.error.clone()} field="email" />
                 </div>
                 <div class="mb-4">
                     <label for="password" class="block text-sm font-medium text-gray-700">
@@ -95,6 +99,7 @@ pub fn sign_up_page() -> Html {
                         value={ sign_up_dto.password.clone() }
                         oninput={ oninput_password }
                     />
+                    <FieldError error={sign_up.error.clone()} field="password" />
                 </div>
                 <div class="mb-4">
                     <label for="display_name" class="block text-sm font-medium text-gray-700">
@@ -106,6 +111,7 @@ pub fn sign_up_page() -> Html {
                         value={ sign_up_dto.display_name.clone() }
                         oninput={ oninput_display_name }
                     />
+                    <FieldError error={sign_up.error.clone()} field="display_name" />
                 </div>
                 <div class="flex justify-center">
                     <button type="submit" class="w-full px-4 py-2 bg-indigo-600 border rounded-md text-white hover:bg-indigo-700 focus:ring focus:ring-indigo-700 focus:outline-none">
@@ -0,0 +1,70 @@
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+use crate::middlewares::context::use_user_context;
+use crate::routes::AppRoute;
+use crate::services::user::verify_email;
+use crate::types::user::{UserDto, VerifyEmailDto};
+use crate::types::Wrapper;
+
+#[derive(Properties, Clone, PartialEq, Eq)]
+pub struct Props {
+    pub token: String,
+}
+
+/// Where the link in a verification email leads; verifies straight away.
+#[function_component(VerifyEmail)]
+pub fn verify_email_page(props: &Props) -> Html {
+    let user_ctx = use_user_context();
+    let verification = {
+        let token = props.token.clone();
+        use_async_with_options(
+            async move { verify_email(Wrapper { data: VerifyEmailDto { token } }).await },
+            UseAsyncOptions::enable_auto(),
+        )
+    };
+
+    {
+        let user_ctx = user_ctx.clone();
+        use_effect_with(
+            verification.data.clone(),
+            move |verified| {
+                if let Some(verified) = verified {
+                    if user_ctx.is_authenticated() && user_ctx.email == verified.data {
+                        user_ctx.login_without_redirection(UserDto {
+                            email_verified: true,
+                            ..(*user_ctx).clone()
+                        });
+                    }
+                }
+                || ()
+            },
+        );
+    }
+
+    html! {
+        <div class="max-w-md mx-auto mt-12 mb-12 text-center">
+            <h1 class="text-xl font-semibold mb-4">{ "Email verification" }</h1>
+            {
+                if let Some(verified) = &verification.data {
+                    html! {
+                        <p>{ format!("Thanks, {} is verified.", verified.data) }</p>
+                    }
+                } else if verification.error.is_some() {
+                    html! {
+                        <p class="text-red-700">
+                            { "This link is invalid or has expired. You can ask for a new one in your " }
+                            <span class="text-blue-500 hover:underline">
+                                <Link<AppRoute> to={AppRoute::Settings}>{ "settings" }</Link<AppRoute>>
+                            </span>
+                            { "." }
+                        </p>
+                    }
+                } else {
+                    html! { <p class="text-gray-500">{ "Verifying..." }</p> }
+                }
+            }
+        </div>
+    }
+}
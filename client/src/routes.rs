@@ -1,22 +1,36 @@
+use std::collections::HashMap;
+
 use yew::prelude::*;
+use yew_router::history::{AnyHistory, BrowserHistory, History, MemoryHistory};
 use yew_router::prelude::*;
 
 use crate::components::{
     footer::Footer,
     header::Header,
+    post_list::PostListFilter,
     user_context_provider::UserContextProvider,
 };
+use crate::middlewares::prefetch::{PrefetchedContext, PrefetchedResponses};
 use crate::pages::{
+    admin::Admin,
+    forgot_password::ForgotPassword,
     health::Healthcheck,
     home::Home,
     login::Login,
+    moderation::Moderation,
+    oauth_callback::OAuthCallback,
     post::Post,
+    post_history::PostHistory,
     profile::{Profile, ProfileTab},
+    reset_password::ResetPassword,
+    search::Search,
     settings::Settings,
     sign_up::SignUp,
-    users::Users
+    users::Users,
+    verify_email::VerifyEmail,
 };
 use crate::pages::editor::Editor;
+use crate::services::{post, profile, tag};
 
 #[derive(Routable, Debug, Clone, PartialEq, Eq)]
 pub enum AppRoute {
@@ -30,23 +44,61 @@ pub enum AppRoute {
     SignUp,
     #[at("/users/login")]
     Login,
+    #[at("/users/verify_email/:token")]
+    VerifyEmail { token: String },
+    #[at("/users/forgot_password")]
+    ForgotPassword,
+    #[at("/users/reset_password/:token")]
+    ResetPassword { token: String },
+    #[at("/users/oauth/:provider/callback")]
+    OAuthCallback { provider: String },
     #[at("/settings")]
     Settings,
+    #[at("/admin")]
+    Admin,
+    #[at("/moderation")]
+    Moderation,
     #[at("/editor")]
     NewPost,
     #[at("/editor/:post_id")]
     EditPost { post_id: String },
-    #[at("/posts/:post_id")]
-    Post { post_id: String },
+    #[at("/posts/:slug")]
+    Post { slug: String },
+    #[at("/history/:post_id")]
+    PostHistory { post_id: String },
+    #[at("/search")]
+    Search,
     #[at("/profile/:display_name")]
     Profile { display_name: String },
     #[at("/profile/:display_name/favorite")]
     ProfileFavorite { display_name: String },
+    #[at("/profile/:display_name/drafts")]
+    ProfileDrafts { display_name: String },
     #[not_found]
     #[at("/404")]
     NotFound,
 }
 
+impl AppRoute {
+    /// The API requests the route's page makes as it first renders, which the server makes itself
+    /// when rendering the page so that it arrives with its content.
+    pub fn prefetch_urls(&self) -> Vec<String> {
+        match self {
+            AppRoute::Home => vec![PostListFilter::All.url(None), tag::get_url()],
+            AppRoute::Post { slug } => vec![post::get_by_slug_url(slug)],
+            AppRoute::Profile { display_name } => vec![
+                profile::get_url(display_name),
+                PostListFilter::ByAuthor(display_name.clone()).url(None),
+            ],
+            AppRoute::ProfileFavorite { display_name } => vec![
+                profile::get_url(display_name),
+                PostListFilter::FavoritedBy(display_name.clone()).url(None),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
 fn switch(route: AppRoute) -> Html {
     match route {
         AppRoute::Home => html! { <Home /> },
@@ -54,32 +106,70 @@ fn switch(route: AppRoute) -> Html {
         AppRoute::Users => html! { <Users /> },
         AppRoute::SignUp => html! { <SignUp /> },
         AppRoute::Login => html! { <Login /> },
+        AppRoute::VerifyEmail { token } => html! { <VerifyEmail token={token} /> },
+        AppRoute::ForgotPassword => html! { <ForgotPassword /> },
+        AppRoute::ResetPassword { token } => html! { <ResetPassword token={token} /> },
+        AppRoute::OAuthCallback { provider } => html! { <OAuthCallback provider={provider} /> },
         AppRoute::Settings => html! { <Settings /> },
+        AppRoute::Admin => html! { <Admin /> },
+        AppRoute::Moderation => html! { <Moderation /> },
         AppRoute::NewPost => {
             let post_id:Option<String> = None;
             html! { <Editor post_id={post_id}/> }
         },
         AppRoute::EditPost { post_id } => html! { <Editor post_id={post_id} /> },
-        AppRoute::Post { post_id } => html! { <Post post_id={post_id} /> },
+        AppRoute::Post { slug } => html! { <Post slug={slug} /> },
+        AppRoute::PostHistory { post_id } => html! { <PostHistory post_id={post_id} /> },
+        AppRoute::Search => html! { <Search /> },
         AppRoute::Profile { display_name } => html! {
             <Profile display_name={display_name} tab={ProfileTab::ByAuthor} />
         },
         AppRoute::ProfileFavorite { display_name } => html! {
             <Profile display_name={display_name} tab={ProfileTab::FavoritedBy} />
         },
+        AppRoute::ProfileDrafts { display_name } => html! {
+            <Profile display_name={display_name} tab={ProfileTab::Drafts} />
+        },
         AppRoute::NotFound => html! { <p class="text-red-500">{ "Page not found" }</p> },
     }
 }
 
+#[derive(Properties, Clone, PartialEq, Default)]
+pub struct AppProps {
+    /// The path to render when rendering on the server; in the browser, the app follows the
+    /// address bar.
+    #[prop_or_default]
+    pub path: Option<String>,
+    #[prop_or_default]
+    pub query: HashMap<String, String>,
+    /// Responses the server fetched for the page, for it to render with rather than fetch.
+    #[prop_or_default]
+    pub prefetched: PrefetchedResponses,
+}
+
 #[function_component(App)]
-pub fn app() -> Html {
+pub fn app(props: &AppProps) -> Html {
+    let history = use_state(|| match &props.path {
+        Some(path) => {
+            let history = MemoryHistory::new();
+            history
+                .replace_with_query(path, &props.query)
+                .expect("a query map always serializes");
+            AnyHistory::from(history)
+        }
+        None => AnyHistory::from(BrowserHistory::new()),
+    });
+    let prefetched = use_state(|| PrefetchedContext::new(props.prefetched.clone()));
+
     html! {
-        <BrowserRouter>
-            <UserContextProvider>
-                <Header />
-                <Switch<AppRoute> render={switch} />
-                <Footer />
-            </UserContextProvider>
-        </BrowserRouter>
+        <Router history={(*history).clone()}>
+            <ContextProvider<PrefetchedContext> context={(*prefetched).clone()}>
+                <UserContextProvider>
+                    <Header />
+                    <Switch<AppRoute> render={switch} />
+                    <Footer />
+                </UserContextProvider>
+            </ContextProvider<PrefetchedContext>>
+        </Router>
     }
 }
@@ -1,6 +1,7 @@
 use crate::middlewares::error::Error;
-use crate::middlewares::request::{request_delete, request_get, request_post};
-use crate::types::comment::{CommentDto, CommentsDto, NewCommentDto};
+use crate::middlewares::pagination::limit;
+use crate::middlewares::request::{request_delete, request_get, request_post, request_put};
+use crate::types::comment::{CommentDto, CommentRevisionsDto, CommentsDto, NewCommentDto, UpdateCommentDto};
 use crate::types::Wrapper;
 
 pub async fn create(post_id: String, comment: Wrapper<NewCommentDto>) -> Result<Wrapper<CommentDto>, Error> {
@@ -11,12 +12,55 @@ pub async fn create(post_id: String, comment: Wrapper<NewCommentDto>) -> Result<
         .await
 }
 
+pub async fn reply(
+    post_id: String,
+    comment_id: String,
+    comment: Wrapper<NewCommentDto>,
+) -> Result<Wrapper<CommentDto>, Error> {
+    request_post::<Wrapper<NewCommentDto>, Wrapper<CommentDto>>(
+        format!("/posts/{}/comments/{}/replies", post_id, comment_id),
+        comment,
+    )
+        .await
+}
+
+pub async fn update(
+    post_id: String,
+    comment_id: String,
+    comment: Wrapper<UpdateCommentDto>,
+) -> Result<Wrapper<CommentDto>, Error> {
+    request_put::<Wrapper<UpdateCommentDto>, Wrapper<CommentDto>>(
+        format!("/posts/{}/comments/{}", post_id, comment_id),
+        comment,
+    )
+        .await
+}
+
 pub async fn delete(post_id: String, comment_id: String) -> Result<Wrapper<String>, Error> {
     request_delete::<Wrapper<String>>(format!("/posts/{}/comments/{}", post_id, comment_id))
         .await
 }
 
-pub async fn get(post_id: String) -> Result<Wrapper<CommentsDto>, Error> {
-    request_get::<Wrapper<CommentsDto>>(format!("/posts/{}/comments", post_id))
+pub async fn get(post_id: String, cursor: Option<String>) -> Result<Wrapper<CommentsDto>, Error> {
+    request_get::<Wrapper<CommentsDto>>(format!("/posts/{}/comments?{}", post_id, limit(10, cursor.as_deref())))
+        .await
+}
+
+pub async fn replies(
+    post_id: String,
+    comment_id: String,
+    cursor: Option<String>,
+) -> Result<Wrapper<CommentsDto>, Error> {
+    request_get::<Wrapper<CommentsDto>>(format!(
+        "/posts/{}/comments/{}/replies?{}",
+        post_id,
+        comment_id,
+        limit(10, cursor.as_deref()),
+    ))
+        .await
+}
+
+pub async fn revisions(post_id: String, comment_id: String) -> Result<Wrapper<CommentRevisionsDto>, Error> {
+    request_get::<Wrapper<CommentRevisionsDto>>(format!("/posts/{}/comments/{}/revisions", post_id, comment_id))
         .await
 }
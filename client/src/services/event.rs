@@ -0,0 +1,86 @@
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use gloo::timers::future::TimeoutFuture;
+use gloo_net::eventsource::futures::EventSource;
+use wasm_bindgen_futures::spawn_local;
+use yew::Callback;
+
+use crate::middlewares::request::{request_post, API_ROOT};
+use crate::types::event::{LiveEvent, StreamTicketDto};
+use crate::types::Wrapper;
+
+/// Delay before reopening a stream the server closed, doubled after each failure in a row.
+const RETRY_DELAY_MS: u32 = 2_000;
+const MAX_RETRY_DELAY_MS: u32 = 60_000;
+
+/// Streams new comments and favorite counts for a post to `callback` until the handle is aborted.
+pub fn subscribe_post(post_id: String, callback: Callback<LiveEvent>) -> AbortHandle {
+    spawn_stream(async move {
+        let mut delay = RETRY_DELAY_MS;
+        loop {
+            if listen(&format!("{}/posts/{}/events", API_ROOT, post_id), &callback).await {
+                delay = RETRY_DELAY_MS;
+            }
+            TimeoutFuture::new(delay).await;
+            delay = (delay * 2).min(MAX_RETRY_DELAY_MS);
+        }
+    })
+}
+
+/// Streams the current user's unread notification count to `callback` until the handle is aborted.
+pub fn subscribe_notifications(callback: Callback<LiveEvent>) -> AbortHandle {
+    spawn_stream(async move {
+        let mut delay = RETRY_DELAY_MS;
+        loop {
+            // Tickets work once, so every connection needs a fresh one.
+            if let Ok(ticket) = stream_ticket().await {
+                let url = format!("{}/notifications/events?ticket={}", API_ROOT, ticket.data.ticket);
+                if listen(&url, &callback).await {
+                    delay = RETRY_DELAY_MS;
+                }
+            }
+            TimeoutFuture::new(delay).await;
+            delay = (delay * 2).min(MAX_RETRY_DELAY_MS);
+        }
+    })
+}
+
+async fn stream_ticket() -> Result<Wrapper<StreamTicketDto>, crate::middlewares::error::Error> {
+    request_post::<(), Wrapper<StreamTicketDto>>("/notifications/stream-ticket".to_string(), ()).await
+}
+
+fn spawn_stream(stream: impl std::future::Future<Output = ()> + 'static) -> AbortHandle {
+    let (handle, registration) = AbortHandle::new_pair();
+    spawn_local(async move {
+        let _ = Abortable::new(stream, registration).await;
+    });
+    handle
+}
+
+/// Emits the events sent on `url` until the connection is closed for good. Returns whether any
+/// event arrived, so a stream that keeps failing straight away backs off.
+async fn listen(url: &str, callback: &Callback<LiveEvent>) -> bool {
+    let mut source = match EventSource::new(url) {
+        Ok(source) => source,
+        Err(_) => return false,
+    };
+    let mut messages = match source.subscribe("message") {
+        Ok(messages) => messages,
+        Err(_) => return false,
+    };
+
+    let mut received = false;
+    while let Some(Ok((_, message))) = messages.next().await {
+        let event = message
+            .data()
+            .as_string()
+            .and_then(|data| serde_json::from_str::<LiveEvent>(&data).ok());
+        if let Some(event) = event {
+            received = true;
+            callback.emit(event);
+        }
+    }
+    source.close();
+
+    received
+}
@@ -0,0 +1,21 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::js_sys::Uint8Array;
+use web_sys::File;
+
+use crate::middlewares::error::Error;
+use crate::middlewares::request::request_upload;
+use crate::types::media::MediaDto;
+use crate::types::Wrapper;
+
+/// Uploads an image picked, dropped or pasted by the user.
+pub async fn upload(file: File) -> Result<Wrapper<MediaDto>, Error> {
+    let buffer = JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|_| Error::BadRequest)?;
+
+    request_upload::<Wrapper<MediaDto>>(
+        "/media".to_string(),
+        file.name(),
+        Uint8Array::new(&buffer).to_vec(),
+    ).await
+}
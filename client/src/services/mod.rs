@@ -1,5 +1,13 @@
 pub mod comment;
+pub mod event;
+pub mod media;
+pub mod moderation;
+pub mod notification;
+pub mod oauth;
 pub mod post;
 pub mod profile;
+pub mod report;
+pub mod search;
 pub mod user;
 pub mod tag;
+pub mod two_factor;
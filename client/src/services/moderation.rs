@@ -0,0 +1,88 @@
+use crate::middlewares::error::Error;
+use crate::middlewares::pagination::limit;
+use crate::middlewares::request::{request_get, request_post, request_put};
+use crate::types::moderation::{AuditLogDto, ModerationDto, ResolveReportsDto, RoleUpdateDto, UserRoleDto};
+use crate::types::report::{ReportQueueDto, ReportTarget};
+use crate::types::Wrapper;
+
+/// What a moderation action applies to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModerationTarget {
+    Post(String),
+    Comment(String),
+}
+
+impl ModerationTarget {
+    fn path(&self) -> String {
+        match self {
+            ModerationTarget::Post(id) => format!("/moderation/posts/{}", id),
+            ModerationTarget::Comment(id) => format!("/moderation/comments/{}", id),
+        }
+    }
+}
+
+async fn moderate(target: ModerationTarget, action: &str, reason: String) -> Result<Wrapper<String>, Error> {
+    request_post::<Wrapper<ModerationDto>, Wrapper<String>>(
+        format!("{}/{}", target.path(), action),
+        Wrapper { data: ModerationDto { reason } },
+    )
+        .await
+}
+
+pub async fn hide(target: ModerationTarget, reason: String) -> Result<Wrapper<String>, Error> {
+    moderate(target, "hide", reason).await
+}
+
+pub async fn unhide(target: ModerationTarget, reason: String) -> Result<Wrapper<String>, Error> {
+    moderate(target, "unhide", reason).await
+}
+
+pub async fn remove(target: ModerationTarget, reason: String) -> Result<Wrapper<String>, Error> {
+    moderate(target, "delete", reason).await
+}
+
+pub async fn report_queue(cursor: Option<String>) -> Result<Wrapper<ReportQueueDto>, Error> {
+    request_get::<Wrapper<ReportQueueDto>>(format!("/moderation/reports?{}", limit(10, cursor.as_deref())))
+        .await
+}
+
+fn reports_path(target: ReportTarget, target_id: &str, action: &str) -> String {
+    let target = match target {
+        ReportTarget::Post => "post",
+        ReportTarget::Comment => "comment",
+    };
+    format!("/moderation/reports/{}/{}/{}", target, target_id, action)
+}
+
+pub async fn resolve_reports(
+    target: ReportTarget,
+    target_id: String,
+    resolution: ResolveReportsDto,
+) -> Result<Wrapper<String>, Error> {
+    request_post::<Wrapper<ResolveReportsDto>, Wrapper<String>>(
+        reports_path(target, &target_id, "resolve"),
+        Wrapper { data: resolution },
+    )
+        .await
+}
+
+pub async fn dismiss_reports(target: ReportTarget, target_id: String, reason: String) -> Result<Wrapper<String>, Error> {
+    request_post::<Wrapper<ModerationDto>, Wrapper<String>>(
+        reports_path(target, &target_id, "dismiss"),
+        Wrapper { data: ModerationDto { reason } },
+    )
+        .await
+}
+
+pub async fn change_role(display_name: String, update: Wrapper<RoleUpdateDto>) -> Result<Wrapper<UserRoleDto>, Error> {
+    request_put::<Wrapper<RoleUpdateDto>, Wrapper<UserRoleDto>>(
+        format!("/admin/users/{}/role", display_name),
+        update,
+    )
+        .await
+}
+
+pub async fn audit_log(cursor: Option<String>) -> Result<Wrapper<AuditLogDto>, Error> {
+    request_get::<Wrapper<AuditLogDto>>(format!("/admin/audit-log?{}", limit(20, cursor.as_deref())))
+        .await
+}
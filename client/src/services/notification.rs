@@ -0,0 +1,35 @@
+use crate::middlewares::error::Error;
+use crate::middlewares::request::{request_get, request_post, request_put};
+use crate::types::notification::{NotificationPreferencesDto, NotificationsDto, UnreadCountDto};
+use crate::types::Wrapper;
+
+/// The latest notifications, for the header dropdown.
+pub async fn latest() -> Result<Wrapper<NotificationsDto>, Error> {
+    request_get::<Wrapper<NotificationsDto>>("/notifications?limit=10".to_string()).await
+}
+
+pub async fn unread_count() -> Result<Wrapper<UnreadCountDto>, Error> {
+    request_get::<Wrapper<UnreadCountDto>>("/notifications/unread-count".to_string()).await
+}
+
+pub async fn mark_read(notification_id: String) -> Result<Wrapper<UnreadCountDto>, Error> {
+    request_post::<(), Wrapper<UnreadCountDto>>(format!("/notifications/{}/read", notification_id), ()).await
+}
+
+pub async fn mark_all_read() -> Result<Wrapper<UnreadCountDto>, Error> {
+    request_post::<(), Wrapper<UnreadCountDto>>("/notifications/read".to_string(), ()).await
+}
+
+pub async fn preferences() -> Result<Wrapper<NotificationPreferencesDto>, Error> {
+    request_get::<Wrapper<NotificationPreferencesDto>>("/notifications/preferences".to_string()).await
+}
+
+pub async fn save_preferences(
+    preferences: Wrapper<NotificationPreferencesDto>,
+) -> Result<Wrapper<NotificationPreferencesDto>, Error> {
+    request_put::<Wrapper<NotificationPreferencesDto>, Wrapper<NotificationPreferencesDto>>(
+        "/notifications/preferences".to_string(),
+        preferences,
+    )
+        .await
+}
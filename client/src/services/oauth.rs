@@ -0,0 +1,49 @@
+use crate::middlewares::error::Error;
+use crate::middlewares::request::{request_delete, request_get, request_post};
+use crate::types::oauth::{
+    AuthorizationUrlDto, AuthorizeDto, IdentitiesDto, OAuthCallbackDto, OAuthCallbackOutcomeDto, OAuthProviderDto,
+};
+use crate::types::Wrapper;
+
+pub async fn providers() -> Result<Wrapper<Vec<OAuthProviderDto>>, Error> {
+    request_get::<Wrapper<Vec<OAuthProviderDto>>>(
+        "/oauth/providers".to_string()
+    ).await
+}
+
+/// The provider URL to send the user to. With `link`, the provider is linked to the signed-in user
+/// instead of used to sign in.
+pub async fn authorize(provider: String, link: bool) -> Result<Wrapper<AuthorizationUrlDto>, Error> {
+    request_post::<Wrapper<AuthorizeDto>, Wrapper<AuthorizationUrlDto>>(
+        format!("/oauth/{}/authorize", provider),
+        Wrapper { data: AuthorizeDto { link } }
+    ).await
+}
+
+pub async fn callback(provider: String, dto: Wrapper<OAuthCallbackDto>) -> Result<Wrapper<OAuthCallbackOutcomeDto>, Error> {
+    request_post::<Wrapper<OAuthCallbackDto>, Wrapper<OAuthCallbackOutcomeDto>>(
+        format!("/oauth/{}/callback", provider),
+        dto
+    ).await
+}
+
+pub async fn identities() -> Result<Wrapper<IdentitiesDto>, Error> {
+    request_get::<Wrapper<IdentitiesDto>>(
+        "/oauth/identities".to_string()
+    ).await
+}
+
+pub async fn unlink(provider: String) -> Result<Wrapper<IdentitiesDto>, Error> {
+    request_delete::<Wrapper<IdentitiesDto>>(
+        format!("/oauth/identities/{}", provider)
+    ).await
+}
+
+/// Sends the browser to the provider; it comes back to the OAuth callback page.
+pub async fn redirect_to_provider(provider: String, link: bool) -> Result<(), Error> {
+    let authorization = authorize(provider, link).await?;
+    let _ = gloo::utils::window()
+        .location()
+        .set_href(&authorization.data.authorization_url);
+    Ok(())
+}
@@ -1,27 +1,62 @@
 use crate::middlewares::error::Error;
 use crate::middlewares::pagination::limit;
 use crate::middlewares::request::{request_delete, request_get, request_post, request_put};
-use crate::types::post::{PostDto, PostsDto, PostUpdateDto};
+use crate::types::post::{PostDiffDto, PostDto, PostRevisionsDto, PostStatus, PostsDto, PostUpdateDto};
 use crate::types::Wrapper;
 
-pub async fn all(page: usize) -> Result<Wrapper<PostsDto>, Error> {
-    request_get::<Wrapper<PostsDto>>(format!("/posts?{}", limit(10, page))).await
+pub fn all_url(cursor: Option<&str>) -> String {
+    format!("/posts?{}", limit(10, cursor))
 }
 
-pub async fn by_author(author: String, page: usize) -> Result<Wrapper<PostsDto>, Error> {
-    request_get::<Wrapper<PostsDto>>(format!("/posts?author={}&{}", author, limit(10, page))).await
+pub async fn all(cursor: Option<String>) -> Result<Wrapper<PostsDto>, Error> {
+    request_get::<Wrapper<PostsDto>>(all_url(cursor.as_deref())).await
 }
 
-pub async fn by_tag(tag: String, page: usize) -> Result<Wrapper<PostsDto>, Error> {
-    request_get::<Wrapper<PostsDto>>(format!("/posts?tag={}&{}", tag, limit(10, page))).await
+pub fn by_author_url(author: &str, cursor: Option<&str>) -> String {
+    format!("/posts?author={}&{}", author, limit(10, cursor))
 }
 
-pub async fn feed() -> Result<Wrapper<PostsDto>, Error> {
-    request_get::<Wrapper<PostsDto>>(format!("/posts/feed?{}", limit(10, 0))).await
+pub async fn by_author(author: String, cursor: Option<String>) -> Result<Wrapper<PostsDto>, Error> {
+    request_get::<Wrapper<PostsDto>>(by_author_url(&author, cursor.as_deref())).await
 }
 
-pub async fn favorited_by(author: String, page: usize) -> Result<Wrapper<PostsDto>, Error> {
-    request_get::<Wrapper<PostsDto>>(format!("/posts?favorited={}&{}", author, limit(10, page))).await
+pub fn by_status_url(author: &str, status: PostStatus, cursor: Option<&str>) -> String {
+    let status = match status {
+        PostStatus::Draft => "draft",
+        PostStatus::Scheduled => "scheduled",
+        PostStatus::Published => "published",
+        PostStatus::Archived => "archived",
+    };
+    format!("/posts?author={}&status={}&{}", author, status, limit(10, cursor))
+}
+
+/// The author's own posts that are not published; only the author gets any back.
+pub async fn by_status(author: String, status: PostStatus, cursor: Option<String>) -> Result<Wrapper<PostsDto>, Error> {
+    request_get::<Wrapper<PostsDto>>(by_status_url(&author, status, cursor.as_deref())).await
+}
+
+pub fn by_tag_url(tag: &str, cursor: Option<&str>) -> String {
+    format!("/posts?tag={}&{}", tag, limit(10, cursor))
+}
+
+pub async fn by_tag(tag: String, cursor: Option<String>) -> Result<Wrapper<PostsDto>, Error> {
+    request_get::<Wrapper<PostsDto>>(by_tag_url(&tag, cursor.as_deref())).await
+}
+
+pub fn feed_url(cursor: Option<&str>) -> String {
+    format!("/posts/feed?{}", limit(10, cursor))
+}
+
+pub async fn feed(cursor: Option<String>) -> Result<Wrapper<PostsDto>, Error> {
+    request_get::<Wrapper<PostsDto>>(feed_url(cursor.as_deref())).await
+}
+
+pub fn favorited_by_url(author: &str, cursor: Option<&str>) -> String {
+    format!("/posts?favorited={}&{}", author, limit(10, cursor))
+}
+
+pub async fn favorited_by(author: String, cursor: Option<String>) -> Result<Wrapper<PostsDto>, Error> {
+    request_get::<Wrapper<PostsDto>>(favorited_by_url(&author, cursor.as_deref())).await
 }
 
 pub async fn favorite(post_id: String) -> Result<Wrapper<PostDto>, Error> {
@@ -36,6 +71,14 @@ pub async fn get(post_id: String) -> Result<Wrapper<PostDto>, Error> {
     request_get::<Wrapper<PostDto>>(format!("/posts/{}", post_id)).await
 }
 
+pub fn get_by_slug_url(slug: &str) -> String {
+    format!("/posts/by-slug/{}", slug)
+}
+
+pub async fn get_by_slug(slug: String) -> Result<Wrapper<PostDto>, Error> {
+    request_get::<Wrapper<PostDto>>(get_by_slug_url(&slug)).await
+}
+
 pub async fn create(post: Wrapper<PostUpdateDto>) -> Result<Wrapper<PostDto>, Error> {
     request_post::<Wrapper<PostUpdateDto>, Wrapper<PostDto>>(
         "/posts".to_string(),
@@ -55,3 +98,23 @@ pub async fn update(post_id: String, post: Wrapper<PostUpdateDto>) -> Result<Wra
 pub async fn del(post_id: String) -> Result<Wrapper<String>, Error> {
     request_delete::<Wrapper<String>>(format!("/posts/{}", post_id)).await
 }
+
+pub async fn revisions(post_id: String) -> Result<Wrapper<PostRevisionsDto>, Error> {
+    request_get::<Wrapper<PostRevisionsDto>>(format!("/posts/{}/revisions", post_id)).await
+}
+
+/// Diffs two versions of a post, where `None` stands for the current version.
+pub async fn diff(post_id: String, from: Option<String>, to: Option<String>) -> Result<Wrapper<PostDiffDto>, Error> {
+    let mut params = Vec::new();
+    if let Some(from) = from {
+        params.push(format!("from={}", from));
+    }
+    if let Some(to) = to {
+        params.push(format!("to={}", to));
+    }
+    request_get::<Wrapper<PostDiffDto>>(format!("/posts/{}/revisions/diff?{}", post_id, params.join("&"))).await
+}
+
+pub async fn restore(post_id: String, revision_id: String) -> Result<Wrapper<PostDto>, Error> {
+    request_post::<(), Wrapper<PostDto>>(format!("/posts/{}/revisions/{}/restore", post_id, revision_id), ()).await
+}
@@ -1,9 +1,14 @@
 use crate::middlewares::error::Error;
 use crate::middlewares::request::{request_delete, request_get, request_post};
-use crate::types::profile::ProfileDtoWrapper;
+use crate::types::profile::{ProfileDtoWrapper, RelatedUsersDto};
+use crate::types::Wrapper;
+
+pub fn get_url(display_name: &str) -> String {
+    format!("/profile/{}", display_name)
+}
 
 pub async fn get(display_name: String) -> Result<ProfileDtoWrapper, Error> {
-    request_get::<ProfileDtoWrapper>(format!("/profile/{}", display_name)).await
+    request_get::<ProfileDtoWrapper>(get_url(&display_name)).await
 }
 
 pub async fn follow(display_name: String) -> Result<ProfileDtoWrapper, Error> {
@@ -13,3 +18,27 @@ pub async fn follow(display_name: String) -> Result<ProfileDtoWrapper, Error> {
 pub async fn unfollow(display_name: String) -> Result<ProfileDtoWrapper, Error> {
     request_delete::<ProfileDtoWrapper>(format!("/profile/{}/follow", display_name)).await
 }
+
+pub async fn block(display_name: String) -> Result<ProfileDtoWrapper, Error> {
+    request_post::<(), ProfileDtoWrapper>(format!("/profile/{}/block", display_name), ()).await
+}
+
+pub async fn unblock(display_name: String) -> Result<ProfileDtoWrapper, Error> {
+    request_delete::<ProfileDtoWrapper>(format!("/profile/{}/block", display_name)).await
+}
+
+pub async fn mute(display_name: String) -> Result<ProfileDtoWrapper, Error> {
+    request_post::<(), ProfileDtoWrapper>(format!("/profile/{}/mute", display_name), ()).await
+}
+
+pub async fn unmute(display_name: String) -> Result<ProfileDtoWrapper, Error> {
+    request_delete::<ProfileDtoWrapper>(format!("/profile/{}/mute", display_name)).await
+}
+
+pub async fn blocked_users() -> Result<Wrapper<RelatedUsersDto>, Error> {
+    request_get::<Wrapper<RelatedUsersDto>>("/user/blocks".to_string()).await
+}
+
+pub async fn muted_users() -> Result<Wrapper<RelatedUsersDto>, Error> {
+    request_get::<Wrapper<RelatedUsersDto>>("/user/mutes".to_string()).await
+}
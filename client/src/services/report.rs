@@ -0,0 +1,21 @@
+use crate::middlewares::error::Error;
+use crate::middlewares::request::request_post;
+use crate::types::report::NewReportDto;
+use crate::types::Wrapper;
+
+pub async fn report_post(post_id: String, report: Wrapper<NewReportDto>) -> Result<Wrapper<String>, Error> {
+    request_post::<Wrapper<NewReportDto>, Wrapper<String>>(format!("/posts/{}/report", post_id), report)
+        .await
+}
+
+pub async fn report_comment(
+    post_id: String,
+    comment_id: String,
+    report: Wrapper<NewReportDto>,
+) -> Result<Wrapper<String>, Error> {
+    request_post::<Wrapper<NewReportDto>, Wrapper<String>>(
+        format!("/posts/{}/comments/{}/report", post_id, comment_id),
+        report,
+    )
+        .await
+}
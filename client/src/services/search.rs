@@ -0,0 +1,25 @@
+use web_sys::js_sys::encode_uri_component;
+
+use crate::middlewares::error::Error;
+use crate::middlewares::request::request_get;
+use crate::types::search::{SearchQuery, SearchResultsDto};
+use crate::types::Wrapper;
+
+pub const RESULTS_PER_PAGE: usize = 10;
+
+pub async fn search(query: SearchQuery, page: usize) -> Result<Wrapper<SearchResultsDto>, Error> {
+    let mut url = format!(
+        "/search?q={}&limit={}&offset={}",
+        String::from(encode_uri_component(&query.q)),
+        RESULTS_PER_PAGE,
+        page * RESULTS_PER_PAGE,
+    );
+    if let Some(tag) = &query.tag {
+        url.push_str(&format!("&tag={}", String::from(encode_uri_component(tag))));
+    }
+    if let Some(author) = &query.author {
+        url.push_str(&format!("&author={}", String::from(encode_uri_component(author))));
+    }
+
+    request_get::<Wrapper<SearchResultsDto>>(url).await
+}
@@ -8,6 +8,10 @@ use crate::types::Wrapper;
 pub struct TagsDto {
     pub tags: Vec<String>,
 }
+pub fn get_url() -> String {
+    "/tags".to_string()
+}
+
 pub async fn get() -> Result<Wrapper<TagsDto>, Error> {
-    request_get::<Wrapper<TagsDto>>("/tags".to_string()).await
+    request_get::<Wrapper<TagsDto>>(get_url()).await
 }
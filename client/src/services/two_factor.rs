@@ -0,0 +1,49 @@
+use crate::middlewares::error::Error;
+use crate::middlewares::request::{request_get, request_post};
+use crate::types::two_factor::{
+    RecoveryCodesDto, TwoFactorCodeDto, TwoFactorEnrollmentDto, TwoFactorLoginDto, TwoFactorStatusDto,
+};
+use crate::types::user::UserDto;
+use crate::types::Wrapper;
+
+/// The second sign-in step, after a password or provider sign-in asked for a code.
+pub async fn login_two_factor(dto: Wrapper<TwoFactorLoginDto>) -> Result<Wrapper<UserDto>, Error> {
+    request_post::<Wrapper<TwoFactorLoginDto>, Wrapper<UserDto>>(
+        "/user/login/two_factor".to_string(),
+        dto
+    ).await
+}
+
+pub async fn status() -> Result<Wrapper<TwoFactorStatusDto>, Error> {
+    request_get::<Wrapper<TwoFactorStatusDto>>(
+        "/user/two_factor".to_string()
+    ).await
+}
+
+pub async fn enroll() -> Result<Wrapper<TwoFactorEnrollmentDto>, Error> {
+    request_post::<(), Wrapper<TwoFactorEnrollmentDto>>(
+        "/user/two_factor/enroll".to_string(),
+        ()
+    ).await
+}
+
+pub async fn confirm(dto: Wrapper<TwoFactorCodeDto>) -> Result<Wrapper<RecoveryCodesDto>, Error> {
+    request_post::<Wrapper<TwoFactorCodeDto>, Wrapper<RecoveryCodesDto>>(
+        "/user/two_factor/confirm".to_string(),
+        dto
+    ).await
+}
+
+pub async fn disable(dto: Wrapper<TwoFactorCodeDto>) -> Result<Wrapper<TwoFactorStatusDto>, Error> {
+    request_post::<Wrapper<TwoFactorCodeDto>, Wrapper<TwoFactorStatusDto>>(
+        "/user/two_factor/disable".to_string(),
+        dto
+    ).await
+}
+
+pub async fn regenerate_recovery_codes(dto: Wrapper<TwoFactorCodeDto>) -> Result<Wrapper<RecoveryCodesDto>, Error> {
+    request_post::<Wrapper<TwoFactorCodeDto>, Wrapper<RecoveryCodesDto>>(
+        "/user/two_factor/recovery_codes".to_string(),
+        dto
+    ).await
+}
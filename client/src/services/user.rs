@@ -1,6 +1,9 @@
 use crate::middlewares::error::Error;
-use crate::middlewares::request::{request_get, request_post, request_put};
-use crate::types::user::{LoginDto, SignUpDto, UserDto, UserUpdateDto};
+use crate::middlewares::request::{request_delete, request_get, request_post, request_put};
+use crate::types::user::{
+    ForgotPasswordDto, LoginDto, ResetPasswordDto, SessionsDto, SignUpDto, UserDto, UserUpdateDto, VerifyEmailDto,
+};
+use crate::types::two_factor::LoginResultDto;
 use crate::types::Wrapper;
 
 pub async fn current() -> Result<Wrapper<UserDto>, Error> {
@@ -9,8 +12,8 @@ pub async fn current() -> Result<Wrapper<UserDto>, Error> {
     ).await
 }
 
-pub async fn login(dto: Wrapper<LoginDto>) -> Result<Wrapper<UserDto>, Error> {
-    request_post::<Wrapper<LoginDto>, Wrapper<UserDto>>(
+pub async fn login(dto: Wrapper<LoginDto>) -> Result<Wrapper<LoginResultDto>, Error> {
+    request_post::<Wrapper<LoginDto>, Wrapper<LoginResultDto>>(
         "/user/login".to_string(),
         dto
     ).await
@@ -29,3 +32,58 @@ pub async fn save(dto: Wrapper<UserUpdateDto>) -> Result<Wrapper<UserDto>, Error
         dto
     ).await
 }
+
+pub async fn logout() -> Result<Wrapper<usize>, Error> {
+    request_post::<(), Wrapper<usize>>(
+        "/user/logout".to_string(),
+        ()
+    ).await
+}
+
+pub async fn logout_all() -> Result<Wrapper<usize>, Error> {
+    request_post::<(), Wrapper<usize>>(
+        "/user/logout_all".to_string(),
+        ()
+    ).await
+}
+
+pub async fn sessions() -> Result<Wrapper<SessionsDto>, Error> {
+    request_get::<Wrapper<SessionsDto>>(
+        "/user/sessions".to_string()
+    ).await
+}
+
+pub async fn revoke_session(session_id: String) -> Result<Wrapper<String>, Error> {
+    request_delete::<Wrapper<String>>(
+        format!("/user/sessions/{}", session_id)
+    ).await
+}
+
+/// Returns the address that was verified.
+pub async fn verify_email(dto: Wrapper<VerifyEmailDto>) -> Result<Wrapper<String>, Error> {
+    request_post::<Wrapper<VerifyEmailDto>, Wrapper<String>>(
+        "/user/verify_email".to_string(),
+        dto
+    ).await
+}
+
+pub async fn resend_verification_email() -> Result<Wrapper<()>, Error> {
+    request_post::<(), Wrapper<()>>(
+        "/user/verify_email/resend".to_string(),
+        ()
+    ).await
+}
+
+pub async fn forgot_password(dto: Wrapper<ForgotPasswordDto>) -> Result<Wrapper<()>, Error> {
+    request_post::<Wrapper<ForgotPasswordDto>, Wrapper<()>>(
+        "/user/forgot_password".to_string(),
+        dto
+    ).await
+}
+
+pub async fn reset_password(dto: Wrapper<ResetPasswordDto>) -> Result<Wrapper<()>, Error> {
+    request_post::<Wrapper<ResetPasswordDto>, Wrapper<()>>(
+        "/user/reset_password".to_string(),
+        dto
+    ).await
+}
@@ -6,10 +6,24 @@ use crate::types::profile::ProfileDto;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct CommentDto {
     pub id: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub body: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-    pub author: ProfileDto,
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+    pub author: Option<ProfileDto>,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub reply_count: i64,
+    #[serde(default)]
+    pub replies: Vec<CommentDto>,
+    #[serde(default)]
+    pub replies_cursor: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -17,8 +31,31 @@ pub struct NewCommentDto {
     pub body: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UpdateCommentDto {
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CommentRevisionDto {
+    pub id: String,
+    pub body: String,
+    pub written_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CommentRevisionsDto {
+    pub revisions: Vec<CommentRevisionDto>,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct CommentsDto {
     pub comments: Vec<CommentDto>,
     pub count: usize,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub prev_cursor: Option<String>,
 }
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A change pushed by the server over an event stream.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    CommentAdded { post_id: String, comment_id: String, author_display_name: String },
+    FavoritesChanged { post_id: String, favorites_count: i64 },
+    Notifications { unread_count: i64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StreamTicketDto {
+    pub ticket: String,
+}
@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An uploaded image, stored in several sizes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MediaDto {
+    pub id: String,
+    pub url: String,
+    /// For embedding in posts.
+    pub medium_url: String,
+    /// A square crop, for avatars.
+    pub thumbnail_url: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size: i64,
+    pub original_filename: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
 
 pub mod comment;
+pub mod event;
+pub mod media;
+pub mod moderation;
+pub mod notification;
+pub mod oauth;
 pub mod post;
 pub mod profile;
+pub mod report;
+pub mod search;
+pub mod two_factor;
 pub mod user;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
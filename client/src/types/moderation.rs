@@ -0,0 +1,49 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::types::user::UserRole;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModerationDto {
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ResolveReportsDto {
+    pub reason: String,
+    pub hide: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RoleUpdateDto {
+    pub role: UserRole,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserRoleDto {
+    pub display_name: String,
+    pub role: UserRole,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuditLogEntryDto {
+    pub id: String,
+    pub actor: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub reason: String,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuditLogDto {
+    pub entries: Vec<AuditLogEntryDto>,
+    pub count: usize,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub prev_cursor: Option<String>,
+}
@@ -0,0 +1,59 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Follow,
+    Favorite,
+    Comment,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NotificationDto {
+    pub id: String,
+    pub kind: NotificationKind,
+    pub actor_display_name: String,
+    pub actor_profile_image_url: Option<String>,
+    #[serde(default)]
+    pub post_slug: Option<String>,
+    #[serde(default)]
+    pub post_title: Option<String>,
+    #[serde(default)]
+    pub comment_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub read: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NotificationsDto {
+    pub notifications: Vec<NotificationDto>,
+    pub count: usize,
+    pub unread_count: i64,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub prev_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnreadCountDto {
+    pub unread_count: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NotificationPreferencesDto {
+    pub follow: bool,
+    pub favorite: bool,
+    pub comment: bool,
+}
+
+impl Default for NotificationPreferencesDto {
+    fn default() -> Self {
+        NotificationPreferencesDto {
+            follow: true,
+            favorite: true,
+            comment: true,
+        }
+    }
+}
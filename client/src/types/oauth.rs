@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::two_factor::TwoFactorChallengeDto;
+use crate::types::user::UserDto;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OAuthProviderDto {
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuthorizeDto {
+    pub link: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuthorizationUrlDto {
+    pub authorization_url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OAuthCallbackDto {
+    pub code: String,
+    pub state: String,
+}
+
+/// What finishing a provider sign-in responds with: the result, or a request for a code first.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum OAuthCallbackOutcomeDto {
+    TwoFactorRequired(TwoFactorChallengeDto),
+    Done(OAuthCallbackResultDto),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OAuthCallbackResultDto {
+    pub linked: bool,
+    pub user: UserDto,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityDto {
+    pub provider: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentitiesDto {
+    pub identities: Vec<IdentityDto>,
+    pub has_password: bool,
+}
@@ -3,6 +3,16 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::profile::ProfileDto;
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Scheduled,
+    #[default]
+    Published,
+    Archived,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct PostDto {
     pub id: String,
@@ -10,14 +20,33 @@ pub struct PostDto {
     pub title: String,
     pub description: String,
     pub body: String,
+    /// `body` rendered and sanitized by the server.
+    #[serde(default)]
+    pub body_html: String,
+    #[serde(default)]
+    pub toc: Vec<TocEntryDto>,
     pub tags: Vec<String>,
     pub favorited: bool,
     pub favorites_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub status: PostStatus,
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
     pub author: ProfileDto,
 }
 
+/// A heading in a post, linked to by its id in `body_html`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TocEntryDto {
+    pub level: u8,
+    pub id: String,
+    pub title: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct PostDtoWrapper {
     pub data: PostDto,
@@ -27,6 +56,12 @@ pub struct PostDtoWrapper {
 pub struct PostsDto {
     pub posts: Vec<PostDto>,
     pub count: usize,
+    #[serde(default)]
+    pub total: Option<i64>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub prev_cursor: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -35,9 +70,54 @@ pub struct PostUpdateDto {
     pub description: String,
     pub body: String,
     pub tags: Option<Vec<String>>,
+    pub status: Option<PostStatus>,
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Tags {
     pub tags: Vec<String>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PostRevisionSummaryDto {
+    pub id: String,
+    pub title: String,
+    pub written_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PostRevisionsDto {
+    pub revisions: Vec<PostRevisionSummaryDto>,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffChange {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DiffLineDto {
+    pub change: DiffChange,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldDiffDto {
+    pub field: String,
+    pub lines: Vec<DiffLineDto>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PostDiffDto {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub fields: Vec<FieldDiffDto>,
+}
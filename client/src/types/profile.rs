@@ -1,3 +1,4 @@
+use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -6,9 +7,26 @@ pub struct ProfileDto {
     pub biography: Option<String>,
     pub profile_image_url: Option<String>,
     pub following: bool,
+    #[serde(default)]
+    pub blocking: bool,
+    #[serde(default)]
+    pub muting: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ProfileDtoWrapper {
     pub data: ProfileDto,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RelatedUserDto {
+    pub display_name: String,
+    pub profile_image_url: Option<String>,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RelatedUsersDto {
+    pub users: Vec<RelatedUserDto>,
+    pub count: usize,
+}
@@ -0,0 +1,84 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportCategory {
+    #[default]
+    Spam,
+    Harassment,
+    Hate,
+    Violence,
+    Misinformation,
+    Other,
+}
+
+impl ReportCategory {
+    pub const ALL: [ReportCategory; 6] = [
+        ReportCategory::Spam,
+        ReportCategory::Harassment,
+        ReportCategory::Hate,
+        ReportCategory::Violence,
+        ReportCategory::Misinformation,
+        ReportCategory::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportCategory::Spam => "Spam",
+            ReportCategory::Harassment => "Harassment",
+            ReportCategory::Hate => "Hate",
+            ReportCategory::Violence => "Violence",
+            ReportCategory::Misinformation => "Misinformation",
+            ReportCategory::Other => "Other",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTarget {
+    Post,
+    Comment,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NewReportDto {
+    pub category: ReportCategory,
+    pub details: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReportDto {
+    pub id: String,
+    pub reporter: String,
+    pub category: ReportCategory,
+    pub details: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReportGroupDto {
+    pub target_type: ReportTarget,
+    pub target_id: String,
+    pub post_slug: String,
+    pub excerpt: String,
+    pub author: String,
+    pub hidden: bool,
+    pub report_count: i64,
+    pub categories: Vec<ReportCategory>,
+    pub first_reported_at: DateTime<Utc>,
+    pub last_reported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub reports: Vec<ReportDto>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReportQueueDto {
+    pub groups: Vec<ReportGroupDto>,
+    pub count: usize,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub prev_cursor: Option<String>,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::post::PostDto;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SearchResultDto {
+    pub post: PostDto,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SearchResultsDto {
+    pub results: Vec<SearchResultDto>,
+    pub count: usize,
+    pub total: i64,
+}
+
+/// Query string of the search results page.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
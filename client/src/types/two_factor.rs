@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::user::UserDto;
+
+/// What a sign-in step responds with: the user, or a request for a code first.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LoginResultDto {
+    TwoFactorRequired(TwoFactorChallengeDto),
+    User(UserDto),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TwoFactorChallengeDto {
+    pub pending_token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TwoFactorLoginDto {
+    pub pending_token: String,
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TwoFactorCodeDto {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TwoFactorStatusDto {
+    pub enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TwoFactorEnrollmentDto {
+    pub secret: String,
+    pub provisioning_uri: String,
+    pub qr_code_svg: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
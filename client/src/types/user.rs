@@ -1,3 +1,4 @@
+use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -13,6 +14,15 @@ pub struct SignUpDto {
     pub display_name: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    #[default]
+    User,
+    Moderator,
+    Admin,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct UserDto {
     pub email: String,
@@ -20,12 +30,25 @@ pub struct UserDto {
     pub access_token: String,
     pub biography: String,
     pub profile_image_url: String,
+    #[serde(default)]
+    pub role: UserRole,
+    #[serde(default)]
+    pub email_verified: bool,
 }
 
 impl UserDto {
     pub fn is_authenticated(&self) -> bool {
         !self.access_token.is_empty()
     }
+
+    /// Moderators and admins.
+    pub fn is_moderator(&self) -> bool {
+        self.is_authenticated() && self.role >= UserRole::Moderator
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.is_authenticated() && self.role == UserRole::Admin
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -36,3 +59,35 @@ pub struct UserUpdateDto {
     pub biography: String,
     pub profile_image_url: String,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct VerifyEmailDto {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ForgotPasswordDto {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ResetPasswordDto {
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SessionDto {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub current: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SessionsDto {
+    pub sessions: Vec<SessionDto>,
+    pub count: usize,
+}
@@ -0,0 +1,3 @@
+-- Add up migration script here
+
+CREATE INDEX IF NOT EXISTS posts_created_at_id_idx ON posts (created_at DESC, id DESC);
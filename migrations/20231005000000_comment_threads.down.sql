@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS comments_parent_id_created_at_idx;
+DROP INDEX IF EXISTS comments_post_id_created_at_idx;
+ALTER TABLE comments DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE comments DROP COLUMN IF EXISTS parent_id;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS posts_scheduled_publish_at_idx;
+ALTER TABLE posts DROP COLUMN IF EXISTS publish_at, DROP COLUMN IF EXISTS status;
+DROP TYPE IF EXISTS post_status;
@@ -0,0 +1,11 @@
+-- Add up migration script here
+
+CREATE TYPE post_status AS ENUM ('draft', 'scheduled', 'published', 'archived');
+
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS status post_status NOT NULL DEFAULT 'published',
+    ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ;
+
+UPDATE posts SET publish_at = created_at WHERE publish_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS posts_scheduled_publish_at_idx ON posts (publish_at) WHERE status = 'scheduled';
@@ -0,0 +1,11 @@
+-- Add down migration script here
+
+ALTER TABLE post_favorites
+    DROP CONSTRAINT IF EXISTS post_favorites_post_id_fkey,
+    ADD CONSTRAINT post_favorites_post_id_fkey FOREIGN KEY (post_id) REFERENCES posts(id);
+
+DROP TABLE IF EXISTS audit_log;
+ALTER TABLE comments DROP COLUMN IF EXISTS hidden_at;
+ALTER TABLE posts DROP COLUMN IF EXISTS hidden_at;
+ALTER TABLE users DROP COLUMN IF EXISTS role;
+DROP TYPE IF EXISTS user_role;
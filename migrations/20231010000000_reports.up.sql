@@ -0,0 +1,25 @@
+-- Add up migration script here
+
+CREATE TYPE report_category AS ENUM ('spam', 'harassment', 'hate', 'violence', 'misinformation', 'other');
+CREATE TYPE report_status AS ENUM ('open', 'resolved', 'dismissed');
+
+CREATE TABLE IF NOT EXISTS reports (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    reporter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    post_id UUID REFERENCES posts(id) ON DELETE CASCADE,
+    comment_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    category report_category NOT NULL,
+    details TEXT NOT NULL DEFAULT '',
+    status report_status NOT NULL DEFAULT 'open',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT reports_one_target CHECK (num_nonnulls(post_id, comment_id) = 1)
+);
+
+-- A reader has at most one open report per post or comment.
+CREATE UNIQUE INDEX IF NOT EXISTS reports_open_post_reporter_idx
+    ON reports (post_id, reporter_id) WHERE status = 'open' AND post_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS reports_open_comment_reporter_idx
+    ON reports (comment_id, reporter_id) WHERE status = 'open' AND comment_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS reports_open_created_at_idx ON reports (created_at) WHERE status = 'open';
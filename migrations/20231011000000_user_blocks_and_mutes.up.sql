@@ -0,0 +1,19 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS user_blocks (
+    blocker_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    blocked_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT cannot_block_yourself CHECK (blocker_user_id != blocked_user_id),
+    PRIMARY KEY (blocker_user_id, blocked_user_id)
+);
+
+CREATE INDEX IF NOT EXISTS user_blocks_blocked_user_id_idx ON user_blocks (blocked_user_id);
+
+CREATE TABLE IF NOT EXISTS user_mutes (
+    muter_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    muted_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT cannot_mute_yourself CHECK (muter_user_id != muted_user_id),
+    PRIMARY KEY (muter_user_id, muted_user_id)
+);
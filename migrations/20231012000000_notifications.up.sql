@@ -0,0 +1,26 @@
+-- Add up migration script here
+
+CREATE TYPE notification_kind AS ENUM ('follow', 'favorite', 'comment');
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    recipient_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    actor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind notification_kind NOT NULL,
+    post_id UUID REFERENCES posts(id) ON DELETE CASCADE,
+    comment_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS notifications_recipient_created_at_idx ON notifications (recipient_id, created_at, id);
+CREATE INDEX IF NOT EXISTS notifications_unread_idx ON notifications (recipient_id) WHERE read_at IS NULL;
+
+-- One row per user who changed a default; every kind is on until turned off.
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    follow BOOLEAN NOT NULL DEFAULT TRUE,
+    favorite BOOLEAN NOT NULL DEFAULT TRUE,
+    comment BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS media (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Of every variant; images are re-encoded, so this is never the uploaded type when it was a GIF or WebP.
+    content_type TEXT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    -- Bytes stored across all variants, which is what counts towards the user's quota.
+    size BIGINT NOT NULL,
+    original_filename TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS media_user_id_created_at_idx ON media (user_id, created_at DESC);
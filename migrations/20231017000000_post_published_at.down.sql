@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS posts_listed_at_id_idx;
+CREATE INDEX IF NOT EXISTS posts_created_at_id_idx ON posts (created_at DESC, id DESC);
+ALTER TABLE posts DROP COLUMN IF EXISTS published_at;
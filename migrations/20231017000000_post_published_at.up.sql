@@ -0,0 +1,10 @@
+-- Add up migration script here
+
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS published_at TIMESTAMPTZ;
+
+-- Posts that went out so far did so when they were published or scheduled for.
+UPDATE posts SET published_at = COALESCE(publish_at, created_at) WHERE status IN ('published', 'archived');
+
+-- Lists are ordered by when posts went out; drafts and scheduled posts by when they were written.
+DROP INDEX IF EXISTS posts_created_at_id_idx;
+CREATE INDEX IF NOT EXISTS posts_listed_at_id_idx ON posts ((COALESCE(published_at, created_at)) DESC, id DESC);
@@ -0,0 +1,53 @@
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+
+use crate::audit::{self, AuditAction};
+use crate::error::AppError;
+use crate::models::user::UserRole;
+
+/// Makes admins of the users signed up with any of `emails`, so a new deployment has someone to
+/// grant roles to everyone else. Run at startup; emails nobody has signed up with yet take effect
+/// on the next start.
+///
+/// Returns how many users were promoted.
+pub async fn promote_configured_admins(db: &Pool<Postgres>, emails: &[String]) -> Result<usize, AppError> {
+    if emails.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = db.begin().await?;
+
+    let promoted = sqlx::query!(
+        r#"
+            WITH promoted AS (
+                SELECT id, role FROM users
+                WHERE LOWER(email) = ANY($1) AND role <> 'admin'
+                FOR UPDATE
+            )
+            UPDATE users
+            SET role = 'admin', updated_at = NOW()
+            FROM promoted
+            WHERE users.id = promoted.id
+            RETURNING users.id, promoted.role AS "from: UserRole"
+        "#,
+        emails,
+    )
+        .fetch_all(&mut *tx)
+        .await?;
+
+    for user in &promoted {
+        audit::record(
+            &mut *tx,
+            None,
+            AuditAction::ChangeRole,
+            user.id,
+            "Listed in ADMIN_EMAILS",
+            json!({ "from": user.from, "to": UserRole::Admin }),
+        )
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(promoted.len())
+}
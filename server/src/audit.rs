@@ -0,0 +1,89 @@
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Privileged actions that are written to `audit_log`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    HidePost,
+    UnhidePost,
+    DeletePost,
+    HideComment,
+    UnhideComment,
+    DeleteComment,
+    ChangeRole,
+    /// Hidden because enough readers reported it; recorded without an actor.
+    AutoHidePost,
+    AutoHideComment,
+    ResolvePostReports,
+    ResolveCommentReports,
+    DismissPostReports,
+    DismissCommentReports,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::HidePost => "hide_post",
+            AuditAction::UnhidePost => "unhide_post",
+            AuditAction::DeletePost => "delete_post",
+            AuditAction::HideComment => "hide_comment",
+            AuditAction::UnhideComment => "unhide_comment",
+            AuditAction::DeleteComment => "delete_comment",
+            AuditAction::ChangeRole => "change_role",
+            AuditAction::AutoHidePost => "auto_hide_post",
+            AuditAction::AutoHideComment => "auto_hide_comment",
+            AuditAction::ResolvePostReports => "resolve_post_reports",
+            AuditAction::ResolveCommentReports => "resolve_comment_reports",
+            AuditAction::DismissPostReports => "dismiss_post_reports",
+            AuditAction::DismissCommentReports => "dismiss_comment_reports",
+        }
+    }
+
+    pub fn target_type(&self) -> &'static str {
+        match self {
+            AuditAction::HidePost
+            | AuditAction::UnhidePost
+            | AuditAction::DeletePost
+            | AuditAction::AutoHidePost
+            | AuditAction::ResolvePostReports
+            | AuditAction::DismissPostReports => "post",
+            AuditAction::HideComment
+            | AuditAction::UnhideComment
+            | AuditAction::DeleteComment
+            | AuditAction::AutoHideComment
+            | AuditAction::ResolveCommentReports
+            | AuditAction::DismissCommentReports => "comment",
+            AuditAction::ChangeRole => "user",
+        }
+    }
+}
+
+/// Records `action` by `actor_id` on `target_id`; `None` is the system acting on its own. Run it in
+/// the same transaction as the action so neither is kept without the other.
+pub async fn record(
+    executor: impl sqlx::PgExecutor<'_>,
+    actor_id: Option<Uuid>,
+    action: AuditAction,
+    target_id: Uuid,
+    reason: &str,
+    details: Value,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+            INSERT INTO audit_log (actor_id, action, target_type, target_id, reason, details)
+            VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        actor_id,
+        action.as_str(),
+        action.target_type(),
+        target_id,
+        reason,
+        details,
+    )
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
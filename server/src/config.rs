@@ -1,6 +1,14 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
 use clap::Parser;
 use sqlx::{Pool, Postgres};
 
+use crate::events::EventHub;
+use crate::mailer::Mailer;
+use crate::markdown::MarkdownRenderer;
+use crate::storage::Storage;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
@@ -15,6 +23,103 @@ pub struct Config {
     pub refresh_token_public_key: String,
     pub refresh_token_expires_in: i64,
     pub refresh_token_max_age: i64,
+
+    /// Reverse proxies whose `X-Forwarded-For` says where requests come from.
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// Users signed up with these emails are made admins at startup, lowercased.
+    pub admin_emails: Vec<String>,
+
+    /// Open reports from this many readers hide a post or comment until a moderator looks at it.
+    pub report_hide_threshold: i64,
+
+    /// Where the client is served, for links in emails.
+    pub app_url: String,
+    pub email_verification_token_max_age: i64,
+    pub password_reset_token_max_age: i64,
+
+    pub mail_transport: MailTransport,
+    pub mail_from: String,
+    pub mail_dir: Option<String>,
+    pub smtp_host: String,
+    pub smtp_port: Option<u16>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+
+    pub oauth_providers: Vec<OAuthProviderConfig>,
+
+    pub storage_backend: StorageBackend,
+    /// Where uploads are written with the local backend.
+    pub upload_dir: String,
+    /// What uploaded files' URLs start with: where `upload_dir` is served, or the bucket's public URL.
+    pub media_url: String,
+    pub s3_bucket: Option<String>,
+    pub s3_region: String,
+    /// For S3-compatible services other than AWS, such as MinIO.
+    pub s3_endpoint: Option<String>,
+    pub s3_access_key_id: Option<String>,
+    pub s3_secret_access_key: Option<String>,
+    /// Largest file a user can upload, in bytes.
+    pub upload_max_size: usize,
+    /// How many bytes of uploads each user can keep, counting every resized variant.
+    pub upload_quota: i64,
+}
+
+/// An OpenID Connect provider users can sign in with. Read from `OAUTH_<NAME>_*` for every name
+/// listed in `OAUTH_PROVIDERS`.
+#[derive(Debug, Clone)]
+pub struct OAuthProviderConfig {
+    /// Lowercase name used in URLs.
+    pub name: String,
+    /// Shown on the login button.
+    pub display_name: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub auth_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    pub scopes: Vec<String>,
+}
+
+impl OAuthProviderConfig {
+    fn from_env(name: &str) -> OAuthProviderConfig {
+        let var = |key: &str| std::env::var(format!("OAUTH_{}_{key}", name.to_uppercase())).ok().filter(|value| !value.is_empty());
+        let required = |key: &str| {
+            var(key).unwrap_or_else(|| panic!("OAUTH_{}_{key} must be set", name.to_uppercase()))
+        };
+
+        OAuthProviderConfig {
+            name: name.to_lowercase(),
+            display_name: var("DISPLAY_NAME").unwrap_or_else(|| name.to_string()),
+            client_id: required("CLIENT_ID"),
+            client_secret: var("CLIENT_SECRET"),
+            auth_url: required("AUTH_URL"),
+            token_url: required("TOKEN_URL"),
+            userinfo_url: required("USERINFO_URL"),
+            scopes: var("SCOPES")
+                .unwrap_or_else(|| "openid email profile".to_string())
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Where uploaded media is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// On disk in `upload_dir`, served by this server under `/media`.
+    Local,
+    /// In an S3-compatible bucket, served from `media_url`.
+    S3,
+}
+
+/// How outgoing email is delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailTransport {
+    Smtp,
+    /// Written to `mail_dir`, or to stdout when that isn't set; for local development and tests.
+    File,
 }
 
 impl Config {
@@ -40,6 +145,69 @@ impl Config {
         let refresh_token_max_age =
             std::env::var("REFRESH_TOKEN_MAX_AGE").expect("REFRESH_TOKEN_MAX_AGE must be set");
 
+        let trusted_proxies = std::env::var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(|address| {
+                address
+                    .parse::<IpAddr>()
+                    .unwrap_or_else(|_| panic!("TRUSTED_PROXIES has an invalid address: {address}"))
+            })
+            .collect();
+
+        let admin_emails = std::env::var("ADMIN_EMAILS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|email| email.trim().to_lowercase())
+            .filter(|email| !email.is_empty())
+            .collect();
+
+        let report_hide_threshold = std::env::var("REPORT_HIDE_THRESHOLD").unwrap_or_else(|_| "5".to_string());
+
+        let app_url = std::env::var("APP_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+        let email_verification_token_max_age = std::env::var("EMAIL_VERIFICATION_TOKEN_MAX_AGE")
+            .unwrap_or_else(|_| "1440".to_string());
+        let password_reset_token_max_age =
+            std::env::var("PASSWORD_RESET_TOKEN_MAX_AGE").unwrap_or_else(|_| "60".to_string());
+
+        let mail_transport = match std::env::var("MAIL_TRANSPORT").as_deref() {
+            Ok("smtp") => MailTransport::Smtp,
+            Ok("file") | Err(_) => MailTransport::File,
+            Ok(other) => panic!("MAIL_TRANSPORT must be smtp or file, not {other}"),
+        };
+        let mail_from =
+            std::env::var("MAIL_FROM").unwrap_or_else(|_| "negatiview <no-reply@localhost>".to_string());
+        let mail_dir = std::env::var("MAIL_DIR").ok().filter(|value| !value.is_empty());
+        let smtp_host = std::env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string());
+        let smtp_port = std::env::var("SMTP_PORT").ok().filter(|value| !value.is_empty());
+        let smtp_username = std::env::var("SMTP_USERNAME").ok().filter(|value| !value.is_empty());
+        let smtp_password = std::env::var("SMTP_PASSWORD").ok().filter(|value| !value.is_empty());
+
+        let oauth_providers = std::env::var("OAUTH_PROVIDERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(OAuthProviderConfig::from_env)
+            .collect();
+
+        let storage_backend = match std::env::var("STORAGE_BACKEND").as_deref() {
+            Ok("s3") => StorageBackend::S3,
+            Ok("local") | Err(_) => StorageBackend::Local,
+            Ok(other) => panic!("STORAGE_BACKEND must be local or s3, not {other}"),
+        };
+        let upload_dir = std::env::var("UPLOAD_DIR").unwrap_or_else(|_| "uploads".to_string());
+        let media_url = std::env::var("MEDIA_URL").unwrap_or_else(|_| "http://localhost:8081/media".to_string());
+        let s3_bucket = std::env::var("S3_BUCKET").ok().filter(|value| !value.is_empty());
+        let s3_region = std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+        let s3_endpoint = std::env::var("S3_ENDPOINT").ok().filter(|value| !value.is_empty());
+        let s3_access_key_id = std::env::var("S3_ACCESS_KEY_ID").ok().filter(|value| !value.is_empty());
+        let s3_secret_access_key = std::env::var("S3_SECRET_ACCESS_KEY").ok().filter(|value| !value.is_empty());
+        let upload_max_size_mb = std::env::var("UPLOAD_MAX_SIZE_MB").unwrap_or_else(|_| "10".to_string());
+        let upload_quota_mb = std::env::var("UPLOAD_QUOTA_MB").unwrap_or_else(|_| "100".to_string());
+
         Config {
             database_url,
             redis_url,
@@ -51,6 +219,30 @@ impl Config {
             refresh_token_public_key,
             refresh_token_expires_in: refresh_token_expires_in.parse::<i64>().unwrap(),
             refresh_token_max_age: refresh_token_max_age.parse::<i64>().unwrap(),
+            trusted_proxies,
+            admin_emails,
+            report_hide_threshold: report_hide_threshold.parse::<i64>().unwrap(),
+            app_url: app_url.trim_end_matches('/').to_string(),
+            email_verification_token_max_age: email_verification_token_max_age.parse::<i64>().unwrap(),
+            password_reset_token_max_age: password_reset_token_max_age.parse::<i64>().unwrap(),
+            mail_transport,
+            mail_from,
+            mail_dir,
+            smtp_host,
+            smtp_port: smtp_port.map(|port| port.parse::<u16>().unwrap()),
+            smtp_username,
+            smtp_password,
+            oauth_providers,
+            storage_backend,
+            upload_dir,
+            media_url: media_url.trim_end_matches('/').to_string(),
+            s3_bucket,
+            s3_region,
+            s3_endpoint,
+            s3_access_key_id,
+            s3_secret_access_key,
+            upload_max_size: upload_max_size_mb.parse::<usize>().unwrap() * 1024 * 1024,
+            upload_quota: upload_quota_mb.parse::<i64>().unwrap() * 1024 * 1024,
         }
     }
 }
@@ -59,6 +251,10 @@ pub struct AppState {
     pub db: Pool<Postgres>,
     pub env: Config,
     pub redis_client: redis::Client,
+    pub events: EventHub,
+    pub mailer: Arc<dyn Mailer>,
+    pub storage: Arc<dyn Storage>,
+    pub markdown: MarkdownRenderer,
 }
 
 #[derive(Parser, Debug)]
@@ -75,4 +271,8 @@ pub struct Opt {
 
     #[clap(long = "static-dir", default_value = "./dist")]
     pub static_dir: String,
+
+    /// How often, in seconds, scheduled posts are checked for being due.
+    #[clap(long = "publish-interval", default_value = "30")]
+    pub publish_interval: u64,
 }
@@ -1,14 +1,25 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use crate::dtos::profile::ProfileDto;
+use crate::validation::{Validate, ValidationErrors, COMMENT_MAX_LENGTH};
 
+/// A comment with the first page of its replies. Deleted comments that still have replies are
+/// kept as tombstones: `deleted` is set and `body` and `author` are cleared.
 #[derive(Serialize, Deserialize)]
 pub struct CommentDto {
     pub id: uuid::Uuid,
+    pub parent_id: Option<uuid::Uuid>,
     pub body: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-    pub author: ProfileDto,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub author: Option<ProfileDto>,
+    pub deleted: bool,
+    /// Set when a moderator hid the comment; like deleted comments, its body and author are blanked.
+    pub hidden: bool,
+    pub reply_count: i64,
+    pub replies: Vec<CommentDto>,
+    pub replies_cursor: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -16,8 +27,50 @@ pub struct NewCommentDto {
     pub body: String,
 }
 
+impl Validate for NewCommentDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if errors.required("body", &self.body) {
+            errors.max_length("body", &self.body, COMMENT_MAX_LENGTH);
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateCommentDto {
+    pub body: String,
+}
+
+impl Validate for UpdateCommentDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if errors.required("body", &self.body) {
+            errors.max_length("body", &self.body, COMMENT_MAX_LENGTH);
+        }
+        errors.into_result()
+    }
+}
+
+/// A body a comment had before it was edited.
+#[derive(Serialize, Deserialize)]
+pub struct CommentRevisionDto {
+    pub id: uuid::Uuid,
+    pub body: String,
+    pub written_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CommentRevisionsDto {
+    pub revisions: Vec<CommentRevisionDto>,
+    pub count: usize,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CommentsDto {
     pub comments: Vec<CommentDto>,
     pub count: usize,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
 }
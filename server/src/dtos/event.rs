@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamTicketDto {
+    pub ticket: Uuid,
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::media::{key, Variant};
+use crate::models::media::Media;
+use crate::storage::Storage;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaDto {
+    pub id: Uuid,
+    pub url: String,
+    pub medium_url: String,
+    pub thumbnail_url: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size: i64,
+    pub original_filename: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MediaDto {
+    pub fn new(media: Media, storage: &dyn Storage) -> Self {
+        let url = |variant| storage.url(&key(media.id, variant, &media.content_type));
+
+        MediaDto {
+            url: url(Variant::Large),
+            medium_url: url(Variant::Medium),
+            thumbnail_url: url(Variant::Thumbnail),
+            id: media.id,
+            content_type: media.content_type,
+            width: media.width,
+            height: media.height,
+            size: media.size,
+            original_filename: media.original_filename,
+            created_at: media.created_at,
+        }
+    }
+}
+
+/// A user's uploads, newest first, and how much of their quota they take up.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaListDto {
+    pub media: Vec<MediaDto>,
+    pub used_bytes: i64,
+    pub quota_bytes: i64,
+}
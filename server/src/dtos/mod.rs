@@ -1,9 +1,17 @@
 use serde::{Deserialize, Serialize};
 
 pub mod comment;
+pub mod event;
+pub mod media;
+pub mod moderation;
+pub mod notification;
+pub mod oauth;
 pub mod post;
+pub mod report;
 pub mod user;
 pub mod profile;
+pub mod search;
+pub mod two_factor;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Wrapper<T> {
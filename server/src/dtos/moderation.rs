@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::user::UserRole;
+use crate::validation::{Validate, ValidationErrors, REASON_MAX_LENGTH};
+
+/// Why a moderator hid, restored or deleted something; kept in the audit log.
+#[derive(Deserialize)]
+pub struct ModerationDto {
+    pub reason: String,
+}
+
+impl Validate for ModerationDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validate_reason(&mut errors, &self.reason);
+        errors.into_result()
+    }
+}
+
+/// Closes the open reports on a post or comment, optionally hiding it.
+#[derive(Deserialize)]
+pub struct ResolveReportsDto {
+    pub reason: String,
+    #[serde(default)]
+    pub hide: bool,
+}
+
+impl Validate for ResolveReportsDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validate_reason(&mut errors, &self.reason);
+        errors.into_result()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RoleUpdateDto {
+    pub role: UserRole,
+    pub reason: String,
+}
+
+impl Validate for RoleUpdateDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validate_reason(&mut errors, &self.reason);
+        errors.into_result()
+    }
+}
+
+fn validate_reason(errors: &mut ValidationErrors, reason: &str) {
+    if errors.required("reason", reason) {
+        errors.max_length("reason", reason, REASON_MAX_LENGTH);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserRoleDto {
+    pub display_name: String,
+    pub role: UserRole,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditLogEntryDto {
+    pub id: uuid::Uuid,
+    /// Display name of whoever acted, unless their account has since been deleted.
+    pub actor: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: uuid::Uuid,
+    pub reason: String,
+    pub details: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditLogDto {
+    pub entries: Vec<AuditLogEntryDto>,
+    pub count: usize,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::notification::NotificationKind;
+
+#[derive(Serialize, Deserialize)]
+pub struct NotificationDto {
+    pub id: uuid::Uuid,
+    pub kind: NotificationKind,
+    pub actor_display_name: String,
+    pub actor_profile_image_url: Option<String>,
+    /// The post that was favorited or commented on.
+    pub post_slug: Option<String>,
+    pub post_title: Option<String>,
+    pub comment_id: Option<uuid::Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub read: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NotificationsDto {
+    pub notifications: Vec<NotificationDto>,
+    pub count: usize,
+    pub unread_count: i64,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UnreadCountDto {
+    pub unread_count: i64,
+}
+
+/// Which kinds of notification a user receives.
+#[derive(Serialize, Deserialize)]
+pub struct NotificationPreferencesDto {
+    pub follow: bool,
+    pub favorite: bool,
+    pub comment: bool,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::user::UserDto;
+use crate::validation::{Validate, ValidationErrors};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuthProviderDto {
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AuthorizeDto {
+    /// Link the provider to the signed-in user instead of signing in with it.
+    #[serde(default)]
+    pub link: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthorizationUrlDto {
+    pub authorization_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuthCallbackDto {
+    pub code: String,
+    pub state: String,
+}
+
+impl Validate for OAuthCallbackDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.required("code", &self.code);
+        errors.required("state", &self.state);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuthCallbackResultDto {
+    /// Whether the provider was linked to the signed-in user rather than used to sign in.
+    pub linked: bool,
+    pub user: UserDto,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdentityDto {
+    pub provider: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdentitiesDto {
+    pub identities: Vec<IdentityDto>,
+    /// Whether the user can also log in with a password, so unlinking the last provider is safe.
+    pub has_password: bool,
+}
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use crate::dtos::profile::ProfileDto;
+use crate::models::post::PostStatus;
+use crate::validation::{Validate, ValidationErrors, BODY_MAX_LENGTH, DESCRIPTION_MAX_LENGTH, TITLE_MAX_LENGTH};
 
 #[derive(Deserialize)]
 pub struct NewPostDto {
@@ -8,6 +10,12 @@ pub struct NewPostDto {
     pub description: String,
     pub body: String,
     pub tags: Vec<String>,
+    /// Defaults to `Published`, so posts go live immediately unless asked otherwise.
+    #[serde(default)]
+    pub status: Option<PostStatus>,
+    /// When a `Scheduled` post goes live; ignored for other statuses.
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize)]
@@ -16,6 +24,71 @@ pub struct UpdatePostDto {
     pub description: Option<String>,
     pub body: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub status: Option<PostStatus>,
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+impl Validate for NewPostDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        validate_title(&mut errors, &self.title);
+        validate_description(&mut errors, &self.description);
+        validate_body(&mut errors, &self.body);
+        errors.tags("tags", &self.tags);
+        validate_schedule(&mut errors, self.status, self.publish_at);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdatePostDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if let Some(title) = &self.title {
+            validate_title(&mut errors, title);
+        }
+        if let Some(description) = &self.description {
+            validate_description(&mut errors, description);
+        }
+        if let Some(body) = &self.body {
+            validate_body(&mut errors, body);
+        }
+        if let Some(tags) = &self.tags {
+            errors.tags("tags", tags);
+        }
+        validate_schedule(&mut errors, self.status, self.publish_at);
+        errors.into_result()
+    }
+}
+
+fn validate_title(errors: &mut ValidationErrors, title: &str) {
+    if errors.required("title", title) {
+        errors.max_length("title", title, TITLE_MAX_LENGTH);
+    }
+}
+
+fn validate_description(errors: &mut ValidationErrors, description: &str) {
+    if errors.required("description", description) {
+        errors.max_length("description", description, DESCRIPTION_MAX_LENGTH);
+    }
+}
+
+fn validate_body(errors: &mut ValidationErrors, body: &str) {
+    if errors.required("body", body) {
+        errors.max_length("body", body, BODY_MAX_LENGTH);
+    }
+}
+
+fn validate_schedule(errors: &mut ValidationErrors, status: Option<PostStatus>, publish_at: Option<DateTime<Utc>>) {
+    if status != Some(PostStatus::Scheduled) {
+        return;
+    }
+    match publish_at {
+        None => errors.add("publish_at", "is required for scheduled posts"),
+        Some(publish_at) if publish_at <= Utc::now() => errors.add("publish_at", "must be in the future"),
+        Some(_) => {}
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,16 +98,95 @@ pub struct PostDto {
     pub title: String,
     pub description: String,
     pub body: String,
+    /// `body` rendered and sanitized, ready to show as is. Lists leave it out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_html: Option<String>,
+    /// The headings in `body`, which `body_html` gives matching ids. Lists leave it out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toc: Option<Vec<TocEntryDto>>,
     pub tags: Vec<String>,
     pub favorited: bool,
     pub favorites_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub status: PostStatus,
+    pub publish_at: Option<DateTime<Utc>>,
+    /// When the post first went out; unset while it is a draft or scheduled.
+    pub published_at: Option<DateTime<Utc>>,
     pub author: ProfileDto,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TocEntryDto {
+    /// 1 to 6, as in `<h1>` to `<h6>`.
+    pub level: u8,
+    pub id: String,
+    pub title: String,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct PostsDto {
     pub posts: Vec<PostDto>,
     pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+/// A saved earlier version of a post, as it was between `written_at` and `replaced_at`.
+#[derive(Serialize, Deserialize)]
+pub struct PostRevisionDto {
+    pub id: uuid::Uuid,
+    pub title: String,
+    pub description: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub written_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PostRevisionSummaryDto {
+    pub id: uuid::Uuid,
+    pub title: String,
+    pub written_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PostRevisionsDto {
+    pub revisions: Vec<PostRevisionSummaryDto>,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffChange {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// One line of a diff; line numbers are 1-based and absent on the side the line doesn't exist in.
+#[derive(Serialize, Deserialize)]
+pub struct DiffLineDto {
+    pub change: DiffChange,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FieldDiffDto {
+    pub field: String,
+    pub lines: Vec<DiffLineDto>,
+}
+
+/// Differences between two versions of a post, where a missing id stands for the current version.
+#[derive(Serialize, Deserialize)]
+pub struct PostDiffDto {
+    pub from: Option<uuid::Uuid>,
+    pub to: Option<uuid::Uuid>,
+    pub fields: Vec<FieldDiffDto>,
 }
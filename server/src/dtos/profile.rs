@@ -1,10 +1,28 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// `blocking` and `muting` describe the viewer's own relationship to the user. They are only
+/// looked up for profile requests; authors embedded in posts and comments leave them false.
 #[derive(Serialize, Deserialize)]
 pub struct ProfileDto {
     pub display_name: String,
     pub biography: Option<String>,
     pub profile_image_url: Option<String>,
     pub following: bool,
+    pub blocking: bool,
+    pub muting: bool,
 }
 
+/// Someone the current user has blocked or muted.
+#[derive(Serialize, Deserialize)]
+pub struct RelatedUserDto {
+    pub display_name: String,
+    pub profile_image_url: Option<String>,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RelatedUsersDto {
+    pub users: Vec<RelatedUserDto>,
+    pub count: usize,
+}
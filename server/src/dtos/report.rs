@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::report::{ReportCategory, ReportTarget};
+use crate::validation::{Validate, ValidationErrors, REPORT_DETAILS_MAX_LENGTH};
+
+#[derive(Deserialize)]
+pub struct NewReportDto {
+    pub category: ReportCategory,
+    #[serde(default)]
+    pub details: String,
+}
+
+impl Validate for NewReportDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.max_length("details", &self.details, REPORT_DETAILS_MAX_LENGTH);
+        errors.into_result()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReportDto {
+    pub id: uuid::Uuid,
+    pub reporter: String,
+    pub category: ReportCategory,
+    pub details: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The open reports on one post or comment.
+#[derive(Serialize, Deserialize)]
+pub struct ReportGroupDto {
+    pub target_type: ReportTarget,
+    pub target_id: uuid::Uuid,
+    /// The post itself, or the post a reported comment is on.
+    pub post_slug: String,
+    /// The post's title or the start of the comment.
+    pub excerpt: String,
+    pub author: String,
+    pub hidden: bool,
+    pub report_count: i64,
+    pub categories: Vec<ReportCategory>,
+    pub first_reported_at: DateTime<Utc>,
+    pub last_reported_at: DateTime<Utc>,
+    pub reports: Vec<ReportDto>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReportQueueDto {
+    pub groups: Vec<ReportGroupDto>,
+    pub count: usize,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::post::PostDto;
+
+/// A post matching a search. `title_highlight` and `snippet` are HTML-escaped and wrap matched
+/// terms in `<mark>`.
+#[derive(Serialize, Deserialize)]
+pub struct SearchResultDto {
+    pub post: PostDto,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchResultsDto {
+    pub results: Vec<SearchResultDto>,
+    pub count: usize,
+    pub total: i64,
+}
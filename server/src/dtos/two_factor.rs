@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::validation::{Validate, ValidationErrors};
+
+/// Sent instead of the user when signing in needs a code as well as a password.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorChallengeDto {
+    pub pending_token: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorLoginDto {
+    pub pending_token: Uuid,
+    pub code: String,
+}
+
+impl Validate for TwoFactorLoginDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.required("code", &self.code);
+        errors.into_result()
+    }
+}
+
+/// A code from the user's authenticator app, or one of their recovery codes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorCodeDto {
+    pub code: String,
+}
+
+impl Validate for TwoFactorCodeDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.required("code", &self.code);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorStatusDto {
+    pub enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorEnrollmentDto {
+    /// For typing into an authenticator app that can't scan the QR code.
+    pub secret: String,
+    pub provisioning_uri: String,
+    pub qr_code_svg: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
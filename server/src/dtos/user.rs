@@ -1,5 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::user::{User, UserRole};
+use crate::validation::{Validate, ValidationErrors, BIOGRAPHY_MAX_LENGTH};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserDto {
     pub email: String,
@@ -7,6 +11,22 @@ pub struct UserDto {
     pub access_token: String,
     pub biography: String,
     pub profile_image_url: String,
+    pub role: UserRole,
+    pub email_verified: bool,
+}
+
+impl UserDto {
+    pub fn new(user: User, access_token: String) -> Self {
+        UserDto {
+            email: user.email,
+            display_name: user.display_name,
+            access_token,
+            biography: user.biography.unwrap_or_default(),
+            profile_image_url: user.profile_image_url.unwrap_or_default(),
+            role: user.role,
+            email_verified: user.email_verified_at.is_some(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,3 +50,93 @@ pub struct SignUpDto {
     pub password: String,
     pub display_name: String,
 }
+
+impl Validate for UserUpdateDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.email("email", &self.email);
+        errors.display_name("display_name", &self.display_name);
+        if let Some(password) = &self.password {
+            errors.password("password", password);
+        }
+        errors.max_length("biography", &self.biography, BIOGRAPHY_MAX_LENGTH);
+        errors.url("profile_image_url", &self.profile_image_url);
+        errors.into_result()
+    }
+}
+
+impl Validate for LoginDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.required("email", &self.email);
+        errors.required("password", &self.password);
+        errors.into_result()
+    }
+}
+
+impl Validate for SignUpDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.email("email", &self.email);
+        errors.password("password", &self.password);
+        errors.display_name("display_name", &self.display_name);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyEmailDto {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForgotPasswordDto {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResetPasswordDto {
+    pub token: String,
+    pub password: String,
+}
+
+impl Validate for VerifyEmailDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.required("token", &self.token);
+        errors.into_result()
+    }
+}
+
+impl Validate for ForgotPasswordDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.email("email", &self.email);
+        errors.into_result()
+    }
+}
+
+impl Validate for ResetPasswordDto {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.required("token", &self.token);
+        errors.password("password", &self.password);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionDto {
+    pub id: uuid::Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionsDto {
+    pub sessions: Vec<SessionDto>,
+    pub count: usize,
+}
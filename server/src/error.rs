@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const UNIQUE_VIOLATION: &str = "23505";
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+/// Body of every error response.
+///
+/// `errors` maps field names to messages and is only filled in for validation failures, but is
+/// always present so clients can deserialize any error the same way.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub status: String,
+    pub message: String,
+    pub errors: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("Validation failed")]
+    Validation(HashMap<String, Vec<String>>),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    TooManyRequests(String),
+    #[error("Internal server error")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::Internal(detail) = &self {
+            tracing::error!("{detail}");
+        }
+
+        let status_code = self.status_code();
+        let message = self.to_string();
+        let errors = match self {
+            AppError::Validation(errors) => errors,
+            _ => HashMap::new(),
+        };
+
+        let body = ErrorResponse {
+            status: "fail".to_string(),
+            message,
+            errors,
+        };
+
+        (status_code, Json(body)).into_response()
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::RowNotFound => AppError::NotFound("Resource not found".to_string()),
+            sqlx::Error::Database(db_err) => match db_err.code().as_deref() {
+                Some(UNIQUE_VIOLATION) => AppError::Conflict(
+                    db_err
+                        .constraint()
+                        .and_then(constraint_column)
+                        .map(|column| format!("{} is already taken", column.replace('_', " ")))
+                        .unwrap_or_else(|| "Resource already exists".to_string()),
+                ),
+                Some(FOREIGN_KEY_VIOLATION) => {
+                    AppError::NotFound("Referenced resource not found".to_string())
+                }
+                _ => AppError::Internal(format!("Database error: {err}")),
+            },
+            _ => AppError::Internal(format!("Database error: {err}")),
+        }
+    }
+}
+
+impl From<redis::RedisError> for AppError {
+    fn from(err: redis::RedisError) -> Self {
+        AppError::Internal(format!("Redis error: {err}"))
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for AppError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        AppError::Internal(format!("Token error: {err}"))
+    }
+}
+
+impl From<argon2::password_hash::Error> for AppError {
+    fn from(err: argon2::password_hash::Error) -> Self {
+        AppError::Internal(format!("Password hashing error: {err}"))
+    }
+}
+
+/// Extracts the column from Postgres' default unique constraint names, e.g. `users_email_key`.
+fn constraint_column(constraint: &str) -> Option<&str> {
+    let column = constraint.strip_suffix("_key")?;
+    column.split_once('_').map(|(_, column)| column)
+}
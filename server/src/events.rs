@@ -0,0 +1,183 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::response::sse::Event;
+use futures::{future, stream, Stream, StreamExt};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::error::AppError;
+
+/// How long a stream ticket can be redeemed for, in seconds.
+const STREAM_TICKET_TTL: usize = 30;
+/// Matches every channel events are published on.
+const CHANNEL_PATTERN: &str = "events:*";
+/// How many events a stream can fall behind by before it starts missing some.
+const EVENT_BUFFER: usize = 1024;
+/// How long to wait before subscribing again after losing the connection to Redis.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Something that happened which open streams are told about straight away. Every server instance
+/// publishes to Redis and relays what it receives, so a change made through one instance reaches
+/// readers connected to any other.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    /// A comment or reply was added to the post.
+    CommentAdded { post_id: Uuid, comment_id: Uuid, author_display_name: String },
+    /// The post was favorited or unfavorited.
+    FavoritesChanged { post_id: Uuid, favorites_count: i64 },
+    /// The user's unread notification count changed.
+    Notifications { unread_count: i64 },
+}
+
+pub fn post_channel(post_id: Uuid) -> String {
+    format!("events:post:{post_id}")
+}
+
+pub fn user_channel(user_id: Uuid) -> String {
+    format!("events:user:{user_id}")
+}
+
+fn stream_ticket_key(ticket: Uuid) -> String {
+    format!("stream_ticket:{ticket}")
+}
+
+#[derive(Debug)]
+struct Message {
+    channel: String,
+    payload: String,
+}
+
+/// This instance's two connections to Redis for events, one to publish on and one subscribed to
+/// every channel, which hands each message to whichever open streams are on its channel. However
+/// many streams readers open, they take up no more connections.
+pub struct EventHub {
+    redis_client: redis::Client,
+    publisher: Mutex<Option<MultiplexedConnection>>,
+    sender: broadcast::Sender<Arc<Message>>,
+}
+
+impl EventHub {
+    /// Subscribes in the background, for as long as the server runs.
+    pub fn start(redis_client: redis::Client) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        tokio::spawn(relay(redis_client.clone(), sender.clone()));
+
+        EventHub {
+            redis_client,
+            publisher: Mutex::new(None),
+            sender,
+        }
+    }
+
+    /// The connection to publish on, made on first use and again after one fails.
+    async fn publisher(&self) -> redis::RedisResult<MultiplexedConnection> {
+        let mut publisher = self.publisher.lock().await;
+        if let Some(connection) = publisher.as_ref() {
+            return Ok(connection.clone());
+        }
+
+        let connection = self.redis_client.get_multiplexed_tokio_connection().await?;
+        *publisher = Some(connection.clone());
+        Ok(connection)
+    }
+}
+
+async fn relay(redis_client: redis::Client, sender: broadcast::Sender<Arc<Message>>) {
+    loop {
+        if let Err(err) = relay_until_disconnected(&redis_client, &sender).await {
+            log::warn!("Failed to subscribe to events: {err}");
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+async fn relay_until_disconnected(
+    redis_client: &redis::Client,
+    sender: &broadcast::Sender<Arc<Message>>,
+) -> redis::RedisResult<()> {
+    let mut pubsub = redis_client.get_async_connection().await?.into_pubsub();
+    pubsub.psubscribe(CHANNEL_PATTERN).await?;
+
+    let mut messages = pubsub.into_on_message();
+    while let Some(msg) = messages.next().await {
+        match msg.get_payload::<String>() {
+            Ok(payload) => {
+                // Fails only while no stream is open, when there is nobody to tell.
+                let _ = sender.send(Arc::new(Message {
+                    channel: msg.get_channel_name().to_string(),
+                    payload,
+                }));
+            }
+            Err(err) => log::warn!("Dropped an event on {}: {err}", msg.get_channel_name()),
+        }
+    }
+
+    log::warn!("Lost the event subscription");
+    Ok(())
+}
+
+/// Publishes `event` on `channel`. Live updates are a convenience on top of the REST API, so a
+/// failure is logged rather than failing the request that caused it.
+pub async fn publish(data: &AppState, channel: &str, event: &LiveEvent) {
+    let payload = serde_json::to_string(event).expect("events always serialize");
+    let result = async {
+        let mut publisher = data.events.publisher().await?;
+        publisher.publish::<_, _, ()>(channel, payload).await
+    }
+        .await;
+
+    if let Err(err) = result {
+        *data.events.publisher.lock().await = None;
+        log::warn!("Failed to publish event on {channel}: {err}");
+    }
+}
+
+/// Everything published on `channel` from now on, as server-sent events. A stream that falls too
+/// far behind skips the events it missed rather than holding up the others.
+pub fn subscribe(data: &AppState, channel: &str) -> impl Stream<Item = Result<Event, Infallible>> {
+    let channel = channel.to_string();
+    let messages = stream::unfold(data.events.sender.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(message) => return Some((message, receiver)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    messages
+        .filter(move |message| future::ready(message.channel == channel))
+        .map(|message| Ok(Event::default().data(message.payload.clone())))
+}
+
+/// Issues a ticket that [`redeem_stream_ticket`] exchanges for `user_id`. Browsers cannot attach
+/// an `Authorization` header to an event stream, so the client fetches a ticket with its access
+/// token and passes it in the stream's query string instead of the token itself.
+pub async fn issue_stream_ticket(data: &AppState, user_id: Uuid) -> Result<Uuid, AppError> {
+    let ticket = Uuid::new_v4();
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    redis_client
+        .set_ex::<_, _, ()>(stream_ticket_key(ticket), user_id.to_string(), STREAM_TICKET_TTL)
+        .await?;
+
+    Ok(ticket)
+}
+
+/// The user a ticket was issued to. Each ticket works once.
+pub async fn redeem_stream_ticket(data: &AppState, ticket: Uuid) -> Result<Uuid, AppError> {
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    redis_client
+        .get_del::<_, Option<String>>(stream_ticket_key(ticket))
+        .await?
+        .and_then(|user_id| Uuid::parse_str(&user_id).ok())
+        .ok_or_else(|| AppError::Unauthorized("Invalid stream ticket".to_string()))
+}
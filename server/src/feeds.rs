@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+
+use atom_syndication::{Category, Content, Entry, Link, Person, Text};
+use chrono::{DateTime, Utc};
+use rss::extension::dublincore::{self, DublinCoreExtension};
+use rss::{Channel, Guid, Item};
+use serde::Serialize;
+
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+const RSS_CONTENT_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/content/";
+
+/// The formats every feed is served in, told apart by the file extension asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Atom,
+    Rss,
+    Json,
+}
+
+impl FeedFormat {
+    pub const ALL: [FeedFormat; 3] = [FeedFormat::Atom, FeedFormat::Rss, FeedFormat::Json];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "atom",
+            FeedFormat::Rss => "rss",
+            FeedFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+
+    /// Splits a feed's file name such as `rust.atom` into its name and format.
+    pub fn parse_file_name(file_name: &str) -> Option<(&str, FeedFormat)> {
+        let (name, extension) = file_name.rsplit_once('.')?;
+        let format = FeedFormat::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)?;
+
+        Some((name, format))
+    }
+}
+
+/// A list of posts to syndicate, with every link already absolute.
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+    /// The page on the site showing the same posts.
+    pub home_page_url: String,
+    /// Where this feed itself is served, in the format being rendered.
+    pub feed_url: String,
+    pub entries: Vec<FeedEntry>,
+}
+
+pub struct FeedEntry {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub summary: String,
+    pub content_html: String,
+    pub author_name: String,
+    pub author_url: String,
+    pub tags: Vec<String>,
+    pub published: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Feed {
+    /// When any entry last changed or went out, since a scheduled post is published without being
+    /// edited. An empty feed is dated to the epoch rather than to now, so that it renders the same
+    /// every time.
+    pub fn updated(&self) -> DateTime<Utc> {
+        self.entries
+            .iter()
+            .map(|entry| entry.updated.max(entry.published))
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn render(&self, format: FeedFormat) -> String {
+        match format {
+            FeedFormat::Atom => self.to_atom(),
+            FeedFormat::Rss => self.to_rss(),
+            FeedFormat::Json => self.to_json(),
+        }
+    }
+
+    fn to_atom(&self) -> String {
+        let feed = atom_syndication::Feed {
+            title: Text::plain(&self.title),
+            id: self.feed_url.clone(),
+            updated: self.updated().into(),
+            subtitle: Some(Text::plain(&self.description)),
+            links: vec![
+                Link {
+                    href: self.home_page_url.clone(),
+                    rel: "alternate".to_string(),
+                    mime_type: Some("text/html".to_string()),
+                    ..Default::default()
+                },
+                Link {
+                    href: self.feed_url.clone(),
+                    rel: "self".to_string(),
+                    mime_type: Some("application/atom+xml".to_string()),
+                    ..Default::default()
+                },
+            ],
+            entries: self
+                .entries
+                .iter()
+                .map(|entry| Entry {
+                    title: Text::plain(&entry.title),
+                    id: entry.id.clone(),
+                    updated: entry.updated.into(),
+                    published: Some(entry.published.into()),
+                    authors: vec![Person {
+                        name: entry.author_name.clone(),
+                        uri: Some(entry.author_url.clone()),
+                        ..Default::default()
+                    }],
+                    categories: entry
+                        .tags
+                        .iter()
+                        .map(|tag| Category {
+                            term: tag.clone(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    links: vec![Link {
+                        href: entry.url.clone(),
+                        rel: "alternate".to_string(),
+                        mime_type: Some("text/html".to_string()),
+                        ..Default::default()
+                    }],
+                    summary: Some(Text::plain(&entry.summary)),
+                    content: Some(Content {
+                        value: Some(entry.content_html.clone()),
+                        content_type: Some("html".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        feed.to_string()
+    }
+
+    fn to_rss(&self) -> String {
+        let channel = Channel {
+            title: self.title.clone(),
+            link: self.home_page_url.clone(),
+            description: self.description.clone(),
+            last_build_date: Some(self.updated().to_rfc2822()),
+            // The extensions below are only declared by the channel when asked to.
+            namespaces: BTreeMap::from([
+                ("content".to_string(), RSS_CONTENT_NAMESPACE.to_string()),
+                ("dc".to_string(), dublincore::NAMESPACE.to_string()),
+            ]),
+            items: self
+                .entries
+                .iter()
+                .map(|entry| Item {
+                    title: Some(entry.title.clone()),
+                    link: Some(entry.url.clone()),
+                    description: Some(entry.summary.clone()),
+                    // RSS wants an email address in `author`, which isn't ours to publish.
+                    dublin_core_ext: Some(DublinCoreExtension {
+                        creators: vec![entry.author_name.clone()],
+                        ..Default::default()
+                    }),
+                    categories: entry
+                        .tags
+                        .iter()
+                        .map(|tag| rss::Category {
+                            name: tag.clone(),
+                            domain: None,
+                        })
+                        .collect(),
+                    guid: Some(Guid {
+                        value: entry.id.clone(),
+                        permalink: false,
+                    }),
+                    pub_date: Some(entry.published.to_rfc2822()),
+                    content: Some(entry.content_html.clone()),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        channel.to_string()
+    }
+
+    fn to_json(&self) -> String {
+        let feed = JsonFeed {
+            version: JSON_FEED_VERSION,
+            title: &self.title,
+            description: &self.description,
+            home_page_url: &self.home_page_url,
+            feed_url: &self.feed_url,
+            items: self
+                .entries
+                .iter()
+                .map(|entry| JsonFeedItem {
+                    id: &entry.id,
+                    url: &entry.url,
+                    title: &entry.title,
+                    summary: &entry.summary,
+                    content_html: &entry.content_html,
+                    date_published: entry.published,
+                    date_modified: entry.updated,
+                    authors: vec![JsonFeedAuthor {
+                        name: &entry.author_name,
+                        url: &entry.author_url,
+                    }],
+                    tags: &entry.tags,
+                })
+                .collect(),
+        };
+
+        serde_json::to_string(&feed).expect("a feed always serializes")
+    }
+}
+
+/// <https://www.jsonfeed.org/version/1.1/>
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    description: &'a str,
+    home_page_url: &'a str,
+    feed_url: &'a str,
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: &'a str,
+    url: &'a str,
+    title: &'a str,
+    summary: &'a str,
+    content_html: &'a str,
+    date_published: DateTime<Utc>,
+    date_modified: DateTime<Utc>,
+    authors: Vec<JsonFeedAuthor<'a>>,
+    tags: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor<'a> {
+    name: &'a str,
+    url: &'a str,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn entry(published: DateTime<Utc>, updated: DateTime<Utc>) -> FeedEntry {
+        FeedEntry {
+            id: "urn:uuid:00000000-0000-0000-0000-000000000000".to_string(),
+            url: "https://example.com/posts/post".to_string(),
+            title: "Post".to_string(),
+            summary: "A post".to_string(),
+            content_html: "<p>A post</p>".to_string(),
+            author_name: "alice".to_string(),
+            author_url: "https://example.com/profile/alice".to_string(),
+            tags: Vec::new(),
+            published,
+            updated,
+        }
+    }
+
+    fn feed(entries: Vec<FeedEntry>) -> Feed {
+        Feed {
+            title: "Posts".to_string(),
+            description: "The latest posts".to_string(),
+            home_page_url: "https://example.com/".to_string(),
+            feed_url: "https://example.com/feeds/all.atom".to_string(),
+            entries,
+        }
+    }
+
+    #[test]
+    fn parse_file_name_splits_off_the_format() {
+        assert_eq!(FeedFormat::parse_file_name("all.atom"), Some(("all", FeedFormat::Atom)));
+        assert_eq!(FeedFormat::parse_file_name("rust.rss"), Some(("rust", FeedFormat::Rss)));
+        assert_eq!(FeedFormat::parse_file_name("alice.json"), Some(("alice", FeedFormat::Json)));
+        assert_eq!(FeedFormat::parse_file_name("node.js.json"), Some(("node.js", FeedFormat::Json)));
+        assert_eq!(FeedFormat::parse_file_name(".atom"), Some(("", FeedFormat::Atom)));
+    }
+
+    #[test]
+    fn parse_file_name_rejects_unknown_formats() {
+        assert_eq!(FeedFormat::parse_file_name("all"), None);
+        assert_eq!(FeedFormat::parse_file_name("all.xml"), None);
+        assert_eq!(FeedFormat::parse_file_name("all.ATOM"), None);
+        assert_eq!(FeedFormat::parse_file_name("all.atom."), None);
+    }
+
+    #[test]
+    fn updated_counts_publishing_as_a_change() {
+        let written = Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap();
+        let published = Utc.with_ymd_and_hms(2023, 10, 5, 0, 0, 0).unwrap();
+        let edited = Utc.with_ymd_and_hms(2023, 10, 3, 0, 0, 0).unwrap();
+
+        assert_eq!(feed(vec![entry(published, written), entry(written, edited)]).updated(), published);
+        assert_eq!(feed(Vec::new()).updated(), DateTime::<Utc>::default());
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use rand_core::{OsRng, RngCore};
+use redis::AsyncCommands;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::dtos::user::{ForgotPasswordDto, ResetPasswordDto, VerifyEmailDto};
+use crate::dtos::Wrapper;
+use crate::error::AppError;
+use crate::handlers::user::{get_hashed_password, revoke_all_sessions};
+use crate::mailer::Email;
+use crate::middlewares::auth::AuthUserClaims;
+use crate::validation::ValidatedJson;
+
+fn email_verification_key(token: &str) -> String {
+    format!("email_verification:{token}")
+}
+
+fn password_reset_key(token: &str) -> String {
+    format!("password_reset:{token}")
+}
+
+/// The one reset token of a user that is still outstanding; asking again replaces it.
+fn user_password_reset_key(user_id: Uuid) -> String {
+    format!("user:{user_id}:password_reset")
+}
+
+/// Marks the email address a verification link was sent to as verified. Links for an address the
+/// user has since changed no longer work.
+pub async fn verify_email(
+    State(data): State<Arc<AppState>>,
+    ValidatedJson(body): ValidatedJson<Wrapper<VerifyEmailDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let invalid = || AppError::NotFound("The verification link is invalid or has expired".to_string());
+
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    let stored = redis_client
+        .get_del::<_, Option<String>>(email_verification_key(&body.data.token))
+        .await?
+        .ok_or_else(invalid)?;
+    let (user_id, email) = stored.split_once(':').ok_or_else(invalid)?;
+    let user_id = Uuid::parse_str(user_id).map_err(|_| invalid())?;
+
+    sqlx::query_scalar!(
+        r#"
+            UPDATE users
+            SET email_verified_at = COALESCE(email_verified_at, NOW())
+            WHERE id = $1 AND email = $2
+            RETURNING id
+        "#,
+        user_id,
+        email,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Email verified",
+        "data": email,
+    }))))
+}
+
+pub async fn resend_verification_email(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = auth_user_claims
+        .user
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    if user.email_verified_at.is_some() {
+        return Err(AppError::Conflict("Email is already verified".to_string()));
+    }
+
+    let token = issue_email_verification_token(&data, user.id, &user.email).await?;
+    data.mailer.send(email_verification_email(&data, &user.email, &token)).await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Verification email sent",
+        "data": (),
+    }))))
+}
+
+/// Emails a password reset link if an account uses the address. The response is the same either
+/// way, so the endpoint can't be used to find out who has an account.
+pub async fn forgot_password(
+    State(data): State<Arc<AppState>>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ForgotPasswordDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", body.data.email)
+        .fetch_optional(&data.db)
+        .await?;
+
+    if let Some(user_id) = user_id {
+        let token = issue_password_reset_token(&data, user_id).await?;
+        let email = password_reset_email(&data, &body.data.email, &token);
+        if let Err(err) = data.mailer.send(email).await {
+            log::warn!("Failed to send password reset email: {err}");
+        }
+    }
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "If an account uses that email, a password reset link has been sent to it",
+        "data": (),
+    }))))
+}
+
+/// Sets a new password with a token from [`forgot_password`] and signs the user out everywhere.
+/// Following the link also proves the email address is theirs, so it counts as verified.
+pub async fn reset_password(
+    State(data): State<Arc<AppState>>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ResetPasswordDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let invalid = || AppError::NotFound("The password reset link is invalid or has expired".to_string());
+
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    let user_id = redis_client
+        .get_del::<_, Option<String>>(password_reset_key(&body.data.token))
+        .await?
+        .and_then(|user_id| Uuid::parse_str(&user_id).ok())
+        .ok_or_else(invalid)?;
+    redis_client.del::<_, ()>(user_password_reset_key(user_id)).await?;
+
+    let hashed_password = get_hashed_password(&body.data.password)?;
+    sqlx::query_scalar!(
+        r#"
+            UPDATE users
+            SET password = $1, email_verified_at = COALESCE(email_verified_at, NOW())
+            WHERE id = $2
+            RETURNING id
+        "#,
+        hashed_password,
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    revoke_all_sessions(&data, user_id, None).await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Password reset",
+        "data": (),
+    }))))
+}
+
+/// Emails a link that confirms `email` belongs to `user_id`. Failures are only logged: the sign-up
+/// or email change that asked for it has already happened, and another link can be requested.
+pub(crate) async fn send_verification_email(data: &AppState, user_id: Uuid, email: &str) {
+    let sent = async {
+        let token = issue_email_verification_token(data, user_id, email).await?;
+        data.mailer.send(email_verification_email(data, email, &token)).await
+    }
+        .await;
+
+    if let Err(err) = sent {
+        log::warn!("Failed to send verification email: {err}");
+    }
+}
+
+async fn issue_email_verification_token(data: &AppState, user_id: Uuid, email: &str) -> Result<String, AppError> {
+    let token = random_token();
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    redis_client
+        .set_ex::<_, _, ()>(
+            email_verification_key(&token),
+            format!("{user_id}:{email}"),
+            (data.env.email_verification_token_max_age * 60) as usize,
+        )
+        .await?;
+
+    Ok(token)
+}
+
+async fn issue_password_reset_token(data: &AppState, user_id: Uuid) -> Result<String, AppError> {
+    let token = random_token();
+    let ttl = (data.env.password_reset_token_max_age * 60) as usize;
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+
+    let previous_token = redis_client
+        .get::<_, Option<String>>(user_password_reset_key(user_id))
+        .await?;
+
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    if let Some(previous_token) = previous_token {
+        pipe.del(password_reset_key(&previous_token)).ignore();
+    }
+    pipe.set_ex(password_reset_key(&token), user_id.to_string(), ttl)
+        .ignore()
+        .set_ex(user_password_reset_key(user_id), &token, ttl)
+        .ignore()
+        .query_async::<_, ()>(&mut redis_client)
+        .await?;
+
+    Ok(token)
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn email_verification_email(data: &AppState, to: &str, token: &str) -> Email {
+    Email {
+        to: to.to_string(),
+        subject: "Confirm your email address".to_string(),
+        body: format!(
+            "Please confirm that this is your email address by opening the link below.\n\n\
+             {}/users/verify_email/{token}\n\n\
+             The link expires in {}. If you didn't sign up for negatiview, you can ignore this email.\n",
+            data.env.app_url,
+            format_minutes(data.env.email_verification_token_max_age),
+        ),
+    }
+}
+
+fn password_reset_email(data: &AppState, to: &str, token: &str) -> Email {
+    Email {
+        to: to.to_string(),
+        subject: "Reset your password".to_string(),
+        body: format!(
+            "Someone asked to reset the password of your negatiview account. To choose a new one, \
+             open the link below.\n\n\
+             {}/users/reset_password/{token}\n\n\
+             The link works once and expires in {}. If it wasn't you, you can ignore this email; \
+             your password stays the same.\n",
+            data.env.app_url,
+            format_minutes(data.env.password_reset_token_max_age),
+        ),
+    }
+}
+
+fn format_minutes(minutes: i64) -> String {
+    match minutes {
+        1 => "1 minute".to_string(),
+        minutes if minutes % 60 != 0 => format!("{minutes} minutes"),
+        60 => "1 hour".to_string(),
+        minutes => format!("{} hours", minutes / 60),
+    }
+}
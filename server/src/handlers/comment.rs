@@ -1,79 +1,102 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{Extension, Json};
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use futures::TryStreamExt;
-use serde_json::{json, Value};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
 
 use crate::config::AppState;
+use crate::error::AppError;
 use crate::dtos::comment::*;
 use crate::dtos::Wrapper;
+use crate::events::{post_channel, publish, LiveEvent};
+use crate::handlers::profile::is_blocked;
 use crate::middlewares::auth::AuthUserClaims;
 use crate::models::comment::CommentFromQuery;
+use crate::models::notification::NotificationKind;
+use crate::notifications::{announce, notify_post_author};
+use crate::pagination::{Cursor, Direction, PageRequest};
+use crate::validation::ValidatedJson;
+
+/// How many levels of replies are loaded below each listed comment unless `depth` says otherwise.
+const DEFAULT_THREAD_DEPTH: usize = 3;
+const MAX_THREAD_DEPTH: usize = 5;
+/// How many replies are loaded per comment inside a tree; the rest are paged in with `get_replies`.
+const REPLIES_PER_THREAD: i64 = 5;
+
+#[derive(Deserialize, Default)]
+pub struct CommentQuery {
+    limit: Option<i64>,
+    cursor: Option<String>,
+    depth: Option<usize>,
+}
 
 pub async fn get_comments(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
-    Path(post_id): Path<uuid::Uuid>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let user_id = &auth_user_claims.user_id().unwrap_or_default();
+    Path(post_id): Path<Uuid>,
+    query: Query<CommentQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
 
-    let post_id = sqlx::query_scalar!("SELECT id FROM posts WHERE id = $1", post_id)
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to get post: {err}"),
-                }))
-            )
-        })?;
-
-    let comments: Vec<CommentDto> = sqlx::query_as!(
-        CommentFromQuery,
+    // Drafts, scheduled and hidden posts only have comments for their authors to see.
+    let post_id = sqlx::query_scalar!(
         r#"
-            SELECT
-                comments.id,
-                body,
-                comments.created_at,
-                comments.updated_at,
-                author.display_name AS author_display_name,
-                author.biography AS author_biography,
-                author.profile_image_url AS author_profile_image_url,
-                EXISTS (SELECT 1 FROM user_follows WHERE followee_user_id = author.id AND follower_user_id = $1) "following_author!"
-            FROM comments
-            INNER JOIN users AS author ON author.id = comments.user_id
-            WHERE post_id = $2
-            ORDER BY created_at
+            SELECT id FROM posts
+            WHERE id = $1
+                AND ((status = 'published' AND hidden_at IS NULL) OR user_id = $2)
         "#,
-        user_id,
         post_id,
+        user_id,
     )
-        .fetch(&data.db)
-        .map_ok(|comment| comment.into_comment_dto())
-        .try_collect()
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to get comments: {err}"),
-                }))
-            )
-        })?;
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    let comments = list_comments(&data, user_id, post_id, None, &query).await?;
 
     let json_response = json!({
         "status": "success",
         "message": "Comments fetched",
-        "data": CommentsDto {
-            count: comments.len(),
-            comments
-        }
+        "data": comments
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+pub async fn get_replies(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path((post_id, comment_id)): Path<(Uuid, Uuid)>,
+    query: Query<CommentQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+
+    let comment_id = sqlx::query_scalar!(
+        r#"
+            SELECT comments.id FROM comments
+            INNER JOIN posts ON posts.id = comments.post_id
+            WHERE comments.id = $1 AND comments.post_id = $2
+                AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $3)
+        "#,
+        comment_id,
+        post_id,
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    let replies = list_comments(&data, user_id, post_id, Some(comment_id), &query).await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Replies fetched",
+        "data": replies
     });
 
     Ok((StatusCode::OK, Json(json_response)))
@@ -82,10 +105,12 @@ pub async fn get_comments(
 pub async fn new_comment(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
-    Path(post_id): Path<uuid::Uuid>,
-    Json(body): Json<Wrapper<NewCommentDto>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    Path(post_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<NewCommentDto>>,
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
+    ensure_not_blocked_by_author(&data, post_id, *user_id).await?;
+
     let comment = sqlx::query_as!(
         CommentFromQuery,
         r#"
@@ -94,13 +119,19 @@ pub async fn new_comment(
                 SELECT id, $1, $2
                 FROM posts
                 WHERE posts.id = $3
-                RETURNING id, body, created_at, updated_at
+                    AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $1)
+                RETURNING id, parent_id, body, deleted_at, hidden_at, created_at, updated_at, edited_at
             )
             SELECT
                 the_comment.id,
+                the_comment.parent_id,
                 body,
+                the_comment.deleted_at,
+                the_comment.hidden_at,
+                0::BIGINT "reply_count!",
                 the_comment.created_at,
                 the_comment.updated_at,
+                the_comment.edited_at,
                 author.display_name AS author_display_name,
                 author.biography AS author_biography,
                 author.profile_image_url AS author_profile_image_url,
@@ -112,17 +143,18 @@ pub async fn new_comment(
         body.data.body,
         post_id,
     )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to create comment: {err}"),
-                }))
-            )
-        })?;
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    let recipient_id = notify_post_author(&data.db, *user_id, NotificationKind::Comment, post_id, Some(comment.id)).await?;
+    announce(&data, recipient_id).await?;
+    publish(&data, &post_channel(post_id), &LiveEvent::CommentAdded {
+        post_id,
+        comment_id: comment.id,
+        author_display_name: comment.author_display_name.clone(),
+    })
+        .await;
 
     let json_response = json!({
         "status": "success",
@@ -133,63 +165,480 @@ pub async fn new_comment(
     Ok((StatusCode::CREATED, Json(json_response)))
 }
 
-pub async fn delete_comment(
+pub async fn reply_comment(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
-    Path((post_id, comment_id)): Path<(uuid::Uuid, uuid::Uuid)>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    Path((post_id, comment_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(body): ValidatedJson<Wrapper<NewCommentDto>>,
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
-    let result = sqlx::query!(
+    ensure_not_blocked_by_author(&data, post_id, *user_id).await?;
+
+    let comment = sqlx::query_as!(
+        CommentFromQuery,
         r#"
             WITH the_comment AS (
-                DELETE FROM comments
-                WHERE
-                    id = $1 AND
-                    post_id IN (SELECT id FROM posts WHERE id = $2) AND
-                    user_id = $3
-                RETURNING 1
+                INSERT INTO comments (post_id, user_id, body, parent_id)
+                SELECT post_id, $1, $2, id
+                FROM comments
+                WHERE id = $3 AND post_id = $4 AND deleted_at IS NULL AND hidden_at IS NULL
+                    AND EXISTS (
+                        SELECT 1 FROM posts
+                        WHERE posts.id = comments.post_id
+                            AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $1)
+                    )
+                RETURNING id, parent_id, body, deleted_at, hidden_at, created_at, updated_at, edited_at
             )
             SELECT
-                EXISTS (
-                    SELECT 1 FROM comments
-                    INNER JOIN posts ON posts.id = comments.post_id
-                    WHERE comments.id = $1 AND posts.id = $2
-                ) "existed!",
-                EXISTS (
-                    SELECT 1 FROM the_comment
-                ) "deleted!"
+                the_comment.id,
+                the_comment.parent_id,
+                body,
+                the_comment.deleted_at,
+                the_comment.hidden_at,
+                0::BIGINT "reply_count!",
+                the_comment.created_at,
+                the_comment.updated_at,
+                the_comment.edited_at,
+                author.display_name AS author_display_name,
+                author.biography AS author_biography,
+                author.profile_image_url AS author_profile_image_url,
+                FALSE "following_author!"
+            FROM the_comment
+            INNER JOIN users AS author ON author.id = $1
+        "#,
+        user_id,
+        body.data.body,
+        comment_id,
+        post_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    let recipient_id = notify_post_author(&data.db, *user_id, NotificationKind::Comment, post_id, Some(comment.id)).await?;
+    announce(&data, recipient_id).await?;
+    publish(&data, &post_channel(post_id), &LiveEvent::CommentAdded {
+        post_id,
+        comment_id: comment.id,
+        author_display_name: comment.author_display_name.clone(),
+    })
+        .await;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Reply created",
+        "data": comment.into_comment_dto()
+    });
+
+    Ok((StatusCode::CREATED, Json(json_response)))
+}
+
+/// Replaces the body of a comment, keeping the previous body as a revision.
+pub async fn update_comment(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path((post_id, comment_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(body): ValidatedJson<Wrapper<UpdateCommentDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = &auth_user_claims.user_id().unwrap_or_default();
+    let mut tx = data.db.begin().await?;
+
+    let comment = sqlx::query!(
+        r#"
+            SELECT user_id, body, created_at, edited_at, deleted_at, hidden_at
+            FROM comments
+            WHERE id = $1 AND post_id = $2
+            FOR UPDATE
+        "#,
+        comment_id,
+        post_id,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .filter(|comment| comment.deleted_at.is_none())
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    if comment.user_id != *user_id {
+        return Err(AppError::Forbidden("Comment is not yours".to_string()));
+    }
+
+    if comment.hidden_at.is_some() {
+        return Err(AppError::Forbidden("Comment has been hidden by a moderator".to_string()));
+    }
+
+    if comment.body != body.data.body {
+        sqlx::query!(
+            "INSERT INTO comment_revisions (comment_id, body, written_at) VALUES ($1, $2, $3)",
+            comment_id,
+            comment.body,
+            comment.edited_at.unwrap_or(comment.created_at),
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "UPDATE comments SET body = $1, edited_at = NOW(), updated_at = NOW() WHERE id = $2",
+            body.data.body,
+            comment_id,
+        )
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let comment = sqlx::query_as!(
+        CommentFromQuery,
+        r#"
+            SELECT
+                comments.id,
+                comments.parent_id,
+                body,
+                comments.deleted_at,
+                comments.hidden_at,
+                (SELECT COUNT(*) FROM comments AS replies WHERE replies.parent_id = comments.id) "reply_count!",
+                comments.created_at,
+                comments.updated_at,
+                comments.edited_at,
+                author.display_name AS author_display_name,
+                author.biography AS author_biography,
+                author.profile_image_url AS author_profile_image_url,
+                FALSE "following_author!"
+            FROM comments
+            INNER JOIN users AS author ON author.id = comments.user_id
+            WHERE comments.id = $1
+        "#,
+        comment_id,
+    )
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Comment updated",
+        "data": comment.into_comment_dto()
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+pub async fn get_comment_revisions(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path((post_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+
+    sqlx::query_scalar!(
+        r#"
+            SELECT comments.id FROM comments
+            INNER JOIN posts ON posts.id = comments.post_id
+            WHERE comments.id = $1 AND comments.post_id = $2
+                AND comments.deleted_at IS NULL AND comments.hidden_at IS NULL
+                AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $3)
         "#,
         comment_id,
         post_id,
         user_id,
     )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to delete comment: {err}"),
-                }))
-            )
-        })?;
-
-    if result.deleted {
-        Ok((StatusCode::OK, Json(json!({
-            "status": "success",
-            "message": "Comment deleted",
-            "data": comment_id,
-        }))))
-    } else if result.existed {
-        Err((StatusCode::FORBIDDEN, Json(json!({
-            "status": "fail",
-            "message": "Comment is not yours",
-        }))))
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    let revisions = sqlx::query_as!(
+        CommentRevisionDto,
+        r#"
+            SELECT id, body, written_at, replaced_at
+            FROM comment_revisions
+            WHERE comment_id = $1
+            ORDER BY replaced_at DESC
+        "#,
+        comment_id,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Comment revisions fetched",
+        "data": CommentRevisionsDto {
+            count: revisions.len(),
+            revisions,
+        }
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// Deletes one of the caller's comments; see [`remove_comment`].
+pub async fn delete_comment(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path((post_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = &auth_user_claims.user_id().unwrap_or_default();
+    let mut tx = data.db.begin().await?;
+
+    let comment = sqlx::query!(
+        r#"
+            SELECT
+                user_id,
+                parent_id,
+                deleted_at,
+                EXISTS (SELECT 1 FROM comments AS replies WHERE replies.parent_id = comments.id) "has_replies!"
+            FROM comments
+            WHERE id = $1 AND post_id = $2
+            FOR UPDATE
+        "#,
+        comment_id,
+        post_id,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .filter(|comment| comment.deleted_at.is_none())
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    if comment.user_id != *user_id {
+        return Err(AppError::Forbidden("Comment is not yours".to_string()));
+    }
+
+    remove_comment(&mut tx, comment_id, comment.parent_id, comment.has_replies).await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Comment deleted",
+        "data": comment_id,
+    }))))
+}
+
+/// Deletes a comment. Comments with replies become tombstones so their threads stay intact, and
+/// deleting the last reply of a tombstone removes the tombstone as well.
+pub(crate) async fn remove_comment(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    comment_id: Uuid,
+    mut parent_id: Option<Uuid>,
+    has_replies: bool,
+) -> Result<(), AppError> {
+    if has_replies {
+        sqlx::query!(
+            "UPDATE comments SET body = '', deleted_at = NOW(), updated_at = NOW() WHERE id = $1",
+            comment_id,
+        )
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM comment_revisions WHERE comment_id = $1", comment_id)
+            .execute(&mut **tx)
+            .await?;
     } else {
-        Err((StatusCode::NOT_FOUND, Json(json!({
-            "status": "fail",
-            "message": "Comment not found",
-        }))))
+        sqlx::query!("DELETE FROM comments WHERE id = $1", comment_id)
+            .execute(&mut **tx)
+            .await?;
+
+        while let Some(id) = parent_id {
+            parent_id = sqlx::query_scalar!(
+                r#"
+                    DELETE FROM comments
+                    WHERE id = $1
+                        AND deleted_at IS NOT NULL
+                        AND NOT EXISTS (SELECT 1 FROM comments AS replies WHERE replies.parent_id = $1)
+                    RETURNING parent_id
+                "#,
+                id,
+            )
+                .fetch_optional(&mut **tx)
+                .await?
+                .flatten();
+        }
     }
+
+    Ok(())
+}
+
+/// Pages through the comments directly below `parent_id` (top-level comments when `None`) in
+/// posting order and loads the replies below them.
+async fn list_comments(
+    data: &AppState,
+    user_id: Uuid,
+    post_id: Uuid,
+    parent_id: Option<Uuid>,
+    query: &CommentQuery,
+) -> Result<CommentsDto, AppError> {
+    let page_request = PageRequest::new(query.cursor.as_deref(), query.limit)?;
+    let depth = query.depth.unwrap_or(DEFAULT_THREAD_DEPTH).min(MAX_THREAD_DEPTH);
+
+    let rows = sqlx::query_as!(
+        CommentFromQuery,
+        r#"
+            SELECT
+                comments.id,
+                comments.parent_id,
+                body,
+                comments.deleted_at,
+                comments.hidden_at,
+                (SELECT COUNT(*) FROM comments AS replies WHERE replies.parent_id = comments.id) "reply_count!",
+                comments.created_at,
+                comments.updated_at,
+                comments.edited_at,
+                author.display_name AS author_display_name,
+                author.biography AS author_biography,
+                author.profile_image_url AS author_profile_image_url,
+                EXISTS (SELECT 1 FROM user_follows WHERE followee_user_id = author.id AND follower_user_id = $1) "following_author!"
+            FROM comments
+            INNER JOIN users AS author ON author.id = comments.user_id
+            WHERE comments.post_id = $2
+                AND comments.parent_id IS NOT DISTINCT FROM $3
+                AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = comments.user_id)
+                AND (
+                    $4::TIMESTAMPTZ IS NULL
+                    OR ( $6 AND (comments.created_at, comments.id) < ($4, $5::UUID) )
+                    OR ( NOT $6 AND (comments.created_at, comments.id) > ($4, $5::UUID) )
+                )
+            ORDER BY
+                CASE WHEN $6 THEN comments.created_at END DESC,
+                CASE WHEN $6 THEN comments.id END DESC,
+                comments.created_at ASC,
+                comments.id ASC
+            LIMIT $7
+        "#,
+        user_id,
+        post_id,
+        parent_id,
+        page_request.timestamp(),
+        page_request.id(),
+        page_request.is_backward(),
+        page_request.fetch_limit(),
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let page = page_request.finish(rows, |comment| (comment.created_at, comment.id));
+    let comments = with_replies(data, user_id, page.rows, depth).await?;
+
+    Ok(CommentsDto {
+        count: comments.len(),
+        comments,
+        next_cursor: page.next_cursor,
+        prev_cursor: page.prev_cursor,
+    })
+}
+
+/// Loads up to `depth` levels of replies below `comments`, one query per level, and nests them.
+async fn with_replies(
+    data: &AppState,
+    user_id: Uuid,
+    comments: Vec<CommentFromQuery>,
+    depth: usize,
+) -> Result<Vec<CommentDto>, AppError> {
+    let mut levels = vec![comments];
+    while levels.len() <= depth {
+        let parent_ids: Vec<Uuid> = levels
+            .last()
+            .into_iter()
+            .flatten()
+            .filter(|comment| comment.reply_count > 0)
+            .map(|comment| comment.id)
+            .collect();
+        if parent_ids.is_empty() {
+            break;
+        }
+
+        let replies = sqlx::query_as!(
+            CommentFromQuery,
+            r#"
+                SELECT
+                    replies.id "id!",
+                    replies.parent_id,
+                    replies.body "body!",
+                    replies.deleted_at,
+                    replies.hidden_at,
+                    replies.reply_count "reply_count!",
+                    replies.created_at "created_at!",
+                    replies.updated_at "updated_at!",
+                    replies.edited_at,
+                    replies.author_display_name "author_display_name!",
+                    replies.author_biography,
+                    replies.author_profile_image_url,
+                    replies.following_author "following_author!"
+                FROM unnest($2::UUID[]) AS parents(id)
+                CROSS JOIN LATERAL (
+                    SELECT
+                        comments.id,
+                        comments.parent_id,
+                        comments.body,
+                        comments.deleted_at,
+                        comments.hidden_at,
+                        (SELECT COUNT(*) FROM comments AS replies WHERE replies.parent_id = comments.id) AS reply_count,
+                        comments.created_at,
+                        comments.updated_at,
+                        comments.edited_at,
+                        author.display_name AS author_display_name,
+                        author.biography AS author_biography,
+                        author.profile_image_url AS author_profile_image_url,
+                        EXISTS (SELECT 1 FROM user_follows WHERE followee_user_id = author.id AND follower_user_id = $1) AS following_author
+                    FROM comments
+                    INNER JOIN users AS author ON author.id = comments.user_id
+                    WHERE comments.parent_id = parents.id
+                        AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = comments.user_id)
+                    ORDER BY comments.created_at, comments.id
+                    LIMIT $3
+                ) AS replies
+                ORDER BY replies.created_at, replies.id
+            "#,
+            user_id,
+            &parent_ids[..],
+            REPLIES_PER_THREAD,
+        )
+            .fetch_all(&data.db)
+            .await?;
+
+        levels.push(replies);
+    }
+
+    let mut replies_by_parent: HashMap<Uuid, Vec<CommentDto>> = HashMap::new();
+    let mut comments = Vec::new();
+    while let Some(level) = levels.pop() {
+        let is_top_level = levels.is_empty();
+        let mut parents: HashMap<Uuid, Vec<CommentDto>> = HashMap::new();
+
+        for row in level {
+            let mut comment = row.into_comment_dto();
+            comment.replies = replies_by_parent.remove(&comment.id).unwrap_or_default();
+            if comment.reply_count > comment.replies.len() as i64 {
+                comment.replies_cursor = comment.replies.last().map(|reply| {
+                    Cursor {
+                        direction: Direction::After,
+                        timestamp: reply.created_at,
+                        id: reply.id,
+                    }
+                    .encode()
+                });
+            }
+
+            match comment.parent_id {
+                Some(parent_id) if !is_top_level => parents.entry(parent_id).or_default().push(comment),
+                _ => comments.push(comment),
+            }
+        }
+
+        replies_by_parent = parents;
+    }
+
+    Ok(comments)
+}
+
+/// Authors who blocked `user_id` don't get comments from them.
+async fn ensure_not_blocked_by_author(data: &AppState, post_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+    let author_id = sqlx::query_scalar!("SELECT user_id FROM posts WHERE id = $1", post_id)
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    if is_blocked(data, author_id, user_id).await? {
+        return Err(AppError::Forbidden("The author of this post has blocked you".to_string()));
+    }
+
+    Ok(())
 }
@@ -0,0 +1,77 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::Stream;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::dtos::event::StreamTicketDto;
+use crate::error::AppError;
+use crate::events::{issue_stream_ticket, post_channel, redeem_stream_ticket, subscribe, user_channel};
+use crate::middlewares::auth::AuthUserClaims;
+
+#[derive(Deserialize)]
+pub struct StreamQuery {
+    ticket: Uuid,
+}
+
+/// New comments and favorite counts for a post, for as long as the reader keeps it open.
+pub async fn post_events(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(post_id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+
+    sqlx::query_scalar!(
+        r#"
+            SELECT id FROM posts
+            WHERE id = $1
+                AND ((status = 'published' AND hidden_at IS NULL) OR user_id = $2)
+        "#,
+        post_id,
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    let stream = subscribe(&data, &post_channel(post_id));
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// A single-use ticket for opening [`notification_events`].
+pub async fn stream_ticket(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+    let ticket = issue_stream_ticket(&data, user_id).await?;
+
+    Ok((StatusCode::CREATED, Json(json!({
+        "status": "success",
+        "message": "Stream ticket issued",
+        "data": StreamTicketDto { ticket },
+    }))))
+}
+
+/// The ticket holder's unread notification count whenever it changes.
+pub async fn notification_events(
+    State(data): State<Arc<AppState>>,
+    Query(query): Query<StreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let user_id = redeem_stream_ticket(&data, query.ticket).await?;
+    let stream = subscribe(&data, &user_channel(user_id));
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use sha2::{Digest, Sha256};
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::feeds::{Feed, FeedEntry, FeedFormat};
+use crate::handlers::post::{query_posts, PostQuery};
+use crate::pagination::PageRequest;
+use crate::seo::encode_segment;
+
+/// How many of the latest posts a feed carries.
+const FEED_LENGTH: i64 = 20;
+const CACHE_CONTROL: &str = "public, max-age=300";
+const SITE_NAME: &str = "negatiview";
+
+/// `/feeds/all.{atom,rss,json}`: the latest published posts.
+pub async fn all_feed(
+    State(data): State<Arc<AppState>>,
+    Path(file_name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let (name, format) = parse_file_name(&file_name)?;
+    if name != "all" {
+        return Err(AppError::NotFound("Feed not found".to_string()));
+    }
+
+    let app_url = &data.env.app_url;
+    let feed = build_feed(
+        &data,
+        PostQuery::default(),
+        format!("{SITE_NAME}: latest posts"),
+        format!("The latest posts on {SITE_NAME}"),
+        format!("{app_url}/"),
+        format!("{app_url}/feeds/all.{}", format.extension()),
+    )
+        .await?;
+
+    Ok(respond(&feed, format, &headers))
+}
+
+/// `/feeds/tag/:tag.{atom,rss,json}`: the latest published posts with a tag.
+pub async fn tag_feed(
+    State(data): State<Arc<AppState>>,
+    Path(file_name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let (tag, format) = parse_file_name(&file_name)?;
+
+    let app_url = &data.env.app_url;
+    let feed = build_feed(
+        &data,
+        PostQuery {
+            tag: Some(tag.to_string()),
+            ..Default::default()
+        },
+        format!("{SITE_NAME}: #{tag}"),
+        format!("The latest posts tagged #{tag} on {SITE_NAME}"),
+        format!("{app_url}/"),
+        format!("{app_url}/feeds/tag/{}.{}", encode_segment(tag), format.extension()),
+    )
+        .await?;
+
+    Ok(respond(&feed, format, &headers))
+}
+
+/// `/feeds/author/:display_name.{atom,rss,json}`: the latest posts someone has published.
+pub async fn author_feed(
+    State(data): State<Arc<AppState>>,
+    Path(file_name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let (display_name, format) = parse_file_name(&file_name)?;
+
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM users WHERE display_name = $1) "exists!""#,
+        display_name,
+    )
+        .fetch_one(&data.db)
+        .await?;
+    if !exists {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
+
+    let app_url = &data.env.app_url;
+    let display_name_segment = encode_segment(display_name);
+    let feed = build_feed(
+        &data,
+        PostQuery {
+            author: Some(display_name.to_string()),
+            ..Default::default()
+        },
+        format!("{SITE_NAME}: {display_name}"),
+        format!("The latest posts by {display_name} on {SITE_NAME}"),
+        format!("{app_url}/profile/{display_name_segment}"),
+        format!("{app_url}/feeds/author/{display_name_segment}.{}", format.extension()),
+    )
+        .await?;
+
+    Ok(respond(&feed, format, &headers))
+}
+
+fn parse_file_name(file_name: &str) -> Result<(&str, FeedFormat), AppError> {
+    FeedFormat::parse_file_name(file_name)
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or_else(|| AppError::NotFound("Feed not found".to_string()))
+}
+
+/// The feed of the posts `post_list` would list for `query` to someone signed out.
+async fn build_feed(
+    data: &AppState,
+    query: PostQuery,
+    title: String,
+    description: String,
+    home_page_url: String,
+    feed_url: String,
+) -> Result<Feed, AppError> {
+    let page_request = PageRequest::new(None, Some(FEED_LENGTH))?;
+    let mut rows = query_posts(&data.db, &uuid::Uuid::nil(), &query, &page_request).await?;
+    rows.truncate(page_request.limit as usize);
+
+    let app_url = &data.env.app_url;
+    let entries = rows
+        .into_iter()
+        .map(|post| {
+            let rendered = data.markdown.render_post(post.id, post.updated_at, &post.body);
+            let published = post.listed_at();
+            FeedEntry {
+                id: format!("urn:uuid:{}", post.id),
+                url: format!("{app_url}/posts/{}", encode_segment(&post.slug)),
+                title: post.title,
+                summary: post.description,
+                content_html: rendered.html.clone(),
+                author_url: format!("{app_url}/profile/{}", encode_segment(&post.author_display_name)),
+                author_name: post.author_display_name,
+                tags: post.tags,
+                published,
+                updated: post.updated_at,
+            }
+        })
+        .collect();
+
+    Ok(Feed {
+        title,
+        description,
+        home_page_url,
+        feed_url,
+        entries,
+    })
+}
+
+/// Renders the feed, or answers `304 Not Modified` when `If-None-Match` has the hash of what it
+/// renders to. `If-Modified-Since` alone never does: a post that is deleted, hidden or archived
+/// drops out of the feed without making any entry newer.
+fn respond(feed: &Feed, format: FeedFormat, request_headers: &HeaderMap) -> Response {
+    let body = feed.render(format);
+
+    let etag = format!("\"{}\"", URL_SAFE_NO_PAD.encode(Sha256::digest(body.as_bytes())));
+    let last_modified = httpdate::fmt_http_date(SystemTime::from(feed.updated()));
+
+    let not_modified = request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|if_none_match| if_none_match.to_str().ok())
+        .map(|tags| tags.split(',').map(str::trim).any(|tag| tag == etag || tag == "*"))
+        .unwrap_or(false);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+    headers.insert(header::ETAG, HeaderValue::from_str(&etag).expect("a base64 ETag is a valid header"));
+    headers.insert(
+        header::LAST_MODIFIED,
+        HeaderValue::from_str(&last_modified).expect("an HTTP date is a valid header"),
+    );
+
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+    (StatusCode::OK, headers, body).into_response()
+}
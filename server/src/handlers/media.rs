@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::body::Bytes;
+use axum::extract::{Multipart, Path, State};
+use axum::response::IntoResponse;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::dtos::media::{MediaDto, MediaListDto};
+use crate::error::AppError;
+use crate::media::{self, invalid_file, ProcessedImage, Variant};
+use crate::middlewares::auth::AuthUserClaims;
+use crate::models::media::Media;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+/// Takes an image as the `file` field of a multipart form, and stores it resized into every
+/// [`Variant`] once it has been cleaned up by [`media::process`].
+pub async fn upload_media(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| invalid_file("could not be uploaded"))?
+    {
+        if field.name() == Some("file") {
+            let original_filename = field.file_name().map(str::to_string);
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|_| invalid_file("could not be uploaded"))?;
+            upload = Some((original_filename, bytes));
+        }
+    }
+    let (original_filename, bytes) = upload.ok_or_else(|| invalid_file("can't be blank"))?;
+
+    if bytes.len() > data.env.upload_max_size {
+        return Err(invalid_file(&format!("must be at most {} MB", data.env.upload_max_size / BYTES_PER_MB)));
+    }
+
+    let processed = tokio::task::spawn_blocking(move || media::process(&bytes))
+        .await
+        .map_err(|err| AppError::Internal(format!("Image processing panicked: {err}")))??;
+
+    // Uploads by the same user wait on their row, so that ones made at the same time can't each
+    // see the quota before the others took up space in it.
+    let mut tx = data.db.begin().await?;
+    sqlx::query!("SELECT id FROM users WHERE id = $1 FOR UPDATE", user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    let media = sqlx::query_as!(
+        Media,
+        r#"
+            INSERT INTO media (user_id, content_type, width, height, size, original_filename)
+            SELECT $1::UUID, $2::TEXT, $3::INTEGER, $4::INTEGER, $5::BIGINT, $6::TEXT
+            WHERE (SELECT COALESCE(SUM(size), 0) FROM media WHERE user_id = $1) + $5 <= $7::BIGINT
+            RETURNING id, user_id, content_type, width, height, size, original_filename, created_at
+        "#,
+        user_id,
+        processed.content_type,
+        processed.width as i32,
+        processed.height as i32,
+        processed.size() as i64,
+        original_filename,
+        data.env.upload_quota,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            invalid_file(&format!(
+                "would take you over your {} MB upload quota",
+                data.env.upload_quota as usize / BYTES_PER_MB,
+            ))
+        })?;
+    tx.commit().await?;
+
+    if let Err(err) = store(&data, &media, processed).await {
+        sqlx::query!("DELETE FROM media WHERE id = $1", media.id)
+            .execute(&data.db)
+            .await?;
+        return Err(err);
+    }
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Media uploaded",
+        "data": MediaDto::new(media, data.storage.as_ref()),
+    })))
+}
+
+pub async fn get_media(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let media = sqlx::query_as!(
+        Media,
+        r#"
+            SELECT id, user_id, content_type, width, height, size, original_filename, created_at
+            FROM media
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+        "#,
+        user_id,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Media fetched",
+        "data": MediaListDto {
+            used_bytes: media.iter().map(|media| media.size).sum(),
+            quota_bytes: data.env.upload_quota,
+            media: media
+                .into_iter()
+                .map(|media| MediaDto::new(media, data.storage.as_ref()))
+                .collect(),
+        },
+    })))
+}
+
+/// Deletes an upload and frees up its space. Posts or profiles still showing it are left with a
+/// broken image.
+pub async fn delete_media(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(media_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let content_type = sqlx::query_scalar!(
+        "DELETE FROM media WHERE id = $1 AND user_id = $2 RETURNING content_type",
+        media_id,
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Media not found".to_string()))?;
+
+    for variant in Variant::ALL {
+        let key = media::key(media_id, variant, &content_type);
+        if let Err(err) = data.storage.delete(&key).await {
+            log::warn!("Failed to delete {key}: {err:?}");
+        }
+    }
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Media deleted",
+        "data": (),
+    })))
+}
+
+/// Puts every variant in storage, removing any already put when one fails.
+async fn store(data: &AppState, media: &Media, processed: ProcessedImage) -> Result<(), AppError> {
+    let mut stored: Vec<String> = Vec::new();
+    for (variant, bytes) in processed.variants {
+        let key = media::key(media.id, variant, &media.content_type);
+        if let Err(err) = data.storage.put(&key, Bytes::from(bytes)).await {
+            for key in &stored {
+                if let Err(err) = data.storage.delete(key).await {
+                    log::warn!("Failed to clean up {key}: {err:?}");
+                }
+            }
+            return Err(err);
+        }
+        stored.push(key);
+    }
+
+    Ok(())
+}
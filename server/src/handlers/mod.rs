@@ -2,11 +2,23 @@ use axum::Json;
 use axum::response::IntoResponse;
 use serde_json::json;
 
+pub mod account;
 pub mod comment;
+pub mod event;
+pub mod feed;
+pub mod media;
+pub mod moderation;
+pub mod notification;
+pub mod oauth;
 pub mod user;
 pub mod post;
+pub mod post_revision;
+pub mod report;
 pub mod profile;
+pub mod search;
+pub mod seo;
 pub mod tag;
+pub mod two_factor;
 
 pub async fn health_check() -> impl IntoResponse {
     const MESSAGE: &str = "negatiview server is working!";
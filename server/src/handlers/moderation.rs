@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::config::AppState;
+use crate::dtos::moderation::*;
+use crate::dtos::Wrapper;
+use crate::error::AppError;
+use crate::handlers::comment::remove_comment;
+use crate::middlewares::role::{Admin, Moderator};
+use crate::models::user::UserRole;
+use crate::pagination::PageRequest;
+use crate::validation::ValidatedJson;
+
+#[derive(Deserialize, Default)]
+pub struct AuditLogQuery {
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+/// Hides a post from everyone but its author.
+pub async fn hide_post(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path(post_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ModerationDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+    set_post_hidden(&mut tx, Some(moderator.id), post_id, true, &body.data.reason).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Post hidden",
+        "data": post_id,
+    }))))
+}
+
+pub async fn unhide_post(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path(post_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ModerationDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+    set_post_hidden(&mut tx, Some(moderator.id), post_id, false, &body.data.reason).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Post restored",
+        "data": post_id,
+    }))))
+}
+
+pub async fn delete_post(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path(post_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ModerationDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+
+    let post = sqlx::query!(
+        "DELETE FROM posts WHERE id = $1 RETURNING user_id, title",
+        post_id,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    audit::record(
+        &mut *tx,
+        Some(moderator.id),
+        AuditAction::DeletePost,
+        post_id,
+        &body.data.reason,
+        json!({ "author_id": post.user_id, "title": post.title }),
+    )
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Post deleted",
+        "data": post_id,
+    }))))
+}
+
+/// Hides a comment; it stays in its thread as a placeholder like a deleted comment.
+pub async fn hide_comment(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path(comment_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ModerationDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+    set_comment_hidden(&mut tx, Some(moderator.id), comment_id, true, &body.data.reason).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Comment hidden",
+        "data": comment_id,
+    }))))
+}
+
+pub async fn unhide_comment(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path(comment_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ModerationDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+    set_comment_hidden(&mut tx, Some(moderator.id), comment_id, false, &body.data.reason).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Comment restored",
+        "data": comment_id,
+    }))))
+}
+
+pub async fn delete_comment(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path(comment_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ModerationDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+
+    let comment = sqlx::query!(
+        r#"
+            SELECT
+                user_id,
+                post_id,
+                parent_id,
+                body,
+                EXISTS (SELECT 1 FROM comments AS replies WHERE replies.parent_id = comments.id) "has_replies!"
+            FROM comments
+            WHERE id = $1 AND deleted_at IS NULL
+            FOR UPDATE
+        "#,
+        comment_id,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    remove_comment(&mut tx, comment_id, comment.parent_id, comment.has_replies).await?;
+
+    audit::record(
+        &mut *tx,
+        Some(moderator.id),
+        AuditAction::DeleteComment,
+        comment_id,
+        &body.data.reason,
+        json!({ "author_id": comment.user_id, "post_id": comment.post_id, "body": comment.body }),
+    )
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Comment deleted",
+        "data": comment_id,
+    }))))
+}
+
+/// Changes another user's role. Admins can't change their own, so there is always at least one.
+pub async fn change_role(
+    Admin(admin): Admin,
+    State(data): State<Arc<AppState>>,
+    Path(display_name): Path<String>,
+    ValidatedJson(body): ValidatedJson<Wrapper<RoleUpdateDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+
+    let user = sqlx::query!(
+        r#"SELECT id, role AS "role: UserRole" FROM users WHERE display_name = $1 FOR UPDATE"#,
+        display_name,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if user.id == admin.id {
+        return Err(AppError::Forbidden("You cannot change your own role".to_string()));
+    }
+
+    let role = body.data.role;
+    if user.role != role {
+        sqlx::query!(
+            "UPDATE users SET role = $1, updated_at = NOW() WHERE id = $2",
+            role as UserRole,
+            user.id,
+        )
+            .execute(&mut *tx)
+            .await?;
+
+        audit::record(
+            &mut *tx,
+            Some(admin.id),
+            AuditAction::ChangeRole,
+            user.id,
+            &body.data.reason,
+            json!({ "from": user.role, "to": role }),
+        )
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Role updated",
+        "data": UserRoleDto { display_name, role },
+    }))))
+}
+
+/// Newest entries first.
+pub async fn get_audit_log(
+    Admin(_): Admin,
+    State(data): State<Arc<AppState>>,
+    query: Query<AuditLogQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let page_request = PageRequest::new(query.cursor.as_deref(), query.limit)?;
+    let rows = sqlx::query_as!(
+        AuditLogEntryDto,
+        r#"
+            SELECT
+                audit_log.id,
+                actor.display_name "actor?",
+                action,
+                target_type,
+                target_id,
+                reason,
+                details,
+                audit_log.created_at
+            FROM audit_log
+            LEFT JOIN users AS actor ON actor.id = audit_log.actor_id
+            WHERE (
+                $1::TIMESTAMPTZ IS NULL
+                OR ( $3 AND (audit_log.created_at, audit_log.id) > ($1, $2::UUID) )
+                OR ( NOT $3 AND (audit_log.created_at, audit_log.id) < ($1, $2::UUID) )
+            )
+            ORDER BY
+                CASE WHEN $3 THEN audit_log.created_at END ASC,
+                CASE WHEN $3 THEN audit_log.id END ASC,
+                audit_log.created_at DESC,
+                audit_log.id DESC
+            LIMIT $4
+        "#,
+        page_request.timestamp(),
+        page_request.id(),
+        page_request.is_backward(),
+        page_request.fetch_limit(),
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let page = page_request.finish(rows, |entry| (entry.created_at, entry.id));
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Audit log fetched",
+        "data": AuditLogDto {
+            count: page.rows.len(),
+            entries: page.rows,
+            next_cursor: page.next_cursor,
+            prev_cursor: page.prev_cursor,
+        },
+    }))))
+}
+
+/// Hides or restores a post. Without an actor the hide is recorded as automatic.
+pub(crate) async fn set_post_hidden(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    actor_id: Option<Uuid>,
+    post_id: Uuid,
+    hidden: bool,
+    reason: &str,
+) -> Result<(), AppError> {
+    let author_id = sqlx::query_scalar!(
+        r#"
+            UPDATE posts
+            SET hidden_at = CASE WHEN $2 THEN COALESCE(hidden_at, NOW()) END
+            WHERE id = $1
+            RETURNING user_id
+        "#,
+        post_id,
+        hidden,
+    )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    let action = match (hidden, actor_id) {
+        (true, None) => AuditAction::AutoHidePost,
+        (true, Some(_)) => AuditAction::HidePost,
+        (false, _) => AuditAction::UnhidePost,
+    };
+    audit::record(&mut **tx, actor_id, action, post_id, reason, json!({ "author_id": author_id })).await
+}
+
+/// Hides or restores a comment; see [`set_post_hidden`].
+pub(crate) async fn set_comment_hidden(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    actor_id: Option<Uuid>,
+    comment_id: Uuid,
+    hidden: bool,
+    reason: &str,
+) -> Result<(), AppError> {
+    let comment = sqlx::query!(
+        r#"
+            UPDATE comments
+            SET hidden_at = CASE WHEN $2 THEN COALESCE(hidden_at, NOW()) END
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING user_id, post_id
+        "#,
+        comment_id,
+        hidden,
+    )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    let action = match (hidden, actor_id) {
+        (true, None) => AuditAction::AutoHideComment,
+        (true, Some(_)) => AuditAction::HideComment,
+        (false, _) => AuditAction::UnhideComment,
+    };
+    audit::record(
+        &mut **tx,
+        actor_id,
+        action,
+        comment_id,
+        reason,
+        json!({ "author_id": comment.user_id, "post_id": comment.post_id }),
+    )
+        .await
+}
@@ -0,0 +1,221 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::dtos::notification::*;
+use crate::dtos::Wrapper;
+use crate::error::AppError;
+use crate::middlewares::auth::AuthUserClaims;
+use crate::models::notification::NotificationKind;
+use crate::events::{publish, user_channel, LiveEvent};
+use crate::notifications::unread_count;
+use crate::pagination::PageRequest;
+
+#[derive(Deserialize, Default)]
+pub struct NotificationQuery {
+    limit: Option<i64>,
+    cursor: Option<String>,
+    /// Only unread notifications.
+    unread: Option<bool>,
+}
+
+/// The current user's notifications, newest first.
+pub async fn get_notifications(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    query: Query<NotificationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user_id(&auth_user_claims)?;
+    let page_request = PageRequest::new(query.cursor.as_deref(), query.limit)?;
+    let rows = sqlx::query_as!(
+        NotificationDto,
+        r#"
+            SELECT
+                notifications.id,
+                kind AS "kind: NotificationKind",
+                actor.display_name AS actor_display_name,
+                actor.profile_image_url AS actor_profile_image_url,
+                posts.slug "post_slug?",
+                posts.title "post_title?",
+                comment_id,
+                notifications.created_at,
+                read_at IS NOT NULL "read!"
+            FROM notifications
+            INNER JOIN users AS actor ON actor.id = notifications.actor_id
+            LEFT JOIN posts ON posts.id = notifications.post_id
+            WHERE recipient_id = $1
+                AND ( NOT $2 OR read_at IS NULL )
+                AND (
+                    $3::TIMESTAMPTZ IS NULL
+                    OR ( $5 AND (notifications.created_at, notifications.id) > ($3, $4::UUID) )
+                    OR ( NOT $5 AND (notifications.created_at, notifications.id) < ($3, $4::UUID) )
+                )
+            ORDER BY
+                CASE WHEN $5 THEN notifications.created_at END ASC,
+                CASE WHEN $5 THEN notifications.id END ASC,
+                notifications.created_at DESC,
+                notifications.id DESC
+            LIMIT $6
+        "#,
+        user_id,
+        query.unread.unwrap_or(false),
+        page_request.timestamp(),
+        page_request.id(),
+        page_request.is_backward(),
+        page_request.fetch_limit(),
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let page = page_request.finish(rows, |notification| (notification.created_at, notification.id));
+    let unread_count = unread_count(&data.db, user_id).await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Notifications fetched",
+        "data": NotificationsDto {
+            count: page.rows.len(),
+            notifications: page.rows,
+            unread_count,
+            next_cursor: page.next_cursor,
+            prev_cursor: page.prev_cursor,
+        },
+    }))))
+}
+
+pub async fn get_unread_count(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user_id(&auth_user_claims)?;
+    let unread_count = unread_count(&data.db, user_id).await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Unread notifications counted",
+        "data": UnreadCountDto { unread_count },
+    }))))
+}
+
+pub async fn mark_read(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(notification_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user_id(&auth_user_claims)?;
+
+    sqlx::query_scalar!(
+        r#"
+            UPDATE notifications
+            SET read_at = COALESCE(read_at, NOW())
+            WHERE id = $1 AND recipient_id = $2
+            RETURNING id
+        "#,
+        notification_id,
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+
+    let unread_count = unread_count(&data.db, user_id).await?;
+    publish(&data, &user_channel(user_id), &LiveEvent::Notifications { unread_count }).await;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Notification marked as read",
+        "data": UnreadCountDto { unread_count },
+    }))))
+}
+
+pub async fn mark_all_read(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user_id(&auth_user_claims)?;
+
+    sqlx::query!(
+        "UPDATE notifications SET read_at = NOW() WHERE recipient_id = $1 AND read_at IS NULL",
+        user_id,
+    )
+        .execute(&data.db)
+        .await?;
+    publish(&data, &user_channel(user_id), &LiveEvent::Notifications { unread_count: 0 }).await;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Notifications marked as read",
+        "data": UnreadCountDto { unread_count: 0 },
+    }))))
+}
+
+pub async fn get_preferences(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user_id(&auth_user_claims)?;
+    let preferences = sqlx::query_as!(
+        NotificationPreferencesDto,
+        "SELECT follow, favorite, comment FROM notification_preferences WHERE user_id = $1",
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .unwrap_or(NotificationPreferencesDto {
+            follow: true,
+            favorite: true,
+            comment: true,
+        });
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Notification preferences fetched",
+        "data": preferences,
+    }))))
+}
+
+pub async fn update_preferences(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Json(body): Json<Wrapper<NotificationPreferencesDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user_id(&auth_user_claims)?;
+    let preferences = sqlx::query_as!(
+        NotificationPreferencesDto,
+        r#"
+            INSERT INTO notification_preferences (user_id, follow, favorite, comment)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE
+            SET follow = EXCLUDED.follow,
+                favorite = EXCLUDED.favorite,
+                comment = EXCLUDED.comment,
+                updated_at = NOW()
+            RETURNING follow, favorite, comment
+        "#,
+        user_id,
+        body.data.follow,
+        body.data.favorite,
+        body.data.comment,
+    )
+        .fetch_one(&data.db)
+        .await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Notification preferences updated",
+        "data": preferences,
+    }))))
+}
+
+fn current_user_id(auth_user_claims: &AuthUserClaims) -> Result<Uuid, AppError> {
+    auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))
+}
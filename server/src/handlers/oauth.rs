@@ -0,0 +1,421 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use axum::http::{header, Response, StatusCode};
+use axum::response::IntoResponse;
+use axum_extra::extract::cookie::CookieJar;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::{AppState, OAuthProviderConfig};
+use crate::dtos::oauth::*;
+use crate::dtos::user::UserDto;
+use crate::dtos::Wrapper;
+use crate::error::AppError;
+use crate::handlers::two_factor::challenge as two_factor_challenge;
+use crate::handlers::user::{find_access_token_in_redis, sign_in, start_session};
+use crate::middlewares::auth::AuthUserClaims;
+use crate::session::ClientInfo;
+use crate::models::user::{User, UserRole};
+use crate::oauth::{self, PendingAuthorization, UserInfo};
+use crate::two_factor;
+use crate::validation::{ValidatedJson, DISPLAY_NAME_MAX_LENGTH, DISPLAY_NAME_MIN_LENGTH};
+
+pub async fn get_providers(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let providers: Vec<OAuthProviderDto> = data
+        .env
+        .oauth_providers
+        .iter()
+        .map(|provider| OAuthProviderDto {
+            name: provider.name.clone(),
+            display_name: provider.display_name.clone(),
+        })
+        .collect();
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Providers fetched",
+        "data": providers,
+    })))
+}
+
+/// Starts signing in with, or linking, a provider. The client sends the user to the returned URL.
+pub async fn authorize(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+    body: Option<Json<Wrapper<AuthorizeDto>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let provider = oauth::find_provider(&data.env, &provider)?;
+    let link = body.map(|Json(body)| body.data.link).unwrap_or_default();
+
+    let link_user_id = if link {
+        Some(auth_user_claims
+            .user_id()
+            .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?)
+    } else {
+        None
+    };
+
+    let (authorization_url, nonce_cookie) = oauth::start_authorization(&data, provider, link_user_id).await?;
+
+    Ok((
+        CookieJar::new().add(nonce_cookie),
+        Json(json!({
+            "status": "success",
+            "message": "Authorization started",
+            "data": AuthorizationUrlDto { authorization_url },
+        })),
+    ))
+}
+
+/// Finishes what [`authorize`] started. Signing in with an identity nobody has used before creates
+/// an account, unless its email address already belongs to one: taking that account over would
+/// only take the provider's word for it, so its owner has to log in and link the provider instead.
+/// Users with two-factor authentication on get the same challenge as after entering a password.
+pub async fn callback(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+    cookie_jar: CookieJar,
+    client_info: ClientInfo,
+    ValidatedJson(body): ValidatedJson<Wrapper<OAuthCallbackDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let provider = oauth::find_provider(&data.env, &provider)?;
+    let nonce = cookie_jar.get(oauth::NONCE_COOKIE).map(|cookie| cookie.value());
+    let (pending, user_info) =
+        oauth::finish_authorization(&data, provider, body.data.code, &body.data.state, nonce).await?;
+
+    let mut response = finish_callback(&data, provider, auth_user_claims, &client_info, pending, user_info).await?;
+    response.headers_mut().append(
+        header::SET_COOKIE,
+        oauth::clear_nonce_cookie().to_string().parse().unwrap(),
+    );
+
+    Ok(response)
+}
+
+async fn finish_callback(
+    data: &Arc<AppState>,
+    provider: &OAuthProviderConfig,
+    auth_user_claims: AuthUserClaims,
+    client_info: &ClientInfo,
+    pending: PendingAuthorization,
+    user_info: UserInfo,
+) -> Result<Response<String>, AppError> {
+    if let Some(link_user_id) = pending.link_user_id {
+        let user = auth_user_claims
+            .user
+            .filter(|user| user.id == link_user_id)
+            .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+        link_identity(data, user.id, &provider.name, &provider.display_name, &user_info).await?;
+
+        let access_token_uuid = auth_user_claims.access_token_uuid.unwrap_or_default();
+        let access_token = find_access_token_in_redis(data, access_token_uuid).await?;
+        let json_response = json!({
+            "status": "success",
+            "message": format!("{} linked", provider.display_name),
+            "data": OAuthCallbackResultDto {
+                linked: true,
+                user: UserDto::new(user, access_token),
+            }
+        });
+
+        return Ok(Response::builder()
+            .status(StatusCode::OK)
+            .body(json_response.to_string())
+            .unwrap());
+    }
+
+    let user = match find_identity_user(data, &provider.name, &user_info.sub).await? {
+        Some(user) => user,
+        None => create_user(data, &provider.name, &provider.display_name, &user_info).await?,
+    };
+
+    if two_factor::is_enabled(&data.db, user.id).await? {
+        return two_factor_challenge(data, user.id).await;
+    }
+
+    let session_id = start_session(data, user.id, client_info).await?;
+    let (user, headers) = sign_in(data, user, session_id).await?;
+    let json_response = json!({
+        "status": "success",
+        "message": "Login successful",
+        "data": OAuthCallbackResultDto {
+            linked: false,
+            user,
+        }
+    });
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .body(json_response.to_string())
+        .unwrap();
+
+    response.headers_mut().extend(headers);
+
+    Ok(response)
+}
+
+pub async fn get_identities(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Linked accounts fetched",
+        "data": identities(&data, user_id).await?,
+    })))
+}
+
+/// Unlinks a provider, unless the user would be left without any way to log in.
+pub async fn unlink_identity(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let current = identities(&data, user_id).await?;
+    if !current.identities.iter().any(|identity| identity.provider == provider) {
+        return Err(AppError::NotFound("Linked account not found".to_string()));
+    }
+    if !current.has_password && current.identities.len() == 1 {
+        return Err(AppError::Conflict(
+            "Set a password before unlinking your only sign-in provider".to_string(),
+        ));
+    }
+
+    sqlx::query!(
+        "DELETE FROM user_identities WHERE user_id = $1 AND provider = $2",
+        user_id,
+        provider,
+    )
+        .execute(&data.db)
+        .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Account unlinked",
+        "data": identities(&data, user_id).await?,
+    })))
+}
+
+async fn identities(data: &AppState, user_id: Uuid) -> Result<IdentitiesDto, AppError> {
+    let identities = sqlx::query_as!(
+        IdentityDto,
+        r#"
+            SELECT provider, email, created_at
+            FROM user_identities
+            WHERE user_id = $1
+            ORDER BY created_at
+        "#,
+        user_id,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let has_password = sqlx::query_scalar!(
+        r#"SELECT password <> '' AS "has_password!" FROM users WHERE id = $1"#,
+        user_id,
+    )
+        .fetch_one(&data.db)
+        .await?;
+
+    Ok(IdentitiesDto { identities, has_password })
+}
+
+async fn link_identity(
+    data: &AppState,
+    user_id: Uuid,
+    provider: &str,
+    provider_display_name: &str,
+    user_info: &UserInfo,
+) -> Result<(), AppError> {
+    let owner_id = sqlx::query_scalar!(
+        "SELECT user_id FROM user_identities WHERE provider = $1 AND subject = $2",
+        provider,
+        user_info.sub,
+    )
+        .fetch_optional(&data.db)
+        .await?;
+
+    match owner_id {
+        Some(owner_id) if owner_id == user_id => {
+            return Err(AppError::Conflict(format!("This {provider_display_name} account is already linked")));
+        }
+        Some(_) => {
+            return Err(AppError::Conflict(format!(
+                "This {provider_display_name} account is linked to another user"
+            )));
+        }
+        None => {}
+    }
+
+    sqlx::query!(
+        r#"
+            INSERT INTO user_identities (user_id, provider, subject, email)
+            VALUES ($1, $2, $3, $4)
+        "#,
+        user_id,
+        provider,
+        user_info.sub,
+        user_info.email,
+    )
+        .execute(&data.db)
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => AppError::Conflict(format!(
+                "A {provider_display_name} account is already linked; unlink it first"
+            )),
+            err => err.into(),
+        })?;
+
+    Ok(())
+}
+
+async fn find_identity_user(data: &AppState, provider: &str, subject: &str) -> Result<Option<User>, AppError> {
+    let user = sqlx::query_as!(
+        User,
+        r#"
+            SELECT u.id, u.email, u.display_name, u.password, u.biography, u.profile_image_url, u.role AS "role: UserRole", u.email_verified_at, u.created_at, u.updated_at
+            FROM users u
+            JOIN user_identities i ON i.user_id = u.id
+            WHERE i.provider = $1 AND i.subject = $2
+        "#,
+        provider,
+        subject,
+    )
+        .fetch_optional(&data.db)
+        .await?;
+
+    Ok(user)
+}
+
+/// Creates an account for someone signing in with a provider for the first time. It has no
+/// password until the user sets one, and its email address counts as verified if the provider
+/// says so.
+async fn create_user(
+    data: &AppState,
+    provider: &str,
+    provider_display_name: &str,
+    user_info: &UserInfo,
+) -> Result<User, AppError> {
+    let email = user_info
+        .email
+        .as_deref()
+        .filter(|email| !email.is_empty())
+        .ok_or_else(|| AppError::Forbidden(format!("{provider_display_name} didn't share your email address")))?;
+
+    let email_taken = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", email)
+        .fetch_optional(&data.db)
+        .await?
+        .is_some();
+    if email_taken {
+        return Err(AppError::Conflict(format!(
+            "An account already uses {email}. Log in with your password and link {provider_display_name} from your settings."
+        )));
+    }
+
+    let base_display_name = base_display_name(user_info, email);
+    let profile_image_url = user_info
+        .picture
+        .as_deref()
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"));
+
+    let mut tx = data.db.begin().await?;
+    let display_name = unique_display_name(&mut *tx, &base_display_name).await?;
+    let user = sqlx::query_as!(
+        User,
+        r#"
+            INSERT INTO users (email, password, display_name, profile_image_url, email_verified_at)
+            VALUES ($1, '', $2, $3, CASE WHEN $4 THEN NOW() END)
+            RETURNING id, email, display_name, password, biography, profile_image_url, role AS "role: UserRole", email_verified_at, created_at, updated_at
+        "#,
+        email,
+        display_name,
+        profile_image_url,
+        user_info.email_verified,
+    )
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query!(
+        r#"
+            INSERT INTO user_identities (user_id, provider, subject, email)
+            VALUES ($1, $2, $3, $4)
+        "#,
+        user.id,
+        provider,
+        user_info.sub,
+        email,
+    )
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(user)
+}
+
+/// A valid display name derived from the provider's username, real name or email address.
+fn base_display_name(user_info: &UserInfo, email: &str) -> String {
+    let candidates = [
+        user_info.preferred_username.as_deref(),
+        user_info.name.as_deref(),
+        email.split('@').next(),
+    ];
+
+    candidates
+        .into_iter()
+        .flatten()
+        .map(|candidate| {
+            candidate
+                .chars()
+                .map(|c| if c.is_whitespace() { '_' } else { c })
+                .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+                // Leaves room for the suffix `unique_display_name` may add.
+                .take(DISPLAY_NAME_MAX_LENGTH - 4)
+                .collect::<String>()
+        })
+        .find(|candidate| candidate.len() >= DISPLAY_NAME_MIN_LENGTH)
+        .unwrap_or_else(|| "user".to_string())
+}
+
+/// Returns `base`, or `base` followed by the smallest free number.
+async fn unique_display_name(
+    executor: impl sqlx::PgExecutor<'_>,
+    base: &str,
+) -> Result<String, AppError> {
+    let taken: HashSet<String> = sqlx::query_scalar!(
+        "SELECT display_name FROM users WHERE display_name LIKE $1 || '%'",
+        base,
+    )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .collect();
+
+    if !taken.contains(base) {
+        return Ok(base.to_string());
+    }
+
+    let mut suffix = 2;
+    loop {
+        let display_name = format!("{base}{suffix}");
+        if !taken.contains(&display_name) {
+            return Ok(display_name);
+        }
+        suffix += 1;
+    }
+}
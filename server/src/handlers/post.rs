@@ -1,33 +1,49 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{Extension, Json};
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use futures::{TryFutureExt, TryStreamExt};
+use chrono::Utc;
 use serde::Deserialize;
-use serde_json::{json, Value};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
 
 use crate::config::AppState;
+use crate::error::AppError;
 use crate::dtos::post::*;
 use crate::dtos::Wrapper;
+use crate::events::{post_channel, publish, LiveEvent};
 use crate::middlewares::auth::AuthUserClaims;
-use crate::models::post::PostFromQuery;
+use crate::models::notification::NotificationKind;
+use crate::models::post::{PostFromQuery, PostStatus};
+use crate::notifications::{announce, notify_post_author};
+use crate::pagination::PageRequest;
+use crate::validation::ValidatedJson;
+
+/// How many times `new_post` picks a fresh slug when a concurrent insert takes the one it chose.
+const SLUG_ATTEMPTS: usize = 5;
 
 #[derive(Deserialize, Default)]
 pub struct PostQuery {
-    tag: Option<String>,
-    author: Option<String>,
-    favorited: Option<String>,
-    limit: Option<i64>,
-    offset: Option<i64>,
+    pub(crate) tag: Option<String>,
+    pub(crate) author: Option<String>,
+    pub(crate) favorited: Option<String>,
+    pub(crate) limit: Option<i64>,
+    pub(crate) cursor: Option<String>,
+    pub(crate) total: Option<bool>,
+    /// Lists posts in this status instead of published ones; only the author's own posts have
+    /// any other status visible.
+    pub(crate) status: Option<PostStatus>,
 }
 
 pub async fn get_post(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(post_id): Path<uuid::Uuid>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
     let post = sqlx::query_as!(
         PostFromQuery,
@@ -41,6 +57,9 @@ pub async fn get_post(
                 tags,
                 posts.created_at,
                 posts.updated_at,
+                posts.status "status: PostStatus",
+                posts.publish_at,
+                posts.published_at,
                 EXISTS (SELECT 1 FROM post_favorites WHERE user_id = $1) "favorited!",
                 COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = posts.id), 0) "favorites_count!",
                 author.display_name AS author_display_name,
@@ -50,26 +69,71 @@ pub async fn get_post(
             FROM posts
             INNER JOIN users AS author ON author.id = posts.user_id
             WHERE posts.id = $2
+                AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $1)
         "#,
         user_id,
         post_id,
     )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to get post: {err}"),
-                }))
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Post fetched",
+        "data": post.into_post_dto(&data.markdown)
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// Looks a post up by its current slug, falling back to slugs it had before being retitled.
+pub async fn get_post_by_slug(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = &auth_user_claims.user_id().unwrap_or_default();
+    let post = sqlx::query_as!(
+        PostFromQuery,
+        r#"
+            SELECT
+                posts.id,
+                slug,
+                title,
+                description,
+                body,
+                tags,
+                posts.created_at,
+                posts.updated_at,
+                posts.status "status: PostStatus",
+                posts.publish_at,
+                posts.published_at,
+                EXISTS (SELECT 1 FROM post_favorites WHERE post_id = posts.id AND user_id = $1) "favorited!",
+                COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = posts.id), 0) "favorites_count!",
+                author.display_name AS author_display_name,
+                author.biography AS author_biography,
+                author.profile_image_url AS author_profile_image_url,
+                EXISTS (SELECT 1 FROM user_follows WHERE followee_user_id = author.id AND follower_user_id = $1) "following_author!"
+            FROM posts
+            INNER JOIN users AS author ON author.id = posts.user_id
+            WHERE (
+                posts.slug = $2
+                OR posts.id = (SELECT post_id FROM post_slug_redirects WHERE slug = $2)
             )
-        })?;
+                AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $1)
+        "#,
+        user_id,
+        slug,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
     let json_response = json!({
         "status": "success",
         "message": "Post fetched",
-        "data": post.into_post_dto()
+        "data": post.into_post_dto(&data.markdown)
     });
 
     Ok((StatusCode::OK, Json(json_response)))
@@ -79,9 +143,61 @@ pub async fn post_list(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     query: Query<PostQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
-    let posts: Vec<PostDto> = sqlx::query_as!(
+    let page_request = PageRequest::new(query.cursor.as_deref(), query.limit)?;
+    let rows = query_posts(&data.db, user_id, &query, &page_request).await?;
+
+    let total = if query.total.unwrap_or(false) {
+        let total = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) "count!"
+                FROM posts
+                INNER JOIN users AS author ON author.id = posts.user_id
+                WHERE ( $1::TEXT IS NULL OR tags @> array[$1] )
+                    AND ( $2::TEXT IS NULL OR author.display_name = $2 )
+                    AND (
+                        $3::TEXT IS NULL OR EXISTS (
+                            SELECT 1 FROM users
+                            INNER JOIN post_favorites ON users.id = post_favorites.user_id
+                            WHERE display_name = $3 AND posts.id = post_favorites.post_id
+                        )
+                    )
+                    AND posts.status = COALESCE($4::post_status, 'published')
+                    AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $5)
+                    AND ( $2::TEXT IS NOT NULL OR NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $5 AND muted_user_id = posts.user_id) )
+            "#,
+            query.tag,
+            query.author,
+            query.favorited,
+            query.status as Option<PostStatus>,
+            user_id,
+        )
+            .fetch_one(&data.db)
+            .await?;
+        Some(total)
+    } else {
+        None
+    };
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Posts fetched",
+        "data": into_posts_dto(page_request, rows, total)
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// One page of the posts matching `query`, as `user_id` is allowed to see them; what `post_list`
+/// serves and the feeds are built from.
+pub(crate) async fn query_posts(
+    db: &PgPool,
+    user_id: &Uuid,
+    query: &PostQuery,
+    page_request: &PageRequest,
+) -> Result<Vec<PostFromQuery>, AppError> {
+    let rows = sqlx::query_as!(
         PostFromQuery,
         r#"
             SELECT
@@ -93,6 +209,9 @@ pub async fn post_list(
                 tags,
                 posts.created_at,
                 posts.updated_at,
+                posts.status "status: PostStatus",
+                posts.publish_at,
+                posts.published_at,
                 EXISTS (SELECT 1 FROM post_favorites WHERE post_id = posts.id AND user_id = $1) "favorited!",
                 COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = posts.id), 0) "favorites_count!",
                 author.display_name AS author_display_name,
@@ -110,50 +229,46 @@ pub async fn post_list(
                         WHERE display_name = $4 AND posts.id = post_favorites.post_id
                     )
                 )
-            ORDER BY posts.created_at DESC
-            LIMIT $5
-            OFFSET $6
+                AND posts.status = COALESCE($9::post_status, 'published')
+                AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $1)
+                -- Muted authors are still listed when their posts are asked for by name.
+                AND ( $3::TEXT IS NOT NULL OR NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = posts.user_id) )
+                AND (
+                    $5::TIMESTAMPTZ IS NULL
+                    OR ( $7 AND (COALESCE(posts.published_at, posts.created_at), posts.id) > ($5, $6::UUID) )
+                    OR ( NOT $7 AND (COALESCE(posts.published_at, posts.created_at), posts.id) < ($5, $6::UUID) )
+                )
+            ORDER BY
+                CASE WHEN $7 THEN COALESCE(posts.published_at, posts.created_at) END ASC,
+                CASE WHEN $7 THEN posts.id END ASC,
+                COALESCE(posts.published_at, posts.created_at) DESC,
+                posts.id DESC
+            LIMIT $8
         "#,
         user_id,
         query.tag,
         query.author,
         query.favorited,
-        query.limit.unwrap_or(10),
-        query.offset.unwrap_or(0),
+        page_request.timestamp(),
+        page_request.id(),
+        page_request.is_backward(),
+        page_request.fetch_limit(),
+        query.status as Option<PostStatus>,
     )
-        .fetch(&data.db)
-        .map_ok(|post| post.into_post_dto())
-        .try_collect()
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to get posts: {err}"),
-                }))
-            )
-        })?;
-
-    let json_response = json!({
-        "status": "success",
-        "message": "Posts fetched",
-        "data": PostsDto {
-            count: posts.len(),
-            posts,
-        }
-    });
+        .fetch_all(db)
+        .await?;
 
-    Ok((StatusCode::OK, Json(json_response)))
+    Ok(rows)
 }
 
 pub async fn feed_list(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     query: Query<PostQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
-    let posts: Vec<PostDto> = sqlx::query_as!(
+    let page_request = PageRequest::new(query.cursor.as_deref(), query.limit)?;
+    let rows = sqlx::query_as!(
         PostFromQuery,
         r#"
             SELECT
@@ -165,7 +280,10 @@ pub async fn feed_list(
                 tags,
                 posts.created_at,
                 posts.updated_at,
-                EXISTS (SELECT 1 FROM post_favorites WHERE user_id = $1) "favorited!",
+                posts.status "status: PostStatus",
+                posts.publish_at,
+                posts.published_at,
+                EXISTS (SELECT 1 FROM post_favorites WHERE post_id = posts.id AND user_id = $1) "favorited!",
                 COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = posts.id), 0) "favorites_count!",
                 author.display_name AS author_display_name,
                 author.biography AS author_biography,
@@ -173,36 +291,56 @@ pub async fn feed_list(
                 TRUE "following_author!"
             FROM user_follows
             INNER JOIN posts ON followee_user_id = posts.user_id
-            INNER JOIN users AS author ON author.id = user_id
+            INNER JOIN users AS author ON author.id = posts.user_id
             WHERE follower_user_id = $1
-            LIMIT $2
-            OFFSET $3
+                AND posts.status = 'published'
+                AND posts.hidden_at IS NULL
+                AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = posts.user_id)
+                AND (
+                    $2::TIMESTAMPTZ IS NULL
+                    OR ( $4 AND (COALESCE(posts.published_at, posts.created_at), posts.id) > ($2, $3::UUID) )
+                    OR ( NOT $4 AND (COALESCE(posts.published_at, posts.created_at), posts.id) < ($2, $3::UUID) )
+                )
+            ORDER BY
+                CASE WHEN $4 THEN COALESCE(posts.published_at, posts.created_at) END ASC,
+                CASE WHEN $4 THEN posts.id END ASC,
+                COALESCE(posts.published_at, posts.created_at) DESC,
+                posts.id DESC
+            LIMIT $5
         "#,
         user_id,
-        query.limit.unwrap_or(10),
-        query.offset.unwrap_or(0),
+        page_request.timestamp(),
+        page_request.id(),
+        page_request.is_backward(),
+        page_request.fetch_limit(),
     )
-        .fetch(&data.db)
-        .map_ok(|post| post.into_post_dto())
-        .try_collect()
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to get posts: {err}"),
-                }))
-            )
-        })
+        .fetch_all(&data.db)
         .await?;
 
+    let total = if query.total.unwrap_or(false) {
+        let total = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) "count!"
+                FROM user_follows
+                INNER JOIN posts ON followee_user_id = posts.user_id
+                WHERE follower_user_id = $1
+                    AND posts.status = 'published'
+                    AND posts.hidden_at IS NULL
+                    AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = posts.user_id)
+            "#,
+            user_id,
+        )
+            .fetch_one(&data.db)
+            .await?;
+        Some(total)
+    } else {
+        None
+    };
+
     let json_response = json!({
         "status": "success",
         "message": "Posts fetched",
-        "data": PostsDto {
-            count: posts.len(),
-            posts,
-        }
+        "data": into_posts_dto(page_request, rows, total)
     });
 
     Ok((StatusCode::OK, Json(json_response)))
@@ -211,103 +349,170 @@ pub async fn feed_list(
 pub async fn new_post(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
-    Json(body): Json<Wrapper<NewPostDto>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    ValidatedJson(body): ValidatedJson<Wrapper<NewPostDto>>,
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
     let mut dto = body.data;
-    let slug = slugify(dto.title.as_str());
+    let base_slug = slugify(dto.title.as_str());
     dto.tags.sort();
+    let status = dto.status.unwrap_or(PostStatus::Published);
+    let publish_at = match status {
+        PostStatus::Scheduled => dto.publish_at,
+        PostStatus::Published => Some(Utc::now()),
+        PostStatus::Draft | PostStatus::Archived => None,
+    };
+    let published_at = (status == PostStatus::Published).then(Utc::now);
+
+    for _ in 0..SLUG_ATTEMPTS {
+        let slug = unique_slug(&data.db, &base_slug, None).await?;
+        let result = sqlx::query_as!(
+            PostFromQuery,
+            r#"
+                WITH the_post AS (
+                    INSERT INTO posts (user_id, slug, title, description, body, tags, status, publish_at, published_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    RETURNING
+                        id,
+                        slug,
+                        title,
+                        description,
+                        body,
+                        tags,
+                        created_at,
+                        updated_at,
+                        status "status: PostStatus",
+                        publish_at,
+                        published_at
+                )
+                SELECT
+                    the_post.*,
+                    FALSE "favorited!",
+                    0::INT "favorites_count!",
+                    display_name AS author_display_name,
+                    biography AS author_biography,
+                    profile_image_url AS author_profile_image_url,
+                    FALSE "following_author!"
+                FROM the_post
+                INNER JOIN users ON users.id = $1
+            "#,
+            user_id,
+            slug,
+            dto.title,
+            dto.description,
+            dto.body,
+            &dto.tags[..],
+            status as PostStatus,
+            publish_at,
+            published_at,
+        )
+            .fetch_one(&data.db)
+            .await;
+
+        let post = match result {
+            Err(err) if is_slug_conflict(&err) => continue,
+            result => result?,
+        };
+
+        let json_response = json!({
+            "status": "success",
+            "message": "Post created",
+            "data": post.into_post_dto(&data.markdown)
+        });
 
-    let post = sqlx::query_as!(
-        PostFromQuery,
-        r#"
-            WITH the_post AS (
-                INSERT INTO posts (user_id, slug, title, description, body, tags)
-                VALUES ($1, $2, $3, $4, $5, $6)
-                RETURNING
-                    id,
-                    slug,
-                    title,
-                    description,
-                    body,
-                    tags,
-                    created_at,
-                    updated_at
-            )
-            SELECT
-                the_post.*,
-                FALSE "favorited!",
-                0::INT "favorites_count!",
-                display_name AS author_display_name,
-                biography AS author_biography,
-                profile_image_url AS author_profile_image_url,
-                FALSE "following_author!"
-            FROM the_post
-            INNER JOIN users ON users.id = $1
-        "#,
-        user_id,
-        slug,
-        dto.title,
-        dto.description,
-        dto.body,
-        &dto.tags[..]
-    )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to create post: {err}"),
-                }))
-            )
-        })?;
-
-    let json_response = json!({
-        "status": "success",
-        "message": "Post created",
-        "data": post.into_post_dto()
-    });
+        return Ok((StatusCode::CREATED, Json(json_response)));
+    }
 
-    Ok((StatusCode::CREATED, Json(json_response)))
+    Err(AppError::Conflict("Could not find a free slug for this title".to_string()))
 }
 
 pub async fn update_post(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(post_id): Path<uuid::Uuid>,
-    Json(body): Json<Wrapper<UpdatePostDto>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    ValidatedJson(body): ValidatedJson<Wrapper<UpdatePostDto>>,
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
 
+    let mut tx = data.db.begin().await?;
+    let post = apply_update(&mut tx, user_id, post_id, &body.data).await?;
+    tx.commit().await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Post updated",
+        "data": post.into_post_dto(&data.markdown)
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// Applies `update` to a post owned by `user_id`, first saving the current version to
+/// `post_revisions` when anything actually changes.
+pub(crate) async fn apply_update(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: &Uuid,
+    post_id: Uuid,
+    update: &UpdatePostDto,
+) -> Result<PostFromQuery, AppError> {
     let post = sqlx::query!(
-        "SELECT id, user_id FROM posts WHERE id = $1",
+        "SELECT id, user_id, slug FROM posts WHERE id = $1 FOR UPDATE",
         post_id
     )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to get post: {err}"),
-                }))
-            )
-        })?;
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
     if post.user_id != *user_id {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({
-                "status": "fail",
-                "message": "You are not allowed to edit this post",
-            }))
-        ))
+        return Err(AppError::Forbidden("You are not allowed to edit this post".to_string()))
     }
 
-    let slug = body.data.title.as_deref().map(slugify);
+    sqlx::query!(
+        r#"
+            INSERT INTO post_revisions (post_id, title, description, body, tags, written_at)
+            SELECT id, title, description, body, tags, updated_at
+            FROM posts
+            WHERE id = $1
+                AND (title, description, body, tags)
+                    IS DISTINCT FROM (COALESCE($2, title), COALESCE($3, description), COALESCE($4, body), COALESCE($5, tags))
+        "#,
+        post_id,
+        update.title,
+        update.description,
+        update.body,
+        update.tags.as_ref().map(|tags| &tags[..]),
+    )
+        .execute(&mut **tx)
+        .await?;
+
+    let slug = match update.title.as_deref().map(slugify) {
+        Some(base_slug) => {
+            let slug = unique_slug(&mut **tx, &base_slug, Some(post_id)).await?;
+            (slug != post.slug).then_some(slug)
+        }
+        None => None,
+    };
+
+    if let Some(slug) = &slug {
+        sqlx::query!(
+            r#"
+                INSERT INTO post_slug_redirects (slug, post_id)
+                VALUES ($1, $2)
+                ON CONFLICT (slug) DO UPDATE SET post_id = EXCLUDED.post_id, created_at = NOW()
+            "#,
+            post.slug,
+            post_id,
+        )
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query!(
+            "DELETE FROM post_slug_redirects WHERE slug = $1 AND post_id = $2",
+            slug,
+            post_id,
+        )
+            .execute(&mut **tx)
+            .await?;
+    }
 
     let post = sqlx::query_as!(
         PostFromQuery,
@@ -319,7 +524,19 @@ pub async fn update_post(
                     title = COALESCE($2, title),
                     description = COALESCE($3, description),
                     body = COALESCE($4, body),
-                    tags = COALESCE($5, tags)
+                    tags = COALESCE($5, tags),
+                    status = COALESCE($8, status),
+                    publish_at = CASE COALESCE($8, status)
+                        WHEN 'scheduled' THEN COALESCE($9, publish_at)
+                        WHEN 'published' THEN CASE WHEN status = 'published' THEN publish_at ELSE NOW() END
+                        ELSE publish_at
+                    END,
+                    -- Set the first time the post goes out, which is where lists place it.
+                    published_at = CASE COALESCE($8, status)
+                        WHEN 'published' THEN COALESCE(published_at, NOW())
+                        ELSE published_at
+                    END,
+                    updated_at = NOW()
                 WHERE id = $6
                 RETURNING
                     id,
@@ -329,11 +546,14 @@ pub async fn update_post(
                     body,
                     tags,
                     created_at,
-                    updated_at
+                    updated_at,
+                    status "status: PostStatus",
+                    publish_at,
+                    published_at
             )
             SELECT
                 the_post.*,
-                EXISTS (SELECT 1 FROM post_favorites WHERE user_id = $7) "favorited!",
+                EXISTS (SELECT 1 FROM post_favorites WHERE post_id = the_post.id AND user_id = $7) "favorited!",
                 COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = the_post.id), 0) "favorites_count!",
                 display_name AS author_display_name,
                 biography AS author_biography,
@@ -343,38 +563,25 @@ pub async fn update_post(
             INNER JOIN users ON users.id = $7
         "#,
         slug,
-        body.data.title,
-        body.data.description,
-        body.data.body,
-        body.data.tags.as_ref().map(|tags| &tags[..]),
+        update.title,
+        update.description,
+        update.body,
+        update.tags.as_ref().map(|tags| &tags[..]),
         post_id,
-        user_id)
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to update post: {err}"),
-                }))
-            )
-        })?;
-
-    let json_response = json!({
-        "status": "success",
-        "message": "Post updated",
-        "data": post.into_post_dto()
-    });
+        user_id,
+        update.status as Option<PostStatus>,
+        update.publish_at)
+        .fetch_one(&mut **tx)
+        .await?;
 
-    Ok((StatusCode::OK, Json(json_response)))
+    Ok(post)
 }
 
 pub async fn delete_post(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(post_id): Path<uuid::Uuid>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
     let result = sqlx::query!(
         r#"
@@ -390,16 +597,7 @@ pub async fn delete_post(
         user_id
     )
         .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to delete post: {err}"),
-                }))
-            )
-        })?;
+        .await?;
 
     if result.deleted {
         Ok((StatusCode::OK, Json(json!({
@@ -408,15 +606,9 @@ pub async fn delete_post(
             "data": post_id
         }))))
     } else if result.existed {
-        Err((StatusCode::FORBIDDEN, Json(json!({
-            "status": "fail",
-            "message": "Post is not yours",
-        }))))
+        Err(AppError::Forbidden("Post is not yours".to_string()))
     } else {
-        Err((StatusCode::NOT_FOUND, Json(json!({
-            "status": "fail",
-            "message": "Post not found",
-        }))))
+        Err(AppError::NotFound("Post not found".to_string()))
     }
 }
 
@@ -424,18 +616,37 @@ pub async fn favorite_post(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(post_id): Path<uuid::Uuid>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
-    let post = sqlx::query_as!(
-        PostFromQuery,
+    // Whether this call favorited the post, rather than finding it favorited already.
+    let favorited = sqlx::query_scalar!(
         r#"
             WITH the_post AS (
-                SELECT * FROM posts WHERE id = $2
+                SELECT id FROM posts
+                WHERE id = $2
+                    AND ((status = 'published' AND hidden_at IS NULL) OR user_id = $1)
             ),
             favorite AS (
                 INSERT INTO post_favorites (user_id, post_id)
                 SELECT $1, id FROM the_post
                 ON CONFLICT DO NOTHING
+                RETURNING post_id
+            )
+            SELECT EXISTS (SELECT 1 FROM favorite) "favorited!"
+            FROM the_post
+        "#,
+        user_id,
+        post_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    let post = sqlx::query_as!(
+        PostFromQuery,
+        r#"
+            WITH the_post AS (
+                SELECT * FROM posts WHERE id = $2
             )
             SELECT
                 the_post.id,
@@ -446,6 +657,9 @@ pub async fn favorite_post(
                 tags,
                 the_post.created_at,
                 the_post.updated_at,
+                the_post.status "status: PostStatus",
+                the_post.publish_at,
+                the_post.published_at,
                 EXISTS (SELECT 1 FROM post_favorites WHERE user_id = $1) "favorited!",
                 COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = the_post.id), 0) "favorites_count!",
                 author.display_name AS author_display_name,
@@ -459,22 +673,20 @@ pub async fn favorite_post(
         user_id,
         post_id,
     )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to favorite post: {err}"),
-                }))
-            )
-        })?;
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    if favorited {
+        let recipient_id = notify_post_author(&data.db, *user_id, NotificationKind::Favorite, post.id, None).await?;
+        announce(&data, recipient_id).await?;
+        publish_favorites_count(&data, post.id).await?;
+    }
 
     let json_response = json!({
         "status": "success",
         "message": "Post favorited",
-        "data": post.into_post_dto()
+        "data": post.into_post_dto(&data.markdown)
     });
 
     Ok((StatusCode::CREATED, Json(json_response)))
@@ -484,7 +696,7 @@ pub async fn unfavorite_post(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(post_id): Path<uuid::Uuid>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
     let post = sqlx::query_as!(
         PostFromQuery,
@@ -505,6 +717,9 @@ pub async fn unfavorite_post(
                 tags,
                 the_post.created_at,
                 the_post.updated_at,
+                the_post.status "status: PostStatus",
+                the_post.publish_at,
+                the_post.published_at,
                 EXISTS (SELECT 1 FROM post_favorites WHERE user_id = $1) "favorited!",
                 COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = the_post.id), 0) "favorites_count!",
                 author.display_name AS author_display_name,
@@ -519,26 +734,34 @@ pub async fn unfavorite_post(
         post_id,
     )
         .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Failed to unfavorite post: {err}"),
-                }))
-            )
-        })?;
+        .await?;
+
+    publish_favorites_count(&data, post.id).await?;
 
     let json_response = json!({
         "status": "success",
         "message": "Post unfavorited",
-        "data": post.into_post_dto()
+        "data": post.into_post_dto(&data.markdown)
     });
 
     Ok((StatusCode::CREATED, Json(json_response)))
 }
 
+/// Tells readers of the post its new favorite count. The count returned by `favorite_post` and
+/// `unfavorite_post` is read in the same statement as the change and doesn't include it yet.
+async fn publish_favorites_count(data: &AppState, post_id: Uuid) -> Result<(), AppError> {
+    let favorites_count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) "count!" FROM post_favorites WHERE post_id = $1"#,
+        post_id,
+    )
+        .fetch_one(&data.db)
+        .await?;
+
+    publish(data, &post_channel(post_id), &LiveEvent::FavoritesChanged { post_id, favorites_count }).await;
+
+    Ok(())
+}
+
 fn slugify(string: &str) -> String {
     const QUOTE_CHARS: &[char] = &['\'', '"'];
 
@@ -552,5 +775,63 @@ fn slugify(string: &str) -> String {
         })
         .collect();
 
+    if slug_parts.is_empty() {
+        return "post".to_string();
+    }
+
     slug_parts.join("-")
 }
+
+/// Returns `base_slug`, or `base_slug-N` with the smallest free `N`, skipping slugs used by other
+/// posts either currently or as redirects. `post_id` excludes the post being renamed.
+async fn unique_slug(
+    executor: impl sqlx::PgExecutor<'_>,
+    base_slug: &str,
+    post_id: Option<Uuid>,
+) -> Result<String, AppError> {
+    let taken: HashSet<String> = sqlx::query_scalar!(
+        r#"
+            SELECT slug "slug!" FROM posts
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND id IS DISTINCT FROM $2
+            UNION
+            SELECT slug FROM post_slug_redirects
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND post_id IS DISTINCT FROM $2
+        "#,
+        base_slug,
+        post_id,
+    )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .collect();
+
+    if !taken.contains(base_slug) {
+        return Ok(base_slug.to_string());
+    }
+
+    let mut suffix = 2;
+    loop {
+        let slug = format!("{base_slug}-{suffix}");
+        if !taken.contains(&slug) {
+            return Ok(slug);
+        }
+        suffix += 1;
+    }
+}
+
+fn into_posts_dto(page_request: PageRequest, rows: Vec<PostFromQuery>, total: Option<i64>) -> PostsDto {
+    let page = page_request.finish(rows, |post| (post.listed_at(), post.id));
+    let posts: Vec<PostDto> = page.rows.into_iter().map(PostFromQuery::into_listed_post_dto).collect();
+
+    PostsDto {
+        count: posts.len(),
+        posts,
+        total,
+        next_cursor: page.next_cursor,
+        prev_cursor: page.prev_cursor,
+    }
+}
+
+fn is_slug_conflict(err: &sqlx::Error) -> bool {
+    matches!(err, sqlx::Error::Database(db_err) if db_err.constraint() == Some("posts_slug_key"))
+}
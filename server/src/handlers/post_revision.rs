@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use serde_json::json;
+use similar::{ChangeTag, TextDiff};
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::dtos::post::*;
+use crate::handlers::post::apply_update;
+use crate::middlewares::auth::AuthUserClaims;
+
+#[derive(Deserialize, Default)]
+pub struct DiffQuery {
+    from: Option<Uuid>,
+    to: Option<Uuid>,
+}
+
+/// The parts of a post that are versioned.
+struct PostVersion {
+    title: String,
+    description: String,
+    body: String,
+    tags: Vec<String>,
+}
+
+/// Revisions are only shown to those who can read the post: anyone once it is published and not
+/// hidden, and its author before that.
+pub async fn get_post_revisions(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(post_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+
+    let post_id = sqlx::query_scalar!(
+        r#"
+            SELECT id FROM posts
+            WHERE id = $1
+                AND ((status = 'published' AND hidden_at IS NULL) OR user_id = $2)
+        "#,
+        post_id,
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    let revisions = sqlx::query_as!(
+        PostRevisionSummaryDto,
+        r#"
+            SELECT id, title, written_at, replaced_at
+            FROM post_revisions
+            WHERE post_id = $1
+            ORDER BY replaced_at DESC
+        "#,
+        post_id,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Post revisions fetched",
+        "data": PostRevisionsDto {
+            count: revisions.len(),
+            revisions,
+        }
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+pub async fn get_post_revision(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path((post_id, revision_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+
+    let revision = sqlx::query_as!(
+        PostRevisionDto,
+        r#"
+            SELECT
+                post_revisions.id,
+                post_revisions.title,
+                post_revisions.description,
+                post_revisions.body,
+                post_revisions.tags,
+                post_revisions.written_at,
+                post_revisions.replaced_at
+            FROM post_revisions
+            INNER JOIN posts ON posts.id = post_revisions.post_id
+            WHERE post_revisions.id = $1 AND post_revisions.post_id = $2
+                AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $3)
+        "#,
+        revision_id,
+        post_id,
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Revision not found".to_string()))?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Post revision fetched",
+        "data": revision
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// Line-level diff between two versions of a post; `from` and `to` default to the current version.
+pub async fn diff_post_revisions(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(post_id): Path<Uuid>,
+    query: Query<DiffQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+
+    let from = load_version(&data, &user_id, post_id, query.from).await?;
+    let to = load_version(&data, &user_id, post_id, query.to).await?;
+
+    let fields = vec![
+        diff_field("title", &from.title, &to.title),
+        diff_field("description", &from.description, &to.description),
+        diff_field("body", &from.body, &to.body),
+        diff_field("tags", &from.tags.join("\n"), &to.tags.join("\n")),
+    ];
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Post diff computed",
+        "data": PostDiffDto {
+            from: query.from,
+            to: query.to,
+            fields,
+        }
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// Makes a revision the current version of the post; the version it replaces becomes a revision
+/// itself, so a restore can be undone like any other edit.
+pub async fn restore_post_revision(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path((post_id, revision_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = &auth_user_claims.user_id().unwrap_or_default();
+
+    let mut tx = data.db.begin().await?;
+
+    let revision = sqlx::query!(
+        "SELECT title, description, body, tags FROM post_revisions WHERE id = $1 AND post_id = $2",
+        revision_id,
+        post_id,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Revision not found".to_string()))?;
+
+    let update = UpdatePostDto {
+        title: Some(revision.title),
+        description: Some(revision.description),
+        body: Some(revision.body),
+        tags: Some(revision.tags),
+        status: None,
+        publish_at: None,
+    };
+    let post = apply_update(&mut tx, user_id, post_id, &update).await?;
+
+    tx.commit().await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Post revision restored",
+        "data": post.into_post_dto(&data.markdown)
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// A revision of the post, or its current version, as long as `user_id` can read the post.
+async fn load_version(
+    data: &AppState,
+    user_id: &Uuid,
+    post_id: Uuid,
+    revision_id: Option<Uuid>,
+) -> Result<PostVersion, AppError> {
+    let version = match revision_id {
+        Some(revision_id) => sqlx::query_as!(
+            PostVersion,
+            r#"
+                SELECT post_revisions.title, post_revisions.description, post_revisions.body, post_revisions.tags
+                FROM post_revisions
+                INNER JOIN posts ON posts.id = post_revisions.post_id
+                WHERE post_revisions.id = $1 AND post_revisions.post_id = $2
+                    AND ((posts.status = 'published' AND posts.hidden_at IS NULL) OR posts.user_id = $3)
+            "#,
+            revision_id,
+            post_id,
+            user_id,
+        )
+            .fetch_optional(&data.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Revision not found".to_string()))?,
+        None => sqlx::query_as!(
+            PostVersion,
+            r#"
+                SELECT title, description, body, tags FROM posts
+                WHERE id = $1
+                    AND ((status = 'published' AND hidden_at IS NULL) OR user_id = $2)
+            "#,
+            post_id,
+            user_id,
+        )
+            .fetch_optional(&data.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?,
+    };
+
+    Ok(version)
+}
+
+fn diff_field(field: &str, old: &str, new: &str) -> FieldDiffDto {
+    let lines = TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLineDto {
+            change: match change.tag() {
+                ChangeTag::Equal => DiffChange::Equal,
+                ChangeTag::Insert => DiffChange::Insert,
+                ChangeTag::Delete => DiffChange::Delete,
+            },
+            old_line: change.old_index().map(|index| index + 1),
+            new_line: change.new_index().map(|index| index + 1),
+            text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect();
+
+    FieldDiffDto {
+        field: field.to_string(),
+        lines,
+    }
+}
@@ -2,46 +2,31 @@ use std::sync::Arc;
 
 use axum::{Extension, Json};
 use axum::extract::{Path, State};
-use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use serde_json::{json, Value};
+use serde_json::json;
+use uuid::Uuid;
 
 use crate::config::AppState;
-use crate::dtos::profile::ProfileDto;
+use crate::error::AppError;
+use crate::dtos::profile::{ProfileDto, RelatedUserDto, RelatedUsersDto};
 use crate::middlewares::auth::AuthUserClaims;
-use crate::models::user::User;
+use crate::models::notification::NotificationKind;
+use crate::notifications::{announce, notify};
 
 pub async fn get_user_profile(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(display_name): Path<String>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let user_id = &auth_user_claims.user_id().unwrap_or_default();
-    let profile = sqlx::query_as!(
-        ProfileDto,
-        r#"
-        SELECT display_name, biography, profile_image_url,
-            EXISTS (
-                SELECT 1 FROM user_follows
-                WHERE followee_user_id = users.id AND follower_user_id = $2
-            ) "following!"
-            FROM users
-            WHERE display_name = $1
-        "#,
-        display_name,
-        user_id
-    )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while fetching profile: {err}")
-                })),
-            )
-        })?;
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let owner_id = find_user_id(&data, &display_name).await?;
+
+    // Blocked users are told the profile doesn't exist rather than that they were blocked.
+    if is_blocked(&data, owner_id, user_id).await? {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
+
+    let profile = fetch_profile(&data, user_id, &display_name).await?;
 
     let json_response = json!({
         "status": "success",
@@ -56,51 +41,36 @@ pub async fn follow_user(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(display_name): Path<String>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let user_id = &auth_user_claims.user_id().unwrap_or_default();
-    let followee = sqlx::query_as!(
-        User,
-        "SELECT * FROM users WHERE display_name = $1",
-        display_name
-    )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while fetching user: {err}")
-                })),
-            )
-        })?;
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let followee_id = find_user_id(&data, &display_name).await?;
 
-    sqlx::query!(
+    if followee_id == user_id {
+        return Err(AppError::Forbidden("You cannot follow yourself".to_string()));
+    }
+
+    if is_blocked_either_way(&data, user_id, followee_id).await? {
+        return Err(AppError::Forbidden("You cannot follow this user".to_string()));
+    }
+
+    let followed = sqlx::query!(
         "INSERT INTO user_follows(follower_user_id, followee_user_id) VALUES($1, $2) ON CONFLICT DO NOTHING",
         user_id,
-        followee.id,
+        followee_id,
     )
         .execute(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while following user: {err}")
-                }))
-            )
-        })?;
+        .await?
+        .rows_affected() > 0;
+
+    if followed {
+        let recipient_id = notify(&data.db, followee_id, user_id, NotificationKind::Follow, None, None).await?;
+        announce(&data, recipient_id).await?;
+    }
 
     let json_response = json!({
         "status": "success",
         "message": "User followed",
-        "data": ProfileDto {
-            display_name: followee.display_name,
-            biography: followee.biography,
-            profile_image_url: followee.profile_image_url,
-            following: true
-        }
+        "data": fetch_profile(&data, user_id, &display_name).await?
     });
 
     Ok(Json(json_response))
@@ -110,52 +80,271 @@ pub async fn unfollow_user(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
     Path(display_name): Path<String>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let user_id = &auth_user_claims.user_id().unwrap_or_default();
-    let followee = sqlx::query_as!(
-        User,
-        "SELECT * FROM users WHERE display_name = $1",
-        display_name
-    )
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while fetching user: {err}")
-                })),
-            )
-        })?;
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let followee_id = find_user_id(&data, &display_name).await?;
 
     sqlx::query!(
         "DELETE FROM user_follows WHERE follower_user_id = $1 AND followee_user_id = $2",
         user_id,
-        followee.id,
+        followee_id,
     )
         .execute(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while unfollowing user: {err}")
-                }))
-            )
-        })?;
+        .await?;
 
     let json_response = json!({
         "status": "success",
         "message": "User unfollowed",
-        "data": ProfileDto {
-            display_name: followee.display_name,
-            biography: followee.biography,
-            profile_image_url: followee.profile_image_url,
-            following: false
+        "data": fetch_profile(&data, user_id, &display_name).await?
+    });
+
+    Ok(Json(json_response))
+}
+
+/// Blocks a user: they can no longer see the blocker's profile, follow them or comment on their
+/// posts. Follows in either direction are removed.
+pub async fn block_user(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(display_name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let blocked_id = find_user_id(&data, &display_name).await?;
+
+    if blocked_id == user_id {
+        return Err(AppError::Forbidden("You cannot block yourself".to_string()));
+    }
+
+    let mut tx = data.db.begin().await?;
+
+    sqlx::query!(
+        "INSERT INTO user_blocks(blocker_user_id, blocked_user_id) VALUES($1, $2) ON CONFLICT DO NOTHING",
+        user_id,
+        blocked_id,
+    )
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query!(
+        r#"
+            DELETE FROM user_follows
+            WHERE (follower_user_id = $1 AND followee_user_id = $2)
+                OR (follower_user_id = $2 AND followee_user_id = $1)
+        "#,
+        user_id,
+        blocked_id,
+    )
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "User blocked",
+        "data": fetch_profile(&data, user_id, &display_name).await?
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn unblock_user(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(display_name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let blocked_id = find_user_id(&data, &display_name).await?;
+
+    sqlx::query!(
+        "DELETE FROM user_blocks WHERE blocker_user_id = $1 AND blocked_user_id = $2",
+        user_id,
+        blocked_id,
+    )
+        .execute(&data.db)
+        .await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "User unblocked",
+        "data": fetch_profile(&data, user_id, &display_name).await?
+    });
+
+    Ok(Json(json_response))
+}
+
+/// Mutes a user: their posts and comments are left out of the muter's lists.
+pub async fn mute_user(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(display_name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let muted_id = find_user_id(&data, &display_name).await?;
+
+    if muted_id == user_id {
+        return Err(AppError::Forbidden("You cannot mute yourself".to_string()));
+    }
+
+    sqlx::query!(
+        "INSERT INTO user_mutes(muter_user_id, muted_user_id) VALUES($1, $2) ON CONFLICT DO NOTHING",
+        user_id,
+        muted_id,
+    )
+        .execute(&data.db)
+        .await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "User muted",
+        "data": fetch_profile(&data, user_id, &display_name).await?
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn unmute_user(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(display_name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let muted_id = find_user_id(&data, &display_name).await?;
+
+    sqlx::query!(
+        "DELETE FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = $2",
+        user_id,
+        muted_id,
+    )
+        .execute(&data.db)
+        .await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "User unmuted",
+        "data": fetch_profile(&data, user_id, &display_name).await?
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn get_blocked_users(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let users = sqlx::query_as!(
+        RelatedUserDto,
+        r#"
+            SELECT display_name, profile_image_url, user_blocks.created_at AS since
+            FROM user_blocks
+            INNER JOIN users ON users.id = user_blocks.blocked_user_id
+            WHERE blocker_user_id = $1
+            ORDER BY user_blocks.created_at DESC
+        "#,
+        user_id,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Blocked users fetched",
+        "data": RelatedUsersDto {
+            count: users.len(),
+            users,
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn get_muted_users(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let users = sqlx::query_as!(
+        RelatedUserDto,
+        r#"
+            SELECT display_name, profile_image_url, user_mutes.created_at AS since
+            FROM user_mutes
+            INNER JOIN users ON users.id = user_mutes.muted_user_id
+            WHERE muter_user_id = $1
+            ORDER BY user_mutes.created_at DESC
+        "#,
+        user_id,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Muted users fetched",
+        "data": RelatedUsersDto {
+            count: users.len(),
+            users,
         }
     });
 
     Ok(Json(json_response))
 }
+
+/// Whether `blocker_id` has blocked `blocked_id`.
+pub(crate) async fn is_blocked(data: &AppState, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, AppError> {
+    let blocked = sqlx::query_scalar!(
+        r#"
+            SELECT EXISTS (
+                SELECT 1 FROM user_blocks WHERE blocker_user_id = $1 AND blocked_user_id = $2
+            ) "blocked!"
+        "#,
+        blocker_id,
+        blocked_id,
+    )
+        .fetch_one(&data.db)
+        .await?;
+
+    Ok(blocked)
+}
+
+async fn is_blocked_either_way(data: &AppState, user_id: Uuid, other_id: Uuid) -> Result<bool, AppError> {
+    Ok(is_blocked(data, user_id, other_id).await? || is_blocked(data, other_id, user_id).await?)
+}
+
+async fn find_user_id(data: &AppState, display_name: &str) -> Result<Uuid, AppError> {
+    let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE display_name = $1", display_name)
+        .fetch_one(&data.db)
+        .await?;
+
+    Ok(user_id)
+}
+
+/// The profile along with `viewer_id`'s relationship to it.
+async fn fetch_profile(data: &AppState, viewer_id: Uuid, display_name: &str) -> Result<ProfileDto, AppError> {
+    let profile = sqlx::query_as!(
+        ProfileDto,
+        r#"
+        SELECT display_name, biography, profile_image_url,
+            EXISTS (
+                SELECT 1 FROM user_follows
+                WHERE followee_user_id = users.id AND follower_user_id = $2
+            ) "following!",
+            EXISTS (
+                SELECT 1 FROM user_blocks
+                WHERE blocked_user_id = users.id AND blocker_user_id = $2
+            ) "blocking!",
+            EXISTS (
+                SELECT 1 FROM user_mutes
+                WHERE muted_user_id = users.id AND muter_user_id = $2
+            ) "muting!"
+            FROM users
+            WHERE display_name = $1
+        "#,
+        display_name,
+        viewer_id
+    )
+        .fetch_one(&data.db)
+        .await?;
+
+    Ok(profile)
+}
@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::audit::{self, AuditAction};
+use crate::config::AppState;
+use crate::dtos::moderation::{ModerationDto, ResolveReportsDto};
+use crate::dtos::report::*;
+use crate::dtos::Wrapper;
+use crate::error::AppError;
+use crate::handlers::moderation::{set_comment_hidden, set_post_hidden};
+use crate::middlewares::auth::AuthUserClaims;
+use crate::middlewares::role::Moderator;
+use crate::models::report::{ReportCategory, ReportTarget};
+use crate::pagination::PageRequest;
+use crate::validation::ValidatedJson;
+
+/// How much of a reported comment is shown in the queue.
+const EXCERPT_LENGTH: usize = 200;
+
+#[derive(Deserialize, Default)]
+pub struct ReportQueueQuery {
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+struct ReportGroupFromQuery {
+    post_id: Option<Uuid>,
+    comment_id: Option<Uuid>,
+    target_id: Uuid,
+    post_slug: String,
+    excerpt: String,
+    author: String,
+    hidden: bool,
+    report_count: i64,
+    categories: Vec<ReportCategory>,
+    first_reported_at: DateTime<Utc>,
+    last_reported_at: DateTime<Utc>,
+}
+
+pub async fn report_post(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(post_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<Wrapper<NewReportDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let mut tx = data.db.begin().await?;
+
+    // Locking the post keeps concurrent reports from both missing the threshold.
+    let author_id = sqlx::query_scalar!(
+        r#"
+            SELECT user_id
+            FROM posts
+            WHERE id = $1 AND status = 'published' AND hidden_at IS NULL
+            FOR UPDATE
+        "#,
+        post_id,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+
+    if author_id == user_id {
+        return Err(AppError::Forbidden("You cannot report your own post".to_string()));
+    }
+
+    let report_id = file_report(&data, &mut tx, user_id, ReportTarget::Post, post_id, &body.data).await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(json!({
+        "status": "success",
+        "message": "Post reported",
+        "data": report_id,
+    }))))
+}
+
+pub async fn report_comment(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path((post_id, comment_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(body): ValidatedJson<Wrapper<NewReportDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let mut tx = data.db.begin().await?;
+
+    let author_id = sqlx::query_scalar!(
+        r#"
+            SELECT user_id
+            FROM comments
+            WHERE id = $1 AND post_id = $2 AND deleted_at IS NULL AND hidden_at IS NULL
+            FOR UPDATE
+        "#,
+        comment_id,
+        post_id,
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+    if author_id == user_id {
+        return Err(AppError::Forbidden("You cannot report your own comment".to_string()));
+    }
+
+    let report_id = file_report(&data, &mut tx, user_id, ReportTarget::Comment, comment_id, &body.data).await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(json!({
+        "status": "success",
+        "message": "Comment reported",
+        "data": report_id,
+    }))))
+}
+
+/// Open reports grouped by what they are about, most recently reported first.
+pub async fn get_report_queue(
+    Moderator(_): Moderator,
+    State(data): State<Arc<AppState>>,
+    query: Query<ReportQueueQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let page_request = PageRequest::new(query.cursor.as_deref(), query.limit)?;
+    let rows = sqlx::query_as!(
+        ReportGroupFromQuery,
+        r#"
+            SELECT
+                grouped.post_id,
+                grouped.comment_id,
+                COALESCE(grouped.post_id, grouped.comment_id) "target_id!",
+                COALESCE(posts.slug, comment_posts.slug) "post_slug!",
+                COALESCE(posts.title, comments.body) "excerpt!",
+                author.display_name AS author,
+                COALESCE(posts.hidden_at, comments.hidden_at) IS NOT NULL "hidden!",
+                grouped.report_count "report_count!",
+                grouped.categories AS "categories!: Vec<ReportCategory>",
+                grouped.first_reported_at "first_reported_at!",
+                grouped.last_reported_at "last_reported_at!"
+            FROM (
+                SELECT
+                    post_id,
+                    comment_id,
+                    COUNT(*) AS report_count,
+                    array_agg(DISTINCT category) AS categories,
+                    MIN(created_at) AS first_reported_at,
+                    MAX(created_at) AS last_reported_at
+                FROM reports
+                WHERE status = 'open'
+                GROUP BY post_id, comment_id
+            ) AS grouped
+            LEFT JOIN posts ON posts.id = grouped.post_id
+            LEFT JOIN comments ON comments.id = grouped.comment_id
+            LEFT JOIN posts AS comment_posts ON comment_posts.id = comments.post_id
+            INNER JOIN users AS author ON author.id = COALESCE(posts.user_id, comments.user_id)
+            WHERE (comments.id IS NULL OR comments.deleted_at IS NULL)
+            AND (
+                $1::TIMESTAMPTZ IS NULL
+                OR ( $3 AND (grouped.last_reported_at, COALESCE(grouped.post_id, grouped.comment_id)) > ($1, $2::UUID) )
+                OR ( NOT $3 AND (grouped.last_reported_at, COALESCE(grouped.post_id, grouped.comment_id)) < ($1, $2::UUID) )
+            )
+            ORDER BY
+                CASE WHEN $3 THEN grouped.last_reported_at END ASC,
+                CASE WHEN $3 THEN COALESCE(grouped.post_id, grouped.comment_id) END ASC,
+                grouped.last_reported_at DESC,
+                COALESCE(grouped.post_id, grouped.comment_id) DESC
+            LIMIT $4
+        "#,
+        page_request.timestamp(),
+        page_request.id(),
+        page_request.is_backward(),
+        page_request.fetch_limit(),
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let page = page_request.finish(rows, |group| (group.last_reported_at, group.target_id));
+
+    let post_ids: Vec<Uuid> = page.rows.iter().filter_map(|group| group.post_id).collect();
+    let comment_ids: Vec<Uuid> = page.rows.iter().filter_map(|group| group.comment_id).collect();
+    let reports = sqlx::query!(
+        r#"
+            SELECT
+                reports.id,
+                COALESCE(reports.post_id, reports.comment_id) "target_id!",
+                reporter.display_name AS reporter,
+                category AS "category: ReportCategory",
+                details,
+                reports.created_at
+            FROM reports
+            INNER JOIN users AS reporter ON reporter.id = reports.reporter_id
+            WHERE status = 'open' AND (post_id = ANY($1) OR comment_id = ANY($2))
+            ORDER BY reports.created_at DESC
+        "#,
+        &post_ids,
+        &comment_ids,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let mut reports_by_target: HashMap<Uuid, Vec<ReportDto>> = HashMap::new();
+    for report in reports {
+        reports_by_target.entry(report.target_id).or_default().push(ReportDto {
+            id: report.id,
+            reporter: report.reporter,
+            category: report.category,
+            details: report.details,
+            created_at: report.created_at,
+        });
+    }
+
+    let groups: Vec<ReportGroupDto> = page
+        .rows
+        .into_iter()
+        .map(|group| ReportGroupDto {
+            target_type: if group.comment_id.is_some() { ReportTarget::Comment } else { ReportTarget::Post },
+            target_id: group.target_id,
+            post_slug: group.post_slug,
+            excerpt: group.excerpt.chars().take(EXCERPT_LENGTH).collect(),
+            author: group.author,
+            hidden: group.hidden,
+            report_count: group.report_count,
+            categories: group.categories,
+            first_reported_at: group.first_reported_at,
+            last_reported_at: group.last_reported_at,
+            reports: reports_by_target.remove(&group.target_id).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Report queue fetched",
+        "data": ReportQueueDto {
+            count: groups.len(),
+            groups,
+            next_cursor: page.next_cursor,
+            prev_cursor: page.prev_cursor,
+        },
+    }))))
+}
+
+/// Upholds the open reports on a post or comment, hiding it if asked to.
+pub async fn resolve_reports(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path((target, target_id)): Path<(ReportTarget, Uuid)>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ResolveReportsDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+
+    let (post_id, comment_id) = split_target(target, target_id);
+    let closed = sqlx::query!(
+        r#"
+            UPDATE reports
+            SET status = 'resolved', resolved_at = NOW(), resolved_by = $1
+            WHERE status = 'open' AND (post_id = $2 OR comment_id = $3)
+        "#,
+        moderator.id,
+        post_id,
+        comment_id,
+    )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    if closed == 0 {
+        return Err(AppError::NotFound("No open reports found".to_string()));
+    }
+
+    if body.data.hide {
+        match target {
+            ReportTarget::Post => set_post_hidden(&mut tx, Some(moderator.id), target_id, true, &body.data.reason).await?,
+            ReportTarget::Comment => set_comment_hidden(&mut tx, Some(moderator.id), target_id, true, &body.data.reason).await?,
+        }
+    }
+
+    let action = match target {
+        ReportTarget::Post => AuditAction::ResolvePostReports,
+        ReportTarget::Comment => AuditAction::ResolveCommentReports,
+    };
+    audit::record(
+        &mut *tx,
+        Some(moderator.id),
+        action,
+        target_id,
+        &body.data.reason,
+        json!({ "report_count": closed, "hidden": body.data.hide }),
+    )
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Reports resolved",
+        "data": target_id,
+    }))))
+}
+
+/// Rejects the open reports on a post or comment. If the reports had hidden it, it is shown again.
+pub async fn dismiss_reports(
+    Moderator(moderator): Moderator,
+    State(data): State<Arc<AppState>>,
+    Path((target, target_id)): Path<(ReportTarget, Uuid)>,
+    ValidatedJson(body): ValidatedJson<Wrapper<ModerationDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = data.db.begin().await?;
+
+    let (post_id, comment_id) = split_target(target, target_id);
+    let closed = sqlx::query!(
+        r#"
+            UPDATE reports
+            SET status = 'dismissed', resolved_at = NOW(), resolved_by = $1
+            WHERE status = 'open' AND (post_id = $2 OR comment_id = $3)
+        "#,
+        moderator.id,
+        post_id,
+        comment_id,
+    )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    if closed == 0 {
+        return Err(AppError::NotFound("No open reports found".to_string()));
+    }
+
+    let (hide, unhide, auto_hide) = match target {
+        ReportTarget::Post => (AuditAction::HidePost, AuditAction::UnhidePost, AuditAction::AutoHidePost),
+        ReportTarget::Comment => (AuditAction::HideComment, AuditAction::UnhideComment, AuditAction::AutoHideComment),
+    };
+    let last_visibility_change = sqlx::query_scalar!(
+        r#"
+            SELECT action
+            FROM audit_log
+            WHERE target_type = $1 AND target_id = $2 AND action = ANY($3)
+            ORDER BY created_at DESC
+            LIMIT 1
+        "#,
+        auto_hide.target_type(),
+        target_id,
+        &[hide.as_str().to_string(), unhide.as_str().to_string(), auto_hide.as_str().to_string()],
+    )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    let restored = last_visibility_change.as_deref() == Some(auto_hide.as_str());
+    if restored {
+        match target {
+            ReportTarget::Post => set_post_hidden(&mut tx, Some(moderator.id), target_id, false, &body.data.reason).await?,
+            ReportTarget::Comment => set_comment_hidden(&mut tx, Some(moderator.id), target_id, false, &body.data.reason).await?,
+        }
+    }
+
+    let action = match target {
+        ReportTarget::Post => AuditAction::DismissPostReports,
+        ReportTarget::Comment => AuditAction::DismissCommentReports,
+    };
+    audit::record(
+        &mut *tx,
+        Some(moderator.id),
+        action,
+        target_id,
+        &body.data.reason,
+        json!({ "report_count": closed, "restored": restored }),
+    )
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "status": "success",
+        "message": "Reports dismissed",
+        "data": target_id,
+    }))))
+}
+
+/// Stores a report and hides its target once enough readers have open reports on it. The caller
+/// has locked the target row.
+async fn file_report(
+    data: &AppState,
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    reporter_id: Uuid,
+    target: ReportTarget,
+    target_id: Uuid,
+    report: &NewReportDto,
+) -> Result<Uuid, AppError> {
+    let (post_id, comment_id) = split_target(target, target_id);
+
+    let report_id = sqlx::query_scalar!(
+        r#"
+            INSERT INTO reports (reporter_id, post_id, comment_id, category, details)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING
+            RETURNING id
+        "#,
+        reporter_id,
+        post_id,
+        comment_id,
+        report.category as ReportCategory,
+        report.details.trim(),
+    )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("You have already reported this".to_string()))?;
+
+    let open_reports = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) "count!" FROM reports WHERE status = 'open' AND (post_id = $1 OR comment_id = $2)"#,
+        post_id,
+        comment_id,
+    )
+        .fetch_one(&mut **tx)
+        .await?;
+
+    if open_reports >= data.env.report_hide_threshold {
+        let reason = format!("Reported by {open_reports} readers");
+        match target {
+            ReportTarget::Post => set_post_hidden(tx, None, target_id, true, &reason).await?,
+            ReportTarget::Comment => set_comment_hidden(tx, None, target_id, true, &reason).await?,
+        }
+    }
+
+    Ok(report_id)
+}
+
+fn split_target(target: ReportTarget, target_id: Uuid) -> (Option<Uuid>, Option<Uuid>) {
+    match target {
+        ReportTarget::Post => (Some(target_id), None),
+        ReportTarget::Comment => (None, Some(target_id)),
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config::AppState;
+use crate::dtos::search::SearchResultsDto;
+use crate::error::AppError;
+use crate::markdown::escape;
+use crate::middlewares::auth::AuthUserClaims;
+use crate::models::post::{PostStatus, SearchResultFromQuery};
+use crate::pagination::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::validation::ValidationErrors;
+
+/// `ts_headline` marks matches with these control characters so the text can be HTML-escaped
+/// before the markers are turned into `<mark>` tags.
+const HIGHLIGHT_START: char = '\u{1}';
+const HIGHLIGHT_STOP: char = '\u{2}';
+
+#[derive(Deserialize, Default)]
+pub struct SearchQuery {
+    q: Option<String>,
+    tag: Option<String>,
+    author: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+pub async fn search(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    query: Query<SearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let q = query.q.as_deref().unwrap_or_default();
+    let mut errors = ValidationErrors::default();
+    errors.required("q", q);
+    errors.into_result()?;
+
+    let user_id = &auth_user_claims.user_id().unwrap_or_default();
+    let title_options = format!("StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_STOP}, HighlightAll=true");
+    let snippet_options = format!(
+        "StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_STOP}, MaxFragments=2, MaxWords=30, MinWords=10, FragmentDelimiter=\" ... \""
+    );
+
+    let rows = sqlx::query_as!(
+        SearchResultFromQuery,
+        r#"
+            WITH search AS (
+                SELECT websearch_to_tsquery('english', $2) AS query
+            ),
+            matches AS (
+                SELECT
+                    posts.id,
+                    ts_rank(posts.search_vector, search.query) + COALESCE (
+                        (
+                            SELECT MAX(ts_rank(comments.search_vector, search.query))
+                            FROM comments
+                            WHERE comments.post_id = posts.id AND comments.search_vector @@ search.query
+                                AND comments.hidden_at IS NULL AND comments.deleted_at IS NULL
+                                AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = comments.user_id)
+                        ),
+                        0
+                    ) AS rank
+                FROM posts, search
+                WHERE posts.status = 'published'
+                    AND posts.hidden_at IS NULL
+                    AND (
+                        posts.search_vector @@ search.query
+                        OR EXISTS (
+                            SELECT 1 FROM comments
+                            WHERE comments.post_id = posts.id AND comments.search_vector @@ search.query
+                                AND comments.hidden_at IS NULL AND comments.deleted_at IS NULL
+                                AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = comments.user_id)
+                        )
+                    )
+            )
+            SELECT
+                posts.id,
+                slug,
+                title,
+                description,
+                body,
+                tags,
+                posts.created_at,
+                posts.updated_at,
+                posts.status "status: PostStatus",
+                posts.publish_at,
+                posts.published_at,
+                EXISTS (SELECT 1 FROM post_favorites WHERE post_id = posts.id AND user_id = $1) "favorited!",
+                COALESCE ( (SELECT COUNT(*) FROM post_favorites WHERE post_id = posts.id), 0) "favorites_count!",
+                author.display_name AS author_display_name,
+                author.biography AS author_biography,
+                author.profile_image_url AS author_profile_image_url,
+                EXISTS (SELECT 1 FROM user_follows WHERE followee_user_id = author.id AND follower_user_id = $1) "following_author!",
+                matches.rank "rank!",
+                ts_headline('english', title, search.query, $5) "title_highlight!",
+                COALESCE (
+                    CASE WHEN posts.search_vector @@ search.query
+                        THEN ts_headline('english', description || E'\n' || body, search.query, $6)
+                    END,
+                    (
+                        SELECT ts_headline('english', comments.body, search.query, $6)
+                        FROM comments
+                        WHERE comments.post_id = posts.id AND comments.search_vector @@ search.query
+                            AND comments.hidden_at IS NULL AND comments.deleted_at IS NULL
+                            AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = comments.user_id)
+                        ORDER BY ts_rank(comments.search_vector, search.query) DESC
+                        LIMIT 1
+                    ),
+                    description
+                ) "snippet!",
+                COUNT(*) OVER () "total!"
+            FROM matches
+            CROSS JOIN search
+            INNER JOIN posts ON posts.id = matches.id
+            INNER JOIN users AS author ON author.id = posts.user_id
+            WHERE ( $3::TEXT IS NULL OR tags @> array[$3] )
+                AND ( $4::TEXT IS NULL OR author.display_name = $4 )
+                -- As in post lists, muted authors only turn up when searched by name.
+                AND ( $4::TEXT IS NOT NULL OR NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = $1 AND muted_user_id = posts.user_id) )
+            ORDER BY matches.rank DESC, posts.created_at DESC, posts.id DESC
+            LIMIT $7
+            OFFSET $8
+        "#,
+        user_id,
+        q,
+        query.tag,
+        query.author,
+        title_options,
+        snippet_options,
+        query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        query.offset.unwrap_or(0).max(0),
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let total = rows.first().map_or(0, |row| row.total);
+    let results: Vec<_> = rows
+        .into_iter()
+        .map(|row| row.into_search_result_dto(highlight))
+        .collect();
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Search results fetched",
+        "data": SearchResultsDto {
+            count: results.len(),
+            results,
+            total,
+        }
+    });
+
+    Ok((StatusCode::OK, Json(json_response)))
+}
+
+/// Escapes `ts_headline` output and turns its match markers into `<mark>` tags.
+fn highlight(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find([HIGHLIGHT_START, HIGHLIGHT_STOP]) {
+        html.push_str(&escape(&rest[..at]));
+        let marker = rest[at..].chars().next().expect("find returned where a marker starts");
+        html.push_str(if marker == HIGHLIGHT_START { "<mark>" } else { "</mark>" });
+        rest = &rest[at + marker.len_utf8()..];
+    }
+    html.push_str(&escape(rest));
+    html
+}
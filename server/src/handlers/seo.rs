@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::{header, HeaderValue};
+use axum::response::IntoResponse;
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::markdown::escape;
+use crate::seo::encode_segment;
+
+/// The most URLs one sitemap may list.
+const SITEMAP_MAX_URLS: i64 = 50_000;
+const CACHE_CONTROL: &str = "public, max-age=3600";
+/// Client routes that only make sense signed in.
+const DISALLOWED_PATHS: [&str; 7] = ["/api/", "/admin", "/editor", "/history/", "/moderation", "/settings", "/users/"];
+
+/// `/sitemap.xml`: the home page, every post that can be read signed out and the profiles of
+/// their authors, newest first.
+pub async fn sitemap(State(data): State<Arc<AppState>>) -> Result<impl IntoResponse, AppError> {
+    let posts = sqlx::query!(
+        r#"
+            SELECT slug, updated_at
+            FROM posts
+            WHERE status = 'published' AND hidden_at IS NULL
+            ORDER BY updated_at DESC
+            LIMIT $1
+        "#,
+        SITEMAP_MAX_URLS / 2,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let authors = sqlx::query!(
+        r#"
+            SELECT users.display_name, MAX(posts.updated_at) "updated_at!"
+            FROM users
+            INNER JOIN posts ON posts.user_id = users.id
+            WHERE posts.status = 'published' AND posts.hidden_at IS NULL
+            GROUP BY users.id
+            ORDER BY 2 DESC
+            LIMIT $1
+        "#,
+        SITEMAP_MAX_URLS / 2 - 1,
+    )
+        .fetch_all(&data.db)
+        .await?;
+
+    let app_url = &data.env.app_url;
+    let mut urls = vec![url_entry(&format!("{app_url}/"), posts.first().map(|post| post.updated_at.to_rfc3339()))];
+    urls.extend(posts.iter().map(|post| {
+        url_entry(
+            &format!("{app_url}/posts/{}", encode_segment(&post.slug)),
+            Some(post.updated_at.to_rfc3339()),
+        )
+    }));
+    urls.extend(authors.iter().map(|author| {
+        url_entry(
+            &format!("{app_url}/profile/{}", encode_segment(&author.display_name)),
+            Some(author.updated_at.to_rfc3339()),
+        )
+    }));
+
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n{}</urlset>\n",
+        urls.concat(),
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/xml; charset=utf-8")),
+            (header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL)),
+        ],
+        body,
+    ))
+}
+
+/// `/robots.txt`: keeps crawlers out of the API and signed-in pages, and points them at the sitemap.
+pub async fn robots(State(data): State<Arc<AppState>>) -> impl IntoResponse {
+    let disallowed: String = DISALLOWED_PATHS
+        .iter()
+        .map(|path| format!("Disallow: {path}\n"))
+        .collect();
+    let body = format!(
+        "User-agent: *\n{disallowed}\nSitemap: {}/sitemap.xml\n",
+        data.env.app_url,
+    );
+
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8")),
+            (header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL)),
+        ],
+        body,
+    )
+}
+
+fn url_entry(location: &str, last_modified: Option<String>) -> String {
+    let escaped = escape(location);
+    match last_modified {
+        Some(last_modified) => format!("  <url><loc>{escaped}</loc><lastmod>{last_modified}</lastmod></url>\n"),
+        None => format!("  <url><loc>{escaped}</loc></url>\n"),
+    }
+}
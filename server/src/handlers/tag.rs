@@ -1,11 +1,12 @@
 use std::sync::Arc;
 use axum::extract::State;
-use axum::http::StatusCode;
-use axum::Json;
+use axum::{Extension, Json};
 use axum::response::IntoResponse;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::json;
 use crate::config::AppState;
+use crate::error::AppError;
+use crate::middlewares::auth::AuthUserClaims;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TagsDto {
@@ -13,26 +14,21 @@ pub struct TagsDto {
 }
 
 pub async fn get_tags(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)>  {
+) -> Result<impl IntoResponse, AppError>  {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
     let tags = sqlx::query_scalar!(
         r#"
             SELECT DISTINCT tag "tag!"
             FROM posts, unnest(tags) as tags(tag)
+            WHERE (status = 'published' AND hidden_at IS NULL) OR user_id = $1
             ORDER BY tag
-        "#
+        "#,
+        user_id,
     )
         .fetch_all(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("error fetching tags: {err}")
-                })),
-            )
-        })?;
+        .await?;
 
     let json_response = json!({
         "status": "success",
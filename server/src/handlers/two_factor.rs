@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum::extract::State;
+use axum::http::{Response, StatusCode};
+use axum::response::IntoResponse;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::dtos::two_factor::*;
+use crate::dtos::Wrapper;
+use crate::error::AppError;
+use crate::handlers::user::{sign_in, start_session};
+use crate::middlewares::auth::AuthUserClaims;
+use crate::session::ClientInfo;
+use crate::models::user::{User, UserRole};
+use crate::two_factor;
+use crate::validation::ValidatedJson;
+
+/// What the first sign-in step responds with when the user has two-factor authentication on: no
+/// tokens yet, only what [`login_two_factor`] exchanges for them along with a code.
+pub(crate) async fn challenge(data: &AppState, user_id: Uuid) -> Result<Response<String>, AppError> {
+    let pending_token = two_factor::issue_pending_token(data, user_id).await?;
+    let json_response = json!({
+        "status": "success",
+        "message": "Two-factor authentication required",
+        "data": TwoFactorChallengeDto { pending_token },
+    });
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .body(json_response.to_string())
+        .unwrap())
+}
+
+/// The second sign-in step.
+pub async fn login_two_factor(
+    State(data): State<Arc<AppState>>,
+    client_info: ClientInfo,
+    ValidatedJson(body): ValidatedJson<Wrapper<TwoFactorLoginDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let req = body.data;
+    let user_id = two_factor::redeem_pending_token(&data, req.pending_token, &req.code).await?;
+
+    let user = sqlx::query_as!(
+        User,
+        r#"
+            SELECT id, email, display_name, password, biography, profile_image_url, role AS "role: UserRole", email_verified_at, created_at, updated_at
+            FROM users
+            WHERE id = $1
+        "#,
+        user_id
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
+
+    let session_id = start_session(&data, user.id, &client_info).await?;
+    let (user, headers) = sign_in(&data, user, session_id).await?;
+    let json_response = json!({
+        "status": "success",
+        "message": "Login successful",
+        "data": user
+    });
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .body(json_response.to_string())
+        .unwrap();
+
+    response.headers_mut().extend(headers);
+
+    Ok(response)
+}
+
+pub async fn get_status(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Two-factor authentication status fetched",
+        "data": status(&data, user_id).await?,
+    })))
+}
+
+/// Starts enrolling with a new secret. Two-factor authentication stays off until [`confirm`]
+/// proves the user's authenticator app has it; starting again replaces the secret.
+pub async fn enroll(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = auth_user_claims
+        .user
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let secret = two_factor::generate_secret();
+    sqlx::query_scalar!(
+        r#"
+            INSERT INTO user_totp (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, created_at = NOW()
+            WHERE user_totp.enabled_at IS NULL
+            RETURNING user_id
+        "#,
+        user.id,
+        secret,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Two-factor authentication is already on".to_string()))?;
+
+    let provisioning_uri = two_factor::provisioning_uri(&secret, &user.email)?;
+    let qr_code_svg = two_factor::qr_code_svg(&provisioning_uri)?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Enrollment started",
+        "data": TwoFactorEnrollmentDto {
+            secret,
+            provisioning_uri,
+            qr_code_svg,
+        },
+    })))
+}
+
+/// Turns two-factor authentication on once the user enters a code from their newly set-up app,
+/// and hands out their recovery codes. This is the only time they are shown.
+pub async fn confirm(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    ValidatedJson(body): ValidatedJson<Wrapper<TwoFactorCodeDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let secret = sqlx::query_scalar!(
+        "SELECT secret FROM user_totp WHERE user_id = $1 AND enabled_at IS NULL",
+        user_id,
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("There is no enrollment to confirm".to_string()))?;
+
+    if !two_factor::accept_totp(&data.db, user_id, &secret, &body.data.code).await? {
+        return Err(two_factor::invalid_code());
+    }
+
+    let mut tx = data.db.begin().await?;
+    sqlx::query!("UPDATE user_totp SET enabled_at = NOW() WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    let recovery_codes = two_factor::replace_recovery_codes(&mut tx, user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Two-factor authentication turned on",
+        "data": RecoveryCodesDto { recovery_codes },
+    })))
+}
+
+/// Turns two-factor authentication off, which takes a current code.
+pub async fn disable(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    ValidatedJson(body): ValidatedJson<Wrapper<TwoFactorCodeDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    two_factor::check(&data, user_id, &body.data.code).await?;
+
+    let mut tx = data.db.begin().await?;
+    sqlx::query!("DELETE FROM user_totp WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM user_recovery_codes WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Two-factor authentication turned off",
+        "data": status(&data, user_id).await?,
+    })))
+}
+
+/// Replaces the user's recovery codes, which takes a current code.
+pub async fn regenerate_recovery_codes(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    ValidatedJson(body): ValidatedJson<Wrapper<TwoFactorCodeDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims
+        .user_id()
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    if !two_factor::is_enabled(&data.db, user_id).await? {
+        return Err(AppError::Conflict("Two-factor authentication is off".to_string()));
+    }
+    two_factor::check(&data, user_id, &body.data.code).await?;
+
+    let mut tx = data.db.begin().await?;
+    let recovery_codes = two_factor::replace_recovery_codes(&mut tx, user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Recovery codes replaced",
+        "data": RecoveryCodesDto { recovery_codes },
+    })))
+}
+
+async fn status(data: &AppState, user_id: Uuid) -> Result<TwoFactorStatusDto, AppError> {
+    let enabled = two_factor::is_enabled(&data.db, user_id).await?;
+    let recovery_codes_remaining = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM user_recovery_codes WHERE user_id = $1 AND used_at IS NULL"#,
+        user_id,
+    )
+        .fetch_one(&data.db)
+        .await?;
+
+    Ok(TwoFactorStatusDto { enabled, recovery_codes_remaining })
+}
@@ -3,61 +3,58 @@ use std::sync::Arc;
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use argon2::password_hash::SaltString;
 use axum::{Extension, Json};
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::http::{HeaderMap, Response, StatusCode};
 use axum::response::IntoResponse;
-use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
 use rand_core::OsRng;
 use redis::aio::Connection;
 use redis::AsyncCommands;
-use serde_json::{json, Value};
+use serde_json::json;
 
 use crate::config::AppState;
 use crate::dtos::user::*;
 use crate::dtos::Wrapper;
+use crate::error::AppError;
+use crate::handlers::account::send_verification_email;
+use crate::handlers::two_factor::challenge as two_factor_challenge;
 use crate::middlewares::auth::AuthUserClaims;
+use crate::session;
+use crate::session::ClientInfo;
 use crate::middlewares::token;
 use crate::middlewares::token::TokenData;
-use crate::models::user::User;
+use crate::models::user::{User, UserRole};
+use crate::two_factor;
+use crate::validation::ValidatedJson;
 
 pub async fn me(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    if let Some(user) = auth_user_claims.user {
-        let access_token_uuid = auth_user_claims.access_token_uuid.unwrap_or_default();
-        let access_token = find_access_token_in_redis(&data, access_token_uuid).await?;
-
-        let json_response = json!({
-            "status": "success",
-            "data": UserDto {
-                email: user.email,
-                display_name: user.display_name,
-                access_token,
-                biography: user.biography.unwrap_or_default(),
-                profile_image_url: user.profile_image_url.unwrap_or_default(),
-            }
-        });
-
-        Ok(Json(json_response))
-    } else {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "status": "fail",
-                "message": "Unauthorized"
-            })),
-        ));
-    }
+) -> Result<impl IntoResponse, AppError> {
+    let user = auth_user_claims
+        .user
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    let access_token_uuid = auth_user_claims.access_token_uuid.unwrap_or_default();
+    let access_token = find_access_token_in_redis(&data, access_token_uuid).await?;
+
+    let json_response = json!({
+        "status": "success",
+        "data": UserDto::new(user, access_token)
+    });
+
+    Ok(Json(json_response))
 }
 
 pub async fn update_me(
     Extension(auth_user_claims): Extension<AuthUserClaims>,
     State(data): State<Arc<AppState>>,
-    Json(body): Json<Wrapper<UserUpdateDto>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    ValidatedJson(body): ValidatedJson<Wrapper<UserUpdateDto>>,
+) -> Result<impl IntoResponse, AppError> {
     let user_id = &auth_user_claims.user_id().unwrap_or_default();
+    let previous_email = auth_user_claims.user.as_ref().map(|user| user.email.clone());
     let req = body.data;
+    let password_changed = req.password.is_some();
 
     let query = match req.password {
         Some(password) => {
@@ -65,7 +62,8 @@ pub async fn update_me(
             sqlx::query_as::<_, User>(
                 r#"
                 UPDATE users
-                SET email = $1, display_name = $2, biography = $3, profile_image_url = $4, password = $5
+                SET email = $1, display_name = $2, biography = $3, profile_image_url = $4, password = $5,
+                    email_verified_at = CASE WHEN email = $1 THEN email_verified_at END
                 WHERE id = $6
                 RETURNING *
                 "#,
@@ -80,7 +78,8 @@ pub async fn update_me(
         None => sqlx::query_as::<_, User>(
             r#"
                 UPDATE users
-                SET email = $1, display_name = $2, biography = $3, profile_image_url = $4
+                SET email = $1, display_name = $2, biography = $3, profile_image_url = $4,
+                    email_verified_at = CASE WHEN email = $1 THEN email_verified_at END
                 WHERE id = $5
                 RETURNING *
                 "#,
@@ -92,15 +91,15 @@ pub async fn update_me(
             .bind(user_id),
     };
 
-    let user = query.fetch_one(&data.db).await.map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "status": "fail",
-                "message": format!("Something bad happened while updating user: {err}")
-            })),
-        )
-    })?;
+    let user = query.fetch_one(&data.db).await?;
+
+    if password_changed {
+        revoke_all_sessions(&data, user.id, auth_user_claims.access_token_uuid).await?;
+    }
+
+    if previous_email.as_deref() != Some(user.email.as_str()) {
+        send_verification_email(&data, user.id, &user.email).await;
+    }
 
     let mut access_token = String::default();
 
@@ -111,13 +110,7 @@ pub async fn update_me(
     let json_response = json!({
         "status": "success",
         "message": "User updated successfully",
-        "data": UserDto {
-            email: user.email,
-            display_name: user.display_name,
-            access_token,
-            biography: user.biography.unwrap_or_default(),
-            profile_image_url: user.profile_image_url.unwrap_or_default(),
-        }
+        "data": UserDto::new(user, access_token)
     });
 
     Ok((StatusCode::OK, Json(json_response)))
@@ -125,56 +118,35 @@ pub async fn update_me(
 
 pub async fn sign_up(
     State(data): State<Arc<AppState>>,
-    Json(body): Json<Wrapper<SignUpDto>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    client_info: ClientInfo,
+    ValidatedJson(body): ValidatedJson<Wrapper<SignUpDto>>,
+) -> Result<impl IntoResponse, AppError> {
     let req = body.data;
     let hashed_password = get_hashed_password(&req.password)?;
 
     let user = sqlx::query_as!(
         User,
-        "INSERT INTO users (email, password, display_name) VALUES ($1, $2, $3) returning *",
+        r#"
+            INSERT INTO users (email, password, display_name)
+            VALUES ($1, $2, $3)
+            RETURNING id, email, display_name, password, biography, profile_image_url, role AS "role: UserRole", email_verified_at, created_at, updated_at
+        "#,
         req.email,
         hashed_password,
         req.display_name
     )
         .fetch_one(&data.db)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while creating user: {err}")
-                })),
-            )
-        })?;
+        .await?;
 
-    let access_token_data = issue_access_token(
-        user.id,
-        data.env.access_token_max_age,
-        data.env.access_token_private_key.to_owned(),
-    )?;
-    let refresh_token_data = issue_access_token(
-        user.id,
-        data.env.refresh_token_max_age,
-        data.env.refresh_token_private_key.to_owned(),
-    )?;
+    send_verification_email(&data, user.id, &user.email).await;
 
-    save_access_token_to_redis(&data, &access_token_data, data.env.access_token_max_age).await?;
-    save_access_token_to_redis(&data, &refresh_token_data, data.env.refresh_token_max_age).await?;
-
-    let headers = set_cookies(data, &access_token_data, &refresh_token_data);
+    let session_id = start_session(&data, user.id, &client_info).await?;
+    let (user, headers) = sign_in(&data, user, session_id).await?;
 
     let json_response = json!({
         "status": "success",
         "message": "User created",
-        "data": UserDto {
-            email: user.email,
-            display_name: user.display_name,
-            access_token: access_token_data.access_token.unwrap(),
-            biography: user.biography.unwrap_or_default(),
-            profile_image_url: user.profile_image_url.unwrap_or_default(),
-        }
+        "data": user
     });
 
     let mut response = Response::builder()
@@ -187,8 +159,24 @@ pub async fn sign_up(
     Ok(response)
 }
 
+/// Issues a token pair for `session_id`, returning the signed-in user along with the cookies that
+/// carry the tokens.
+pub(crate) async fn sign_in(
+    data: &Arc<AppState>,
+    user: User,
+    session_id: uuid::Uuid,
+) -> Result<(UserDto, HeaderMap), AppError> {
+    let (access_token_data, refresh_token_data) = issue_tokens(data, user.id, session_id).await?;
+    let headers = set_cookies(data, &access_token_data, &refresh_token_data);
+
+    Ok((
+        UserDto::new(user, access_token_data.access_token.unwrap()),
+        headers,
+    ))
+}
+
 fn set_cookies(
-    data: Arc<AppState>,
+    data: &AppState,
     access_token_data: &TokenData,
     refresh_token_data: &TokenData,
 ) -> HeaderMap {
@@ -226,88 +214,120 @@ fn set_cookies(
     headers
 }
 
-fn get_hashed_password(password: &str) -> Result<String, (StatusCode, Json<Value>)> {
+fn clear_cookies() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for name in ["access_token", "refresh_token", "logged_in"] {
+        let cookie = Cookie::build(name, "")
+            .path("/")
+            .max_age(time::Duration::ZERO)
+            .same_site(SameSite::Lax)
+            .http_only(name != "logged_in")
+            .finish();
+        headers.append("Set-Cookie", cookie.to_string().parse().unwrap());
+    }
+    headers
+}
+
+pub(crate) fn get_hashed_password(password: &str) -> Result<String, AppError> {
     let salt = SaltString::generate(&mut OsRng);
     let hashed_password = Argon2::default()
-        .hash_password(password.as_bytes(), &salt)
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while hashing password: {err}")
-                })),
-            )
-        })
-        .map(|hash| hash.to_string())?;
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string();
     Ok(hashed_password)
 }
 
 pub async fn login(
     State(data): State<Arc<AppState>>,
-    Json(body): Json<Wrapper<LoginDto>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    client_info: ClientInfo,
+    ValidatedJson(body): ValidatedJson<Wrapper<LoginDto>>,
+) -> Result<impl IntoResponse, AppError> {
     let req = body.data;
-    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE email = $1", req.email)
+    let user = sqlx::query_as!(
+        User,
+        r#"
+            SELECT id, email, display_name, password, biography, profile_image_url, role AS "role: UserRole", email_verified_at, created_at, updated_at
+            FROM users
+            WHERE email = $1
+        "#,
+        req.email
+    )
         .fetch_optional(&data.db)
-        .await
-        .map_err(|err| {
-            let error_response = json!({
-                "status": "error",
-                "message": format!("Login failed: {err}")
-            });
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-        })?
-        .ok_or_else(|| {
-            let error_response = json!({
-                "status": "error",
-                "message": "Login failed: Invalid credentials"
-            });
-            (StatusCode::UNAUTHORIZED, Json(error_response))
-        })?;
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Login failed: Invalid credentials".to_string()))?;
 
     let is_valid = match PasswordHash::new(&user.password) {
         Ok(parsed_hash) => Argon2::default()
             .verify_password(req.password.as_bytes(), &parsed_hash)
-            .map_or(false, |_| true),
+            .is_ok(),
         Err(_) => false,
     };
 
     if !is_valid {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "status": "fail",
-                "message": "Login failed: Invalid credentials"
-            })),
-        ));
+        return Err(AppError::Unauthorized("Login failed: Invalid credentials".to_string()));
     }
 
-    let access_token_data = issue_access_token(
-        user.id,
-        data.env.access_token_max_age,
-        data.env.access_token_private_key.to_owned(),
-    )?;
-    let refresh_token_data = issue_access_token(
-        user.id,
-        data.env.refresh_token_max_age,
-        data.env.refresh_token_private_key.to_owned(),
-    )?;
-
-    save_access_token_to_redis(&data, &access_token_data, data.env.access_token_max_age).await?;
-    save_access_token_to_redis(&data, &refresh_token_data, data.env.refresh_token_max_age).await?;
+    if two_factor::is_enabled(&data.db, user.id).await? {
+        return two_factor_challenge(&data, user.id).await;
+    }
 
-    let headers = set_cookies(data, &access_token_data, &refresh_token_data);
+    let session_id = start_session(&data, user.id, &client_info).await?;
+    let (user, headers) = sign_in(&data, user, session_id).await?;
     let json_response = json!({
         "status": "success",
         "message": "Login successful",
-        "data": UserDto {
-            email: user.email,
-            display_name: user.display_name,
-            access_token: access_token_data.access_token.unwrap(),
-            biography: user.biography.unwrap_or_default(),
-            profile_image_url: user.profile_image_url.unwrap_or_default(),
-        }
+        "data": user
+    });
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .body(json_response.to_string())
+        .unwrap();
+
+    response.headers_mut().extend(headers);
+
+    Ok(response)
+}
+
+pub async fn refresh(
+    cookie_jar: CookieJar,
+    State(data): State<Arc<AppState>>,
+    client_info: ClientInfo,
+) -> Result<impl IntoResponse, AppError> {
+    // Only ever sent as an httpOnly cookie, out of reach of scripts on the page.
+    let refresh_token = cookie_jar
+        .get("refresh_token")
+        .map(|cookie| cookie.value().to_string())
+        .ok_or_else(|| AppError::Unauthorized("Refresh token is missing".to_string()))?;
+
+    let refresh_token_data =
+        token::verify_token(data.env.refresh_token_public_key.to_owned(), &refresh_token)
+            .map_err(|err| AppError::Unauthorized(format!("Invalid refresh token: {err}")))?;
+
+    let session_id = find_session_id(&data, refresh_token_data.token_uuid).await?;
+    rotate_refresh_token(&data, &refresh_token_data, &refresh_token).await?;
+
+    let user = sqlx::query_as!(
+        User,
+        r#"
+            SELECT id, email, display_name, password, biography, profile_image_url, role AS "role: UserRole", email_verified_at, created_at, updated_at
+            FROM users
+            WHERE id = $1
+        "#,
+        refresh_token_data.user_id
+    )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
+
+    let session_id = match session_id {
+        Some(session_id) => session_id,
+        None => start_session(&data, user.id, &client_info).await?,
+    };
+    let (user, headers) = sign_in(&data, user, session_id).await?;
+    let json_response = json!({
+        "status": "success",
+        "message": "Token refreshed",
+        "data": user
     });
 
     let mut response = Response::builder()
@@ -320,81 +340,410 @@ pub async fn login(
     Ok(response)
 }
 
+pub async fn logout(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let access_token_uuid = auth_user_claims.access_token_uuid.unwrap_or_default();
+
+    let session_id = find_session_id(&data, access_token_uuid).await?;
+    let revoked = revoke_token_pair(&data, user_id, access_token_uuid).await?;
+
+    if let Some(session_id) = session_id {
+        let mut redis_client = get_redis_client(&data).await?;
+        session::end(&mut redis_client, user_id, session_id).await?;
+    }
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Logged out",
+        "data": revoked
+    });
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .body(json_response.to_string())
+        .unwrap();
+
+    response.headers_mut().extend(clear_cookies());
+
+    Ok(response)
+}
+
+pub async fn logout_all(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+
+    let revoked = revoke_all_sessions(&data, user_id, None).await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Logged out from all devices",
+        "data": revoked
+    });
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .body(json_response.to_string())
+        .unwrap();
+
+    response.headers_mut().extend(clear_cookies());
+
+    Ok(response)
+}
+
+pub async fn sessions(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let mut redis_client = get_redis_client(&data).await?;
+
+    let sessions: Vec<SessionDto> = session::list(&mut redis_client, user_id)
+        .await?
+        .into_iter()
+        .map(|session| SessionDto {
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+            current: session.access_token_uuid.is_some()
+                && session.access_token_uuid == auth_user_claims.access_token_uuid,
+        })
+        .collect();
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Sessions fetched",
+        "data": SessionsDto {
+            count: sessions.len(),
+            sessions,
+        }
+    });
+
+    Ok(Json(json_response))
+}
+
+pub async fn delete_session(
+    Extension(auth_user_claims): Extension<AuthUserClaims>,
+    State(data): State<Arc<AppState>>,
+    Path(session_id): Path<uuid::Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_user_claims.user_id().unwrap_or_default();
+    let mut redis_client = get_redis_client(&data).await?;
+
+    let session = session::find(&mut redis_client, session_id)
+        .await?
+        .filter(|session| session.user_id == user_id)
+        .ok_or_else(|| AppError::NotFound("Session not found".to_string()))?;
+
+    let token_uuids: Vec<uuid::Uuid> = session
+        .access_token_uuid
+        .into_iter()
+        .chain(session.refresh_token_uuid)
+        .collect();
+    delete_tokens_from_redis(&mut redis_client, user_id, &token_uuids).await?;
+    session::end(&mut redis_client, user_id, session_id).await?;
+
+    let json_response = json!({
+        "status": "success",
+        "message": "Session revoked",
+        "data": session_id
+    });
+
+    Ok(Json(json_response))
+}
+
+async fn issue_tokens(
+    data: &Arc<AppState>,
+    user_id: uuid::Uuid,
+    session_id: uuid::Uuid,
+) -> Result<(TokenData, TokenData), AppError> {
+    let access_token_data = issue_access_token(
+        user_id,
+        data.env.access_token_max_age,
+        data.env.access_token_private_key.to_owned(),
+    )?;
+    let refresh_token_data = issue_access_token(
+        user_id,
+        data.env.refresh_token_max_age,
+        data.env.refresh_token_private_key.to_owned(),
+    )?;
+
+    save_access_token_to_redis(data, &access_token_data, data.env.access_token_max_age).await?;
+    save_access_token_to_redis(data, &refresh_token_data, data.env.refresh_token_max_age).await?;
+
+    let ttl = (data.env.refresh_token_max_age * 60) as usize;
+    let mut redis_client = get_redis_client(data).await?;
+    redis::pipe()
+        .atomic()
+        .set_ex(
+            paired_access_token_key(refresh_token_data.token_uuid),
+            access_token_data.token_uuid.to_string(),
+            ttl,
+        )
+        .set_ex(
+            paired_refresh_token_key(access_token_data.token_uuid),
+            refresh_token_data.token_uuid.to_string(),
+            ttl,
+        )
+        .sadd(
+            user_token_uuids_key(user_id),
+            &[
+                access_token_data.token_uuid.to_string(),
+                refresh_token_data.token_uuid.to_string(),
+            ],
+        )
+        .expire(user_token_uuids_key(user_id), ttl)
+        .query_async::<_, ()>(&mut redis_client)
+        .await?;
+
+    session::attach_tokens(
+        &mut redis_client,
+        session_id,
+        access_token_data.token_uuid,
+        refresh_token_data.token_uuid,
+        ttl,
+    )
+        .await?;
+
+    Ok((access_token_data, refresh_token_data))
+}
+
+pub(crate) async fn start_session(
+    data: &Arc<AppState>,
+    user_id: uuid::Uuid,
+    client_info: &ClientInfo,
+) -> Result<uuid::Uuid, AppError> {
+    let mut redis_client = get_redis_client(data).await?;
+
+    let session_id = session::start(
+        &mut redis_client,
+        user_id,
+        client_info,
+        (data.env.refresh_token_max_age * 60) as usize,
+    )
+        .await?;
+
+    Ok(session_id)
+}
+
+async fn find_session_id(
+    data: &Arc<AppState>,
+    token_uuid: uuid::Uuid,
+) -> Result<Option<uuid::Uuid>, AppError> {
+    let mut redis_client = get_redis_client(data).await?;
+
+    Ok(session::find_by_token(&mut redis_client, token_uuid).await?)
+}
+
+fn paired_access_token_key(refresh_token_uuid: uuid::Uuid) -> String {
+    format!("{refresh_token_uuid}:access_token_uuid")
+}
+
+fn paired_refresh_token_key(access_token_uuid: uuid::Uuid) -> String {
+    format!("{access_token_uuid}:refresh_token_uuid")
+}
+
+fn used_refresh_token_key(refresh_token_uuid: uuid::Uuid) -> String {
+    format!("{refresh_token_uuid}:used")
+}
+
+fn user_token_uuids_key(user_id: uuid::Uuid) -> String {
+    format!("user:{user_id}:token_uuids")
+}
+
+/// Consumes a refresh token so that it can be exchanged exactly once.
+///
+/// The stored token and the access token issued alongside it are removed, and the UUID is
+/// remembered as used until it would have expired. Presenting a used token again is treated as
+/// theft and revokes every session of the user.
+async fn rotate_refresh_token(
+    data: &Arc<AppState>,
+    refresh_token_data: &TokenData,
+    refresh_token: &str,
+) -> Result<(), AppError> {
+    let mut redis_client = get_redis_client(data).await?;
+    let refresh_token_uuid = refresh_token_data.token_uuid;
+
+    let stored_refresh_token = redis_client
+        .get_del::<_, Option<String>>(refresh_token_uuid.to_string())
+        .await?;
+
+    if stored_refresh_token.as_deref() != Some(refresh_token) {
+        let reused = redis_client
+            .exists::<_, bool>(used_refresh_token_key(refresh_token_uuid))
+            .await
+            .unwrap_or_default();
+
+        if reused {
+            revoke_all_sessions(data, refresh_token_data.user_id, None).await?;
+            return Err(AppError::Unauthorized("Refresh token reuse detected".to_string()));
+        }
+
+        return Err(AppError::Unauthorized("Invalid refresh token".to_string()));
+    }
+
+    redis_client
+        .set_ex::<_, _, ()>(
+            used_refresh_token_key(refresh_token_uuid),
+            true,
+            (data.env.refresh_token_max_age * 60) as usize,
+        )
+        .await?;
+
+    let paired_access_token_uuid =
+        find_token_uuid_in_redis(&mut redis_client, paired_access_token_key(refresh_token_uuid))
+            .await?;
+
+    if let Some(access_token_uuid) = paired_access_token_uuid {
+        revoke_token_pair(data, refresh_token_data.user_id, access_token_uuid).await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes an access token together with the refresh token issued alongside it.
+///
+/// Returns the number of tokens that were still live.
+async fn revoke_token_pair(
+    data: &Arc<AppState>,
+    user_id: uuid::Uuid,
+    access_token_uuid: uuid::Uuid,
+) -> Result<usize, AppError> {
+    let mut redis_client = get_redis_client(data).await?;
+
+    let refresh_token_uuid =
+        find_token_uuid_in_redis(&mut redis_client, paired_refresh_token_key(access_token_uuid))
+            .await?;
+
+    let mut token_uuids = vec![access_token_uuid];
+    token_uuids.extend(refresh_token_uuid);
+
+    delete_tokens_from_redis(&mut redis_client, user_id, &token_uuids).await
+}
+
+/// Deletes every token and session of a user, optionally sparing the session of
+/// `keep_access_token_uuid`.
+///
+/// Returns the number of tokens that were still live.
+pub(crate) async fn revoke_all_sessions(
+    data: &Arc<AppState>,
+    user_id: uuid::Uuid,
+    keep_access_token_uuid: Option<uuid::Uuid>,
+) -> Result<usize, AppError> {
+    let mut redis_client = get_redis_client(data).await?;
+
+    let mut keep = Vec::new();
+    if let Some(access_token_uuid) = keep_access_token_uuid {
+        keep.push(access_token_uuid);
+        keep.extend(
+            find_token_uuid_in_redis(&mut redis_client, paired_refresh_token_key(access_token_uuid))
+                .await?,
+        );
+    }
+
+    let token_uuids: Vec<uuid::Uuid> = redis_client
+        .smembers::<_, Vec<String>>(user_token_uuids_key(user_id))
+        .await?
+        .iter()
+        .filter_map(|token_uuid| uuid::Uuid::parse_str(token_uuid).ok())
+        .filter(|token_uuid| !keep.contains(token_uuid))
+        .collect();
+
+    let revoked = delete_tokens_from_redis(&mut redis_client, user_id, &token_uuids).await?;
+
+    let sessions = session::list(&mut redis_client, user_id).await?;
+    for session in sessions {
+        if keep_access_token_uuid.is_none() || session.access_token_uuid != keep_access_token_uuid {
+            session::end(&mut redis_client, user_id, session.id).await?;
+        }
+    }
+
+    Ok(revoked)
+}
+
+async fn find_token_uuid_in_redis(
+    redis_client: &mut Connection,
+    key: String,
+) -> Result<Option<uuid::Uuid>, AppError> {
+    let token_uuid = redis_client.get::<_, Option<String>>(key).await?;
+
+    Ok(token_uuid.and_then(|token_uuid| uuid::Uuid::parse_str(&token_uuid).ok()))
+}
+
+async fn delete_tokens_from_redis(
+    redis_client: &mut Connection,
+    user_id: uuid::Uuid,
+    token_uuids: &[uuid::Uuid],
+) -> Result<usize, AppError> {
+    if token_uuids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for token_uuid in token_uuids {
+        pipe.del(token_uuid.to_string())
+            .del(&[
+                paired_access_token_key(*token_uuid),
+                paired_refresh_token_key(*token_uuid),
+                session::token_session_key(*token_uuid),
+            ])
+            .ignore()
+            .srem(user_token_uuids_key(user_id), token_uuid.to_string())
+            .ignore();
+    }
+
+    let deleted: Vec<usize> = pipe.query_async(redis_client).await?;
+
+    Ok(deleted.iter().sum())
+}
+
 fn issue_access_token(
     user_id: uuid::Uuid,
     max_age: i64,
     private_key: String,
-) -> Result<TokenData, (StatusCode, Json<Value>)> {
-    token::generate_token(user_id, max_age, private_key).map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "status": "fail",
-                "message": format!("Something bad happened while generating token: {err}")
-            })),
-        )
-    })
+) -> Result<TokenData, AppError> {
+    Ok(token::generate_token(user_id, max_age, private_key)?)
 }
 
 async fn save_access_token_to_redis(
     data: &Arc<AppState>,
     token_data: &TokenData,
     max_age: i64,
-) -> Result<(), (StatusCode, Json<Value>)> {
+) -> Result<(), AppError> {
     let mut redis_client = get_redis_client(data).await?;
 
     redis_client
-        .set_ex(
+        .set_ex::<_, _, ()>(
             token_data.token_uuid.to_string(),
             token_data.access_token.as_ref(),
             (max_age * 60) as usize,
         )
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while saving token to redis: {err}")
-                })),
-            )
-        })?;
+        .await?;
 
     Ok(())
 }
 
-async fn find_access_token_in_redis(
+pub(crate) async fn find_access_token_in_redis(
     data: &Arc<AppState>,
     access_token_uuid: uuid::Uuid,
-) -> Result<String, (StatusCode, Json<Value>)> {
+) -> Result<String, AppError> {
     let mut redis_client = get_redis_client(data).await?;
 
     let access_token = redis_client
-        .get::<_, String>(access_token_uuid.to_string())
-        .await
-        .map_err(|err| {
-            (StatusCode::UNPROCESSABLE_ENTITY, Json(json!({
-                "status": "fail",
-                "message": format!("Something bad happened while fetching token from redis: {err}")
-            })))
-        })?;
+        .get::<_, Option<String>>(access_token_uuid.to_string())
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Access token expired or revoked".to_string()))?;
 
     Ok(access_token)
 }
 
-async fn get_redis_client(data: &Arc<AppState>) -> Result<Connection, (StatusCode, Json<Value>)> {
-    let redis_client = data
-        .redis_client
-        .get_async_connection()
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Something bad happened while connecting to redis: {err}")
-                })),
-            )
-        })?;
-    Ok(redis_client)
+async fn get_redis_client(data: &Arc<AppState>) -> Result<Connection, AppError> {
+    Ok(data.redis_client.get_async_connection().await?)
 }
@@ -1,9 +1,27 @@
 extern crate core;
 
+pub mod admins;
+pub mod audit;
 pub mod config;
 pub mod dtos;
+pub mod error;
+pub mod events;
+pub mod feeds;
 pub mod handlers;
+pub mod mailer;
+pub mod markdown;
+pub mod media;
 pub mod middlewares;
+pub mod notifications;
+pub mod oauth;
 pub mod models;
+pub mod pagination;
 pub mod routes;
+pub mod scheduler;
 pub mod schema;
+pub mod seo;
+pub mod session;
+pub mod ssr;
+pub mod storage;
+pub mod two_factor;
+pub mod validation;
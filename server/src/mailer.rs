@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::Utc;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::config::{Config, MailTransport};
+use crate::error::AppError;
+
+/// A plain-text email.
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), AppError>;
+}
+
+/// The mailer selected by `MAIL_TRANSPORT`.
+pub fn from_config(config: &Config) -> Arc<dyn Mailer> {
+    let from = config
+        .mail_from
+        .parse::<Mailbox>()
+        .unwrap_or_else(|err| panic!("MAIL_FROM is not a valid mailbox: {err}"));
+
+    match config.mail_transport {
+        MailTransport::Smtp => Arc::new(SmtpMailer::new(config, from)),
+        MailTransport::File => Arc::new(FileMailer {
+            from,
+            dir: config.mail_dir.as_ref().map(PathBuf::from),
+        }),
+    }
+}
+
+/// Delivers through an SMTP relay, using STARTTLS unless the relay is on localhost.
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &Config, from: Mailbox) -> Self {
+        let mut builder = if config.smtp_host == "localhost" {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+                .unwrap_or_else(|err| panic!("Invalid SMTP_HOST: {err}"))
+        };
+        if let Some(port) = config.smtp_port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        SmtpMailer {
+            transport: builder.build(),
+            from,
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), AppError> {
+        let message = build_message(&self.from, email)?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|err| AppError::Internal(format!("SMTP error: {err}")))?;
+
+        Ok(())
+    }
+}
+
+/// Writes each email as an `.eml` file in `dir`, or prints it when there is no `dir`.
+pub struct FileMailer {
+    from: Mailbox,
+    dir: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), AppError> {
+        let message = String::from_utf8_lossy(&build_message(&self.from, email)?.formatted()).into_owned();
+
+        match &self.dir {
+            Some(dir) => {
+                let path = dir.join(format!("{}-{}.eml", Utc::now().format("%Y%m%dT%H%M%S"), Uuid::new_v4()));
+                let written = async {
+                    fs::create_dir_all(dir).await?;
+                    fs::write(&path, message).await
+                }
+                    .await;
+                written.map_err(|err| AppError::Internal(format!("Failed to write {}: {err}", path.display())))?;
+            }
+            None => println!("{message}"),
+        }
+
+        Ok(())
+    }
+}
+
+fn build_message(from: &Mailbox, email: Email) -> Result<Message, AppError> {
+    let to = email
+        .to
+        .parse::<Mailbox>()
+        .map_err(|err| AppError::Internal(format!("Invalid recipient {}: {err}", email.to)))?;
+
+    Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(email.subject)
+        .body(email.body)
+        .map_err(|err| AppError::Internal(format!("Failed to build email: {err}")))
+}
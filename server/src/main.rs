@@ -2,6 +2,7 @@ use std::{
     net::{IpAddr, Ipv6Addr, SocketAddr},
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 use clap::Parser;
@@ -9,8 +10,14 @@ use dotenv::dotenv;
 use redis::Client;
 use sqlx::postgres::PgPoolOptions;
 
+use server::admins;
 use server::config::{AppState, Config, Opt};
+use server::events::EventHub;
+use server::mailer;
+use server::markdown::MarkdownRenderer;
 use server::routes::create_router;
+use server::scheduler;
+use server::storage;
 
 #[tokio::main]
 async fn main() {
@@ -37,6 +44,12 @@ async fn main() {
         Err(err) => panic!("Error connecting to database: {err}"),
     };
 
+    match admins::promote_configured_admins(&pool, &config.admin_emails).await {
+        Ok(0) => {}
+        Ok(count) => log::info!("Promoted {count} users listed in ADMIN_EMAILS to admin"),
+        Err(err) => log::warn!("Failed to promote the users listed in ADMIN_EMAILS: {err:?}"),
+    }
+
     let redis_client = match Client::open(config.redis_url.to_owned()) {
         Ok(client) => client,
         Err(err) => panic!("Error connecting to redis: {err}"),
@@ -47,11 +60,20 @@ async fn main() {
         opt.port,
     ));
 
+    tokio::spawn(scheduler::publish_scheduled_posts(
+        pool.clone(),
+        Duration::from_secs(opt.publish_interval.max(1)),
+    ));
+
     let app = create_router(
         Arc::new(AppState {
             db: pool.clone(),
             env: config.clone(),
             redis_client: redis_client.clone(),
+            events: EventHub::start(redis_client.clone()),
+            mailer: mailer::from_config(&config),
+            storage: storage::from_config(&config),
+            markdown: MarkdownRenderer::new(),
         }),
         opt,
     );
@@ -62,7 +84,7 @@ async fn main() {
     );
 
     axum::Server::bind(&socket_addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .expect("Unable to start server")
 }
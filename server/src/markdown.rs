@@ -0,0 +1,336 @@
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use ammonia::Builder;
+use chrono::{DateTime, Utc};
+use lru::LruCache;
+use pulldown_cmark::escape::escape_html;
+use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag};
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+use uuid::Uuid;
+
+use crate::dtos::post::TocEntryDto;
+
+/// How many rendered post bodies are kept around.
+const CACHE_CAPACITY: usize = 1024;
+const THEME: &str = "InspiredGitHub";
+/// Put in front of every id in a post, so that none can clobber the page's own elements or
+/// globals.
+const ID_PREFIX: &str = "user-content-";
+
+/// Everything rendered HTML may contain; anything else in a post's raw HTML is dropped.
+const TAGS: [&str; 42] = [
+    "a", "abbr", "b", "blockquote", "br", "code", "dd", "del", "details", "div", "dl", "dt", "em",
+    "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i", "img", "input", "kbd", "li", "ol", "p", "pre",
+    "s", "span", "strong", "sub", "summary", "sup", "table", "tbody", "td", "th", "thead", "tr",
+    "u", "ul",
+];
+const TAG_ATTRIBUTES: [(&str, &[&str]); 15] = [
+    ("a", &["href", "title"]),
+    ("abbr", &["title"]),
+    // Footnotes are linked to by id.
+    ("div", &["id"]),
+    ("h1", &["id"]),
+    ("h2", &["id"]),
+    ("h3", &["id"]),
+    ("h4", &["id"]),
+    ("h5", &["id"]),
+    ("h6", &["id"]),
+    ("img", &["src", "alt", "title", "width", "height"]),
+    ("input", &["type", "checked", "disabled"]),
+    ("pre", &["style"]),
+    ("span", &["style"]),
+    ("td", &["align"]),
+    ("th", &["align"]),
+];
+const URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Rendered posts by id and `updated_at`.
+type RenderCache = LruCache<(Uuid, DateTime<Utc>), Arc<RenderedMarkdown>>;
+
+/// A post body as HTML that is safe to put on the page as is.
+#[derive(Debug)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub toc: Vec<TocEntryDto>,
+}
+
+/// Turns Markdown into sanitized HTML, with anchors on headings, a table of contents built from
+/// them, and highlighted code blocks.
+pub struct MarkdownRenderer {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+    sanitizer: Builder<'static>,
+    cache: Mutex<RenderCache>,
+}
+
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownRenderer {
+    pub fn new() -> Self {
+        let mut themes = ThemeSet::load_defaults().themes;
+        let theme = themes
+            .remove(THEME)
+            .unwrap_or_else(|| panic!("syntect has no {THEME} theme"));
+
+        MarkdownRenderer {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme,
+            sanitizer: sanitizer(),
+            cache: Mutex::new(LruCache::new(NonZeroUsize::new(CACHE_CAPACITY).unwrap())),
+        }
+    }
+
+    /// Renders a post's body. Every edit to a post bumps `updated_at`, so the pair names one
+    /// revision of it, and each revision is only rendered once for as long as it stays cached.
+    pub fn render_post(&self, post_id: Uuid, updated_at: DateTime<Utc>, body: &str) -> Arc<RenderedMarkdown> {
+        let key = (post_id, updated_at);
+        if let Some(rendered) = self.cache.lock().unwrap().get(&key) {
+            return rendered.clone();
+        }
+
+        let rendered = Arc::new(self.render(body));
+        self.cache.lock().unwrap().put(key, rendered.clone());
+        rendered
+    }
+
+    pub fn render(&self, markdown: &str) -> RenderedMarkdown {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_FOOTNOTES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        options.insert(Options::ENABLE_TASKLISTS);
+        options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+
+        let mut parser = Parser::new_ext(markdown, options);
+        let mut events = Vec::new();
+        let mut toc = Vec::new();
+        let mut ids = HashSet::new();
+
+        while let Some(event) = parser.next() {
+            match event {
+                Event::Start(Tag::Heading(level, id, _)) => {
+                    let inner: Vec<Event> = parser
+                        .by_ref()
+                        .take_while(|event| !matches!(event, Event::End(Tag::Heading(..))))
+                        .collect();
+                    let title = plain_text(&inner);
+                    let id = format!("{ID_PREFIX}{}", unique_id(&slugify(id.unwrap_or(&title)), &mut ids));
+
+                    let mut heading = String::new();
+                    html::push_html(&mut heading, inner.into_iter());
+                    events.push(Event::Html(
+                        format!("<{level} id=\"{id}\">{heading} <a class=\"anchor\" href=\"#{id}\">#</a></{level}>\n").into(),
+                    ));
+                    toc.push(TocEntryDto { level: level as u8, id, title });
+                }
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    let code: String = parser
+                        .by_ref()
+                        .take_while(|event| !matches!(event, Event::End(Tag::CodeBlock(_))))
+                        .filter_map(|event| match event {
+                            Event::Text(text) => Some(text.into_string()),
+                            _ => None,
+                        })
+                        .collect();
+                    let language = match &kind {
+                        CodeBlockKind::Fenced(info) => info.split_whitespace().next(),
+                        CodeBlockKind::Indented => None,
+                    };
+                    events.push(Event::Html(self.highlight(&code, language).into()));
+                }
+                event => events.push(event),
+            }
+        }
+
+        let mut unsafe_html = String::new();
+        html::push_html(&mut unsafe_html, events.into_iter());
+
+        RenderedMarkdown {
+            html: self.sanitizer.clean(&unsafe_html).to_string(),
+            toc,
+        }
+    }
+
+    /// A code block, colored with inline styles when its language is known.
+    fn highlight(&self, code: &str, language: Option<&str>) -> String {
+        let highlighted = language
+            .and_then(|language| self.syntaxes.find_syntax_by_token(language))
+            .and_then(|syntax| highlighted_html_for_string(code, &self.syntaxes, syntax, &self.theme).ok());
+        if let Some(highlighted) = highlighted {
+            return highlighted;
+        }
+
+        format!("<pre><code>{}</code></pre>\n", escape(code))
+    }
+}
+
+/// `text` with the characters that mean something in HTML or XML escaped, for use in text or
+/// attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    escape_html(&mut escaped, text).expect("writing to a String never fails");
+    escaped
+}
+
+fn sanitizer() -> Builder<'static> {
+    let mut builder = Builder::empty();
+    builder
+        .tags(TAGS.into_iter().collect())
+        .tag_attributes(
+            TAG_ATTRIBUTES
+                .into_iter()
+                .map(|(tag, attributes)| (tag, attributes.iter().copied().collect()))
+                .collect(),
+        )
+        .allowed_classes(HashMap::from([("a", HashSet::from(["anchor"]))]))
+        .url_schemes(URL_SCHEMES.into_iter().collect())
+        // Added to every link, though it only matters on external ones.
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .id_prefix(Some(ID_PREFIX))
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            // Links within the post, such as to footnotes, follow their targets' ids.
+            ("a", "href") if value.starts_with('#') && !value[1..].starts_with(ID_PREFIX) => {
+                Some(format!("#{ID_PREFIX}{}", &value[1..]).into())
+            }
+            (_, "style") if is_highlighting_style(value) => Some(value.into()),
+            (_, "style") => None,
+            // Only task list checkboxes.
+            ("input", "type") if value == "checkbox" => Some(value.into()),
+            ("input", "type") => None,
+            _ => Some(value.into()),
+        });
+    builder
+}
+
+/// Whether `style` is no more than the colors and font styles syntax highlighting uses.
+fn is_highlighting_style(style: &str) -> bool {
+    style
+        .split(';')
+        .map(str::trim)
+        .filter(|declaration| !declaration.is_empty())
+        .all(|declaration| match declaration.split_once(':').map(|(property, value)| (property.trim(), value.trim())) {
+            Some(("color" | "background-color", value)) => {
+                value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+            }
+            Some(("font-weight", "bold")) | Some(("font-style", "italic")) | Some(("text-decoration", "underline")) => true,
+            _ => false,
+        })
+}
+
+fn plain_text(events: &[Event]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A heading's anchor: its words, lowercased and joined with dashes.
+fn slugify(text: &str) -> String {
+    let slug = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() { "section".to_string() } else { slug }
+}
+
+/// `slug`, numbered when an earlier heading already has it.
+fn unique_id(slug: &str, ids: &mut HashSet<String>) -> String {
+    let mut id = slug.to_string();
+    let mut n = 1;
+    while !ids.insert(id.clone()) {
+        id = format!("{slug}-{n}");
+        n += 1;
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(markdown: &str) -> RenderedMarkdown {
+        MarkdownRenderer::new().render(markdown)
+    }
+
+    #[test]
+    fn strips_scripts() {
+        let html = render("Hi <script>alert(1)</script>\n\n<img src=x onerror=\"alert(1)\">").html;
+
+        assert!(!html.contains("<script"), "{html}");
+        assert!(!html.contains("alert"), "{html}");
+        assert!(!html.contains("onerror"), "{html}");
+    }
+
+    #[test]
+    fn strips_javascript_urls() {
+        let html = render("[link](javascript:alert(1)) <a href=\"JavaScript:alert(1)\">raw</a>").html;
+
+        assert!(!html.to_lowercase().contains("javascript:"), "{html}");
+    }
+
+    #[test]
+    fn keeps_only_highlighting_styles() {
+        let html = render("<span style=\"color: #ff0000; font-weight: bold\">ok</span> <span style=\"position: fixed\">no</span>").html;
+
+        assert!(html.contains("style=\"color: #ff0000; font-weight: bold\""), "{html}");
+        assert!(!html.contains("position"), "{html}");
+    }
+
+    #[test]
+    fn highlights_code_with_inline_styles() {
+        let html = render("```rust\nfn main() {}\n```").html;
+
+        assert!(html.contains("<pre style=\""), "{html}");
+        assert!(html.contains("<span style=\"color:#"), "{html}");
+    }
+
+    #[test]
+    fn escapes_code_in_unknown_languages() {
+        let html = render("```nonsense\n<b>&</b>\n```").html;
+
+        assert!(html.contains("<pre><code>&lt;b&gt;&amp;&lt;/b&gt;\n</code></pre>"), "{html}");
+    }
+
+    #[test]
+    fn prefixes_author_ids() {
+        let html = render("<div id=\"cookie\">x</div>").html;
+
+        assert!(html.contains("<div id=\"user-content-cookie\">"), "{html}");
+    }
+
+    #[test]
+    fn gives_headings_and_toc_the_same_prefixed_ids() {
+        let rendered = render("# Hello, world\n\n## Hello, world\n\n### Custom {#mine}");
+
+        let ids: Vec<&str> = rendered.toc.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, ["user-content-hello-world", "user-content-hello-world-1", "user-content-mine"]);
+        for id in ids {
+            assert!(rendered.html.contains(&format!(" id=\"{id}\"")), "{id} in {}", rendered.html);
+            assert!(rendered.html.contains(&format!("href=\"#{id}\"")), "{id} in {}", rendered.html);
+        }
+    }
+
+    #[test]
+    fn points_in_page_links_at_prefixed_ids() {
+        let html = render("Text[^note] and [top](#hello)\n\n[^note]: A note").html;
+
+        assert!(html.contains("href=\"#user-content-note\""), "{html}");
+        assert!(html.contains("id=\"user-content-note\""), "{html}");
+        assert!(html.contains("href=\"#user-content-hello\""), "{html}");
+        assert!(!html.contains("user-content-user-content"), "{html}");
+    }
+}
@@ -0,0 +1,181 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::io::{Limits, Reader};
+use image::{DynamicImage, ImageError, ImageFormat, ImageOutputFormat};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::storage::CONTENT_TYPES;
+use crate::validation::ValidationErrors;
+
+/// Uploads wider or taller than this are refused rather than decoded.
+const MAX_DIMENSION: u32 = 12_000;
+const JPEG_QUALITY: u8 = 85;
+
+/// The sizes every uploaded image is stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// The image itself, scaled down if it is bigger than anyone needs.
+    Large,
+    /// For embedding in posts.
+    Medium,
+    /// A square crop, for avatars and lists.
+    Thumbnail,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 3] = [Variant::Large, Variant::Medium, Variant::Thumbnail];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Large => "large",
+            Variant::Medium => "medium",
+            Variant::Thumbnail => "thumbnail",
+        }
+    }
+
+    fn resize(self, image: &DynamicImage) -> DynamicImage {
+        let fit = |max: u32| {
+            if image.width() > max || image.height() > max {
+                image.resize(max, max, FilterType::CatmullRom)
+            } else {
+                image.clone()
+            }
+        };
+
+        match self {
+            Variant::Large => fit(2048),
+            Variant::Medium => fit(800),
+            Variant::Thumbnail => image.resize_to_fill(160, 160, FilterType::CatmullRom),
+        }
+    }
+}
+
+/// Where a variant of an upload is stored.
+pub fn key(media_id: Uuid, variant: Variant, content_type: &str) -> String {
+    let extension = CONTENT_TYPES
+        .iter()
+        .find(|(_, known)| *known == content_type)
+        .map(|(extension, _)| *extension)
+        .unwrap_or("bin");
+
+    format!("{media_id}/{}.{extension}", variant.name())
+}
+
+/// An upload after [`process`]: re-encoded, so nothing the camera or editor embedded survives.
+pub struct ProcessedImage {
+    pub content_type: &'static str,
+    /// Of the large variant.
+    pub width: u32,
+    pub height: u32,
+    pub variants: Vec<(Variant, Vec<u8>)>,
+}
+
+impl ProcessedImage {
+    pub fn size(&self) -> usize {
+        self.variants.iter().map(|(_, bytes)| bytes.len()).sum()
+    }
+}
+
+/// Turns an uploaded file into the variants to store. The type is sniffed from the contents rather
+/// than trusted from the request, the EXIF orientation is applied to the pixels, and each variant
+/// is encoded afresh, which drops EXIF and other metadata such as GPS coordinates. Photos stay
+/// JPEGs; everything else becomes a PNG, keeping only the first frame of an animation.
+///
+/// This is CPU-bound, so run it off the async runtime.
+pub fn process(bytes: &[u8]) -> Result<ProcessedImage, AppError> {
+    let format = sniff(bytes)?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    let mut reader = Reader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+    let image = reader.decode().map_err(|err| match err {
+        ImageError::Limits(_) => invalid_file("is too large an image"),
+        _ => invalid_file("could not be read as an image"),
+    })?;
+    let image = orient(image, orientation(bytes));
+
+    let (content_type, output_format) = match format {
+        ImageFormat::Jpeg => ("image/jpeg", ImageFormat::Jpeg),
+        _ => ("image/png", ImageFormat::Png),
+    };
+
+    let mut width = image.width();
+    let mut height = image.height();
+    let variants = Variant::ALL
+        .into_iter()
+        .map(|variant| {
+            let resized = variant.resize(&image);
+            if variant == Variant::Large {
+                width = resized.width();
+                height = resized.height();
+            }
+            encode(&resized, output_format).map(|bytes| (variant, bytes))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ProcessedImage {
+        content_type,
+        width,
+        height,
+        variants,
+    })
+}
+
+fn sniff(bytes: &[u8]) -> Result<ImageFormat, AppError> {
+    match infer::get(bytes).map(|kind| kind.mime_type()) {
+        Some("image/jpeg") => Ok(ImageFormat::Jpeg),
+        Some("image/png") => Ok(ImageFormat::Png),
+        Some("image/gif") => Ok(ImageFormat::Gif),
+        Some("image/webp") => Ok(ImageFormat::WebP),
+        _ => Err(invalid_file("must be a JPEG, PNG, GIF or WebP image")),
+    }
+}
+
+/// The EXIF orientation tag, 1 (upright) when there is none.
+fn orientation(bytes: &[u8]) -> u32 {
+    exif::Reader::new()
+        .read_from_container(&mut Cursor::new(bytes))
+        .ok()
+        .and_then(|exif| {
+            exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+        })
+        .unwrap_or(1)
+}
+
+fn orient(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, AppError> {
+    let mut bytes = Vec::new();
+    let encoded = match format {
+        ImageFormat::Jpeg => JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)
+            .encode_image(&DynamicImage::ImageRgb8(image.to_rgb8())),
+        _ => image.write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png),
+    };
+    encoded.map_err(|err| AppError::Internal(format!("Failed to encode image: {err}")))?;
+
+    Ok(bytes)
+}
+
+/// A problem with the uploaded file, reported on the `file` field.
+pub fn invalid_file(message: &str) -> AppError {
+    let mut errors = ValidationErrors::default();
+    errors.add("file", message);
+    errors.into()
+}
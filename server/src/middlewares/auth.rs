@@ -2,20 +2,20 @@ use std::sync::Arc;
 
 use axum::{
     extract::State,
-    http::{header, Request, StatusCode},
-    Json,
+    http::{header, Request},
     middleware::Next,
     response::IntoResponse,
 };
 use axum_extra::extract::cookie::CookieJar;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::config::AppState;
+use crate::error::AppError;
+use crate::session;
 use crate::middlewares::token;
-use crate::models::user::User;
+use crate::models::user::{User, UserRole};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthUserClaims {
@@ -34,7 +34,7 @@ pub async fn auth<B>(
     State(data): State<Arc<AppState>>,
     mut req: Request<B>,
     next: Next<B>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+) -> Result<impl IntoResponse, AppError> {
     let get_token = cookie_jar
         .get("access_token")
         .map(|cookie| cookie.value().to_string())
@@ -42,13 +42,8 @@ pub async fn auth<B>(
             req.headers()
                 .get(header::AUTHORIZATION)
                 .and_then(|auth_header| auth_header.to_str().ok())
-                .and_then(|auth_value| {
-                    if auth_value.starts_with("Bearer ") {
-                        Some(auth_value[7..].to_owned())
-                    } else {
-                        None
-                    }
-                })
+                .and_then(|auth_value| auth_value.strip_prefix("Bearer "))
+                .map(|token| token.to_owned())
         });
 
     if get_token.is_none() {
@@ -62,93 +57,37 @@ pub async fn auth<B>(
     let access_token = get_token.unwrap();
 
     let access_token_data =
-        match token::verify_token(data.env.access_token_public_key.to_owned(), &access_token) {
-            Ok(data) => data,
-            Err(err) => {
-                return Err((
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({
-                        "status": "fail",
-                        "message": format!("Invalid access token: {err}"),
-                    })),
-                ))
-            }
-        };
+        token::verify_token(data.env.access_token_public_key.to_owned(), &access_token)
+            .map_err(|err| AppError::Unauthorized(format!("Invalid access token: {err}")))?;
 
-    let access_token_uuid =
-        Uuid::parse_str(&access_token_data.token_uuid.to_string()).map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "status": "fail",
-                    "message": "Invalid access token",
-                })),
-            )
-        })?;
+    let access_token_uuid = access_token_data.token_uuid;
 
-    let mut redis_client = data
-        .redis_client
-        .get_async_connection()
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Error connecting to redis: {err}"),
-                })),
-            )
-        })?;
+    let mut redis_client = data.redis_client.get_async_connection().await?;
 
     let access_token_in_redis = redis_client
-        .get::<_, String>(access_token_uuid.clone().to_string())
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "status": "fail",
-                    "message": format!("Error fetching token from redis: {err}"),
-                })),
-            )
-        })?;
+        .get::<_, Option<String>>(access_token_uuid.to_string())
+        .await?;
 
-    if access_token_in_redis != access_token {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "status": "fail",
-                "message": "Invalid access token",
-            })),
-        ));
+    if access_token_in_redis.as_deref() != Some(access_token.as_str()) {
+        return Err(AppError::Unauthorized("Invalid access token".to_string()));
+    }
+
+    if let Err(err) = session::touch(&mut redis_client, access_token_uuid).await {
+        log::warn!("Failed to update session last-seen time: {err}");
     }
 
     let user = sqlx::query_as!(
         User,
-        "SELECT * FROM users WHERE id = $1",
+        r#"
+            SELECT id, email, display_name, password, biography, profile_image_url, role AS "role: UserRole", email_verified_at, created_at, updated_at
+            FROM users
+            WHERE id = $1
+        "#,
         access_token_data.user_id
     )
     .fetch_optional(&data.db)
-    .await
-    .map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "status": "fail",
-                "message": format!("Error fetching user from database: {err}"),
-            })),
-        )
-    })?;
-
-    let user = user.ok_or_else(|| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "status": "fail",
-                "message": "User not found",
-            })),
-        )
-    })?;
+    .await?
+    .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
 
     req.extensions_mut().insert(AuthUserClaims {
         user: Some(user),
@@ -0,0 +1,46 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+
+use crate::error::AppError;
+use crate::middlewares::auth::AuthUserClaims;
+use crate::models::user::{User, UserRole};
+
+/// A signed-in user who is at least a moderator. Like the other auth-aware handlers, routes using
+/// it need the [`auth`](crate::middlewares::auth::auth) layer.
+pub struct Moderator(pub User);
+
+/// A signed-in admin; see [`Moderator`].
+pub struct Admin(pub User);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Moderator {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        require_role(parts, UserRole::Moderator).map(Moderator)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Admin {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        require_role(parts, UserRole::Admin).map(Admin)
+    }
+}
+
+fn require_role(parts: &Parts, role: UserRole) -> Result<User, AppError> {
+    let user = parts
+        .extensions
+        .get::<AuthUserClaims>()
+        .and_then(|claims| claims.user.clone())
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?;
+
+    if user.role < role {
+        return Err(AppError::Forbidden("You are not allowed to do this".to_string()));
+    }
+
+    Ok(user)
+}
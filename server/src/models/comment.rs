@@ -15,9 +15,14 @@ pub struct Comment {
 
 pub struct CommentFromQuery {
     pub id: uuid::Uuid,
+    pub parent_id: Option<uuid::Uuid>,
     pub body: String,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub hidden_at: Option<DateTime<Utc>>,
+    pub reply_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
     pub author_display_name: String,
     pub author_biography: Option<String>,
     pub author_profile_image_url: Option<String>,
@@ -26,17 +31,30 @@ pub struct CommentFromQuery {
 
 impl CommentFromQuery {
     pub fn into_comment_dto(self) -> CommentDto {
+        let deleted = self.deleted_at.is_some();
+        let hidden = self.hidden_at.is_some();
+        let removed = deleted || hidden;
+
         CommentDto {
             id: self.id,
-            body: self.body,
+            parent_id: self.parent_id,
+            body: if removed { String::new() } else { self.body },
             created_at: self.created_at,
             updated_at: self.updated_at,
-            author: ProfileDto {
+            edited_at: self.edited_at,
+            author: (!removed).then_some(ProfileDto {
                 display_name: self.author_display_name,
                 biography: self.author_biography,
                 profile_image_url: self.author_profile_image_url,
                 following: self.following_author,
-            },
+                blocking: false,
+                muting: false,
+            }),
+            deleted,
+            hidden,
+            reply_count: self.reply_count,
+            replies: Vec::new(),
+            replies_cursor: None,
         }
     }
 }
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An uploaded image, stored in every [`crate::media::Variant`].
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Media {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size: i64,
+    pub original_filename: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
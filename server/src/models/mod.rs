@@ -1,3 +1,6 @@
 pub mod comment;
+pub mod media;
+pub mod notification;
 pub mod post;
+pub mod report;
 pub mod user;
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// What happened to make a notification; each kind can be turned off in the recipient's
+/// preferences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "notification_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Follow,
+    Favorite,
+    Comment,
+}
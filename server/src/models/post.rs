@@ -3,6 +3,19 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use crate::dtos::post::PostDto;
 use crate::dtos::profile::ProfileDto;
+use crate::dtos::search::SearchResultDto;
+use crate::markdown::MarkdownRenderer;
+
+/// Where a post is in its lifecycle; only `Published` posts are shown to anyone but their author.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "post_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Scheduled,
+    Published,
+    Archived,
+}
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Post {
@@ -13,6 +26,9 @@ pub struct Post {
     pub description: String,
     pub body: String,
     pub tags: Vec<String>,
+    pub status: PostStatus,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -26,6 +42,9 @@ pub struct PostFromQuery {
     pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub status: PostStatus,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub published_at: Option<DateTime<Utc>>,
     pub favorited: bool,
     pub favorites_count: i64,
     pub author_display_name: String,
@@ -35,16 +54,37 @@ pub struct PostFromQuery {
 }
 
 impl PostFromQuery {
-    pub fn into_post_dto(self) -> PostDto {
+    /// Where lists place the post: when it went out, or when it was written while it hasn't.
+    pub fn listed_at(&self) -> DateTime<Utc> {
+        self.published_at.unwrap_or(self.created_at)
+    }
+
+    pub fn into_post_dto(self, markdown: &MarkdownRenderer) -> PostDto {
+        let rendered = markdown.render_post(self.id, self.updated_at, &self.body);
+        PostDto {
+            body_html: Some(rendered.html.clone()),
+            toc: Some(rendered.toc.clone()),
+            ..self.into_listed_post_dto()
+        }
+    }
+
+    /// The post as lists show it, without the rendered body, which only the post's own page
+    /// needs and would otherwise be highlighted for every post on the page.
+    pub fn into_listed_post_dto(self) -> PostDto {
         PostDto {
             id: self.id,
             slug: self.slug,
             title: self.title,
             description: self.description,
             body: self.body,
+            body_html: None,
+            toc: None,
             tags: self.tags,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            status: self.status,
+            publish_at: self.publish_at,
+            published_at: self.published_at,
             favorited: self.favorited,
             favorites_count: self.favorites_count,
             author: ProfileDto {
@@ -52,7 +92,63 @@ impl PostFromQuery {
                 biography: self.author_biography,
                 profile_image_url: self.author_profile_image_url,
                 following: self.following_author,
+                blocking: false,
+                muting: false,
+            }
+        }
+    }
+}
+
+pub struct SearchResultFromQuery {
+    pub id: uuid::Uuid,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub status: PostStatus,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub favorited: bool,
+    pub favorites_count: i64,
+    pub author_display_name: String,
+    pub author_biography: Option<String>,
+    pub author_profile_image_url: Option<String>,
+    pub following_author: bool,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+    pub total: i64,
+}
+
+impl SearchResultFromQuery {
+    pub fn into_search_result_dto(self, highlight: impl Fn(&str) -> String) -> SearchResultDto {
+        SearchResultDto {
+            rank: self.rank,
+            title_highlight: highlight(&self.title_highlight),
+            snippet: highlight(&self.snippet),
+            post: PostFromQuery {
+                id: self.id,
+                slug: self.slug,
+                title: self.title,
+                description: self.description,
+                body: self.body,
+                tags: self.tags,
+                created_at: self.created_at,
+                updated_at: self.updated_at,
+                status: self.status,
+                publish_at: self.publish_at,
+                published_at: self.published_at,
+                favorited: self.favorited,
+                favorites_count: self.favorites_count,
+                author_display_name: self.author_display_name,
+                author_biography: self.author_biography,
+                author_profile_image_url: self.author_profile_image_url,
+                following_author: self.following_author,
             }
+            .into_listed_post_dto(),
         }
     }
 }
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Why a reader flagged a post or comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "report_category", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReportCategory {
+    Spam,
+    Harassment,
+    Hate,
+    Violence,
+    Misinformation,
+    Other,
+}
+
+/// What a report is about; a report has exactly one of `post_id` and `comment_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTarget {
+    Post,
+    Comment,
+}
@@ -0,0 +1,94 @@
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::events::{publish, user_channel, LiveEvent};
+use crate::models::notification::NotificationKind;
+
+/// Tells `recipient_id` that `actor_id` did something. Nothing is stored when the recipient is the
+/// actor, has turned the kind off, has muted or blocked the actor, or still has the same
+/// notification unread. Returns the recipient if a notification was stored.
+pub async fn notify(
+    executor: impl sqlx::PgExecutor<'_>,
+    recipient_id: Uuid,
+    actor_id: Uuid,
+    kind: NotificationKind,
+    post_id: Option<Uuid>,
+    comment_id: Option<Uuid>,
+) -> Result<Option<Uuid>, AppError> {
+    let recipient_id = sqlx::query_scalar!(
+        r#"
+            INSERT INTO notifications (recipient_id, actor_id, kind, post_id, comment_id)
+            SELECT recipient.id, $2, $3, $4, $5
+            FROM users AS recipient
+            LEFT JOIN notification_preferences AS preferences ON preferences.user_id = recipient.id
+            WHERE recipient.id = $1
+                AND recipient.id <> $2
+                AND CASE $3::notification_kind
+                    WHEN 'follow' THEN COALESCE(preferences.follow, TRUE)
+                    WHEN 'favorite' THEN COALESCE(preferences.favorite, TRUE)
+                    ELSE COALESCE(preferences.comment, TRUE)
+                END
+                AND NOT EXISTS (SELECT 1 FROM user_mutes WHERE muter_user_id = recipient.id AND muted_user_id = $2)
+                AND NOT EXISTS (SELECT 1 FROM user_blocks WHERE blocker_user_id = recipient.id AND blocked_user_id = $2)
+                AND NOT EXISTS (
+                    SELECT 1 FROM notifications
+                    WHERE recipient_id = recipient.id
+                        AND actor_id = $2
+                        AND kind = $3
+                        AND post_id IS NOT DISTINCT FROM $4
+                        AND comment_id IS NOT DISTINCT FROM $5
+                        AND read_at IS NULL
+                )
+            RETURNING recipient_id
+        "#,
+        recipient_id,
+        actor_id,
+        kind as NotificationKind,
+        post_id,
+        comment_id,
+    )
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(recipient_id)
+}
+
+/// [`notify`]s the author of `post_id`, returning them if a notification was stored.
+pub async fn notify_post_author(
+    executor: impl sqlx::PgExecutor<'_> + Copy,
+    actor_id: Uuid,
+    kind: NotificationKind,
+    post_id: Uuid,
+    comment_id: Option<Uuid>,
+) -> Result<Option<Uuid>, AppError> {
+    let author_id = sqlx::query_scalar!("SELECT user_id FROM posts WHERE id = $1", post_id)
+        .fetch_optional(executor)
+        .await?;
+
+    match author_id {
+        Some(author_id) => notify(executor, author_id, actor_id, kind, Some(post_id), comment_id).await,
+        None => Ok(None),
+    }
+}
+
+pub async fn unread_count(executor: impl sqlx::PgExecutor<'_>, user_id: Uuid) -> Result<i64, AppError> {
+    let unread_count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) "count!" FROM notifications WHERE recipient_id = $1 AND read_at IS NULL"#,
+        user_id,
+    )
+        .fetch_one(executor)
+        .await?;
+
+    Ok(unread_count)
+}
+
+/// Pushes `user_id`'s unread count to their open notification streams.
+pub async fn announce(data: &AppState, user_id: Option<Uuid>) -> Result<(), AppError> {
+    if let Some(user_id) = user_id {
+        let unread_count = unread_count(&data.db, user_id).await?;
+        publish(data, &user_channel(user_id), &LiveEvent::Notifications { unread_count }).await;
+    }
+
+    Ok(())
+}
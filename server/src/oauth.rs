@@ -0,0 +1,185 @@
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use oauth2::basic::BasicClient;
+use oauth2::http::{header, HeaderMap, HeaderValue, Method};
+use oauth2::reqwest::async_http_client;
+use oauth2::url::Url;
+use oauth2::{
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, HttpRequest, PkceCodeChallenge,
+    PkceCodeVerifier, RedirectUrl, Scope, TokenResponse, TokenUrl,
+};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::{AppState, Config, OAuthProviderConfig};
+use crate::error::AppError;
+
+/// How long a user has to come back from the provider, in seconds.
+const AUTHORIZATION_TTL: usize = 600;
+/// Holds the nonce that ties an authorization to the browser that started it.
+pub const NONCE_COOKIE: &str = "oauth_nonce";
+
+/// What the provider's UserInfo endpoint says about the user.
+#[derive(Debug, Deserialize)]
+pub struct UserInfo {
+    /// The user's id at the provider; never reused, unlike the email address.
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+}
+
+/// Kept in Redis between sending the user to the provider and their return, keyed by the `state`
+/// parameter so a callback can't be replayed or forged.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingAuthorization {
+    pub provider: String,
+    pub pkce_verifier: String,
+    /// Also set in the [`NONCE_COOKIE`] of the browser that started the authorization, so that a
+    /// callback link made by someone else can't sign that browser into their account.
+    pub nonce: String,
+    /// Set when a signed-in user is linking the provider rather than signing in with it.
+    pub link_user_id: Option<Uuid>,
+}
+
+fn pending_authorization_key(state: &str) -> String {
+    format!("oauth_state:{state}")
+}
+
+pub fn find_provider<'a>(config: &'a Config, name: &str) -> Result<&'a OAuthProviderConfig, AppError> {
+    config
+        .oauth_providers
+        .iter()
+        .find(|provider| provider.name == name)
+        .ok_or_else(|| AppError::NotFound("Unknown sign-in provider".to_string()))
+}
+
+/// The URL to send the user to, with a fresh PKCE challenge and `state`, along with the nonce
+/// cookie to set in their browser.
+pub async fn start_authorization(
+    data: &AppState,
+    provider: &OAuthProviderConfig,
+    link_user_id: Option<Uuid>,
+) -> Result<(String, Cookie<'static>), AppError> {
+    let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+    let (authorization_url, state) = client(&data.env, provider)?
+        .authorize_url(CsrfToken::new_random)
+        .add_scopes(provider.scopes.iter().cloned().map(Scope::new))
+        .set_pkce_challenge(pkce_challenge)
+        .url();
+
+    let nonce = CsrfToken::new_random().secret().clone();
+    let pending = PendingAuthorization {
+        provider: provider.name.clone(),
+        pkce_verifier: pkce_verifier.secret().clone(),
+        nonce: nonce.clone(),
+        link_user_id,
+    };
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    redis_client
+        .set_ex::<_, _, ()>(
+            pending_authorization_key(state.secret()),
+            serde_json::to_string(&pending).expect("pending authorizations always serialize"),
+            AUTHORIZATION_TTL,
+        )
+        .await?;
+
+    let cookie = Cookie::build(NONCE_COOKIE, nonce)
+        .path("/")
+        .max_age(time::Duration::seconds(AUTHORIZATION_TTL as i64))
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .finish();
+
+    Ok((authorization_url.to_string(), cookie))
+}
+
+/// Unsets the nonce cookie once its authorization is finished.
+pub fn clear_nonce_cookie() -> Cookie<'static> {
+    Cookie::build(NONCE_COOKIE, "")
+        .path("/")
+        .max_age(time::Duration::ZERO)
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .finish()
+}
+
+/// Exchanges the code the provider sent the user back with and looks up who they are. Each `state`
+/// works once, and only in the browser it was issued to, which sends back its `nonce`.
+pub async fn finish_authorization(
+    data: &AppState,
+    provider: &OAuthProviderConfig,
+    code: String,
+    state: &str,
+    nonce: Option<&str>,
+) -> Result<(PendingAuthorization, UserInfo), AppError> {
+    let failed = || AppError::Unauthorized(format!("Signing in with {} failed", provider.display_name));
+
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    let pending: PendingAuthorization = redis_client
+        .get_del::<_, Option<String>>(pending_authorization_key(state))
+        .await?
+        .and_then(|pending| serde_json::from_str(&pending).ok())
+        .filter(|pending: &PendingAuthorization| pending.provider == provider.name)
+        .filter(|pending| nonce == Some(pending.nonce.as_str()))
+        .ok_or_else(failed)?;
+
+    let token = client(&data.env, provider)?
+        .exchange_code(AuthorizationCode::new(code))
+        .set_pkce_verifier(PkceCodeVerifier::new(pending.pkce_verifier.clone()))
+        .request_async(async_http_client)
+        .await
+        .map_err(|err| {
+            log::warn!("Token exchange with {} failed: {err}", provider.name);
+            failed()
+        })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", token.access_token().secret())).map_err(|_| failed())?,
+    );
+    let response = async_http_client(HttpRequest {
+        url: parse_url(&provider.userinfo_url)?,
+        method: Method::GET,
+        headers,
+        body: Vec::new(),
+    })
+        .await
+        .map_err(|err| {
+            log::warn!("UserInfo request to {} failed: {err}", provider.name);
+            failed()
+        })?;
+
+    if !response.status_code.is_success() {
+        log::warn!("UserInfo request to {} returned {}", provider.name, response.status_code);
+        return Err(failed());
+    }
+
+    let user_info = serde_json::from_slice::<UserInfo>(&response.body).map_err(|err| {
+        log::warn!("Unexpected UserInfo response from {}: {err}", provider.name);
+        failed()
+    })?;
+
+    Ok((pending, user_info))
+}
+
+fn client(config: &Config, provider: &OAuthProviderConfig) -> Result<BasicClient, AppError> {
+    let redirect_url = format!("{}/users/oauth/{}/callback", config.app_url, provider.name);
+
+    Ok(BasicClient::new(
+        ClientId::new(provider.client_id.clone()),
+        provider.client_secret.clone().map(ClientSecret::new),
+        AuthUrl::from_url(parse_url(&provider.auth_url)?),
+        Some(TokenUrl::from_url(parse_url(&provider.token_url)?)),
+    )
+        .set_redirect_uri(RedirectUrl::from_url(parse_url(&redirect_url)?)))
+}
+
+fn parse_url(url: &str) -> Result<Url, AppError> {
+    Url::parse(url).map_err(|err| AppError::Internal(format!("Invalid OAuth URL {url}: {err}")))
+}
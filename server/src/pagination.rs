@@ -0,0 +1,150 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::validation::ValidationErrors;
+
+pub const DEFAULT_LIMIT: i64 = 10;
+pub const MAX_LIMIT: i64 = 100;
+
+/// Which side of the cursor a page is read from: `After` continues the list past the cursor and
+/// `Before` walks back toward its start, whichever way the list itself is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    After,
+    Before,
+}
+
+/// Position in a list ordered by a timestamp such as `created_at`, then `id`, handed to clients as
+/// an opaque token.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor {
+    pub direction: Direction,
+    pub timestamp: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let direction = match self.direction {
+            Direction::After => 'a',
+            Direction::Before => 'b',
+        };
+        let raw = format!("{direction}:{}:{}", self.timestamp.timestamp_micros(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Result<Cursor, AppError> {
+        let invalid = || {
+            let mut errors = ValidationErrors::default();
+            errors.add("cursor", "is not a valid cursor");
+            AppError::from(errors)
+        };
+
+        let raw = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let mut parts = raw.splitn(3, ':');
+
+        let direction = match parts.next() {
+            Some("a") => Direction::After,
+            Some("b") => Direction::Before,
+            _ => return Err(invalid()),
+        };
+        let timestamp = parts
+            .next()
+            .and_then(|micros| micros.parse::<i64>().ok())
+            .and_then(|micros| Utc.timestamp_micros(micros).single())
+            .ok_or_else(invalid)?;
+        let id = parts
+            .next()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(invalid)?;
+
+        Ok(Cursor {
+            direction,
+            timestamp,
+            id,
+        })
+    }
+}
+
+/// A decoded page request: rows are fetched one past `limit` so `finish` can tell whether more
+/// exist in the direction being read.
+pub struct PageRequest {
+    pub cursor: Option<Cursor>,
+    pub limit: i64,
+}
+
+impl PageRequest {
+    pub fn new(cursor: Option<&str>, limit: Option<i64>) -> Result<PageRequest, AppError> {
+        Ok(PageRequest {
+            cursor: cursor.map(Cursor::decode).transpose()?,
+            limit: limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        })
+    }
+
+    pub fn is_backward(&self) -> bool {
+        matches!(self.cursor, Some(Cursor { direction: Direction::Before, .. }))
+    }
+
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.cursor.map(|cursor| cursor.timestamp)
+    }
+
+    pub fn id(&self) -> Option<Uuid> {
+        self.cursor.map(|cursor| cursor.id)
+    }
+
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+
+    /// Trims the extra row, restores list order and works out the neighbouring cursors.
+    pub fn finish<T>(
+        &self,
+        mut rows: Vec<T>,
+        key: impl Fn(&T) -> (DateTime<Utc>, Uuid),
+    ) -> Page<T> {
+        let has_more = rows.len() as i64 > self.limit;
+        rows.truncate(self.limit as usize);
+        if self.is_backward() {
+            rows.reverse();
+        }
+
+        let (has_previous, has_next) = match self.cursor {
+            None => (false, has_more),
+            Some(Cursor { direction: Direction::After, .. }) => (true, has_more),
+            Some(Cursor { direction: Direction::Before, .. }) => (has_more, true),
+        };
+
+        let cursor = |row: &T, direction: Direction| {
+            let (timestamp, id) = key(row);
+            Cursor {
+                direction,
+                timestamp,
+                id,
+            }
+            .encode()
+        };
+
+        Page {
+            prev_cursor: rows
+                .first()
+                .filter(|_| has_previous)
+                .map(|row| cursor(row, Direction::Before)),
+            next_cursor: rows
+                .last()
+                .filter(|_| has_next)
+                .map(|row| cursor(row, Direction::After)),
+            rows,
+        }
+    }
+}
+
+pub struct Page<T> {
+    pub rows: Vec<T>,
+    pub prev_cursor: Option<String>,
+    pub next_cursor: Option<String>,
+}
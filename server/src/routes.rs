@@ -3,137 +3,460 @@ use std::sync::Arc;
 
 use axum::{http::StatusCode, middleware, Router, routing::get, routing::post};
 use axum::body::{Body, boxed};
-use axum::http::Response;
-use axum::routing::delete;
+use axum::extract::DefaultBodyLimit;
+use axum::http::{header, HeaderValue, Request, Response, Uri};
+use axum::routing::{delete, put};
 use tokio::fs;
 use tower::{ServiceBuilder, ServiceExt};
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
 use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
 
 use crate::config::{AppState, Opt};
-use crate::handlers::comment::{delete_comment, get_comments, new_comment};
+use crate::handlers::account::{forgot_password, resend_verification_email, reset_password, verify_email};
+use crate::handlers::comment::{delete_comment, get_comment_revisions, get_comments, get_replies, new_comment, reply_comment, update_comment};
+use crate::handlers::event::{notification_events, post_events, stream_ticket};
+use crate::handlers::feed::{all_feed, author_feed, tag_feed};
 use crate::handlers::health_check;
-use crate::handlers::post::{delete_post, favorite_post, feed_list, get_post, new_post, post_list, unfavorite_post, update_post};
-use crate::handlers::profile::{follow_user, get_user_profile, unfollow_user};
+use crate::handlers::media::{delete_media, get_media, upload_media};
+use crate::handlers::moderation;
+use crate::handlers::notification::{get_notifications, get_preferences, get_unread_count, mark_all_read, mark_read, update_preferences};
+use crate::handlers::oauth::{authorize, callback, get_identities, get_providers, unlink_identity};
+use crate::handlers::post::{delete_post, favorite_post, feed_list, get_post, get_post_by_slug, new_post, post_list, unfavorite_post, update_post};
+use crate::handlers::post_revision::{diff_post_revisions, get_post_revision, get_post_revisions, restore_post_revision};
+use crate::handlers::report::{dismiss_reports, get_report_queue, report_comment, report_post, resolve_reports};
+use crate::handlers::profile::{block_user, follow_user, get_blocked_users, get_muted_users, get_user_profile, mute_user, unblock_user, unfollow_user, unmute_user};
+use crate::handlers::search::search;
+use crate::handlers::seo::{robots, sitemap};
 use crate::handlers::tag::get_tags;
-use crate::handlers::user::{login, me, sign_up, update_me};
+use crate::handlers::two_factor::{confirm as confirm_two_factor, disable as disable_two_factor, enroll as enroll_two_factor, get_status as get_two_factor_status, login_two_factor, regenerate_recovery_codes};
+use crate::handlers::user::{delete_session, login, logout, logout_all, me, refresh, sessions, sign_up, update_me};
 use crate::middlewares::auth::auth;
+use crate::seo;
+use crate::ssr;
+
+/// Room for the rest of a multipart upload besides the file itself.
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
 
 pub fn create_router(app_state: Arc<AppState>, opt: Opt) -> Router {
+    let api = api_router(&app_state);
+    // For the fallback, which serves `index.html` with the page rendered into it from what the API
+    // answers for it, and with meta tags looked up for it.
+    let page_api = api.clone().with_state(app_state.clone());
+    let index_state = app_state.clone();
+
     Router::new()
+        .nest("/api", api)
+        .nest(
+            "/feeds",
+            Router::new()
+                // Each path parameter is a file name such as `rust.atom`, naming the format too.
+                .route("/:file_name",
+                       get(all_feed)
+                )
+                .route("/tag/:file_name",
+                       get(tag_feed)
+                )
+                .route("/author/:file_name",
+                       get(author_feed)
+                )
+        )
+        .route("/sitemap.xml", get(sitemap))
+        .route("/robots.txt", get(robots))
+        // Uploads kept by the local storage backend; empty with any other.
+        .nest_service("/media", ServeDir::new(&app_state.env.upload_dir))
+        .fallback_service(get(|req: Request<Body>| async move {
+            let uri = req.uri().clone();
+            let path = uri.path().to_string();
+            // `ServeDir` would answer `/` with `index.html` as is, but the home page is rendered too.
+            if path != "/" {
+                match ServeDir::new(&opt.static_dir).oneshot(req).await {
+                    Ok(res) if res.status() == StatusCode::NOT_FOUND => {}
+                    Ok(res) => return res.map(boxed),
+                    Err(err) => {
+                        return Response::builder()
+                            .status(StatusCode::INTERNAL_SERVER_ERROR)
+                            .body(boxed(Body::from(format!("error: {err}"))))
+                            .expect("error response")
+                    }
+                }
+            }
+
+            let index_path = PathBuf::from(&opt.static_dir).join("index.html");
+            let index_content = match fs::read_to_string(index_path).await {
+                Ok(index_content) => index_content,
+                Err(_) => {
+                    return Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .body(boxed(Body::from("index file not found")))
+                        .unwrap()
+                }
+            };
+
+            // Link previews and crawlers don't run the client, so they are
+            // given what it would show about the post or profile up front.
+            let index_content = match seo::page_meta(&index_state, &path).await {
+                Ok(Some(meta)) => seo::inject(&index_content, &meta),
+                Ok(None) => index_content,
+                Err(err) => {
+                    log::warn!("Failed to look up meta tags for {path}: {err:?}");
+                    index_content
+                }
+            };
+            let index_content = ssr::render(page_api, &uri, index_content).await;
+
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+                .body(boxed(Body::from(index_content)))
+                .unwrap()
+        }))
+        .layer(cors_layer(&app_state.env.app_url))
+        .layer(ServiceBuilder::new().layer(TraceLayer::new_for_http()))
+        .with_state(app_state)
+}
+
+/// The client sends its cookies along with API requests, so rather than any origin, only the one it
+/// is served from is allowed to make them and read the responses.
+fn cors_layer(app_url: &str) -> CorsLayer {
+    let origin = app_url
+        .parse::<Uri>()
+        .ok()
+        .and_then(|uri| Some(format!("{}://{}", uri.scheme_str()?, uri.authority()?)))
+        .and_then(|origin| HeaderValue::from_str(&origin).ok())
+        .expect("APP_URL must be an absolute URL");
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::exact(origin))
+        .allow_methods(AllowMethods::mirror_request())
+        .allow_headers(AllowHeaders::mirror_request())
+        .allow_credentials(true)
+}
+
+/// Everything under `/api`. The fallback also sends the requests a page makes as it first renders
+/// through it, to render the page on the server.
+fn api_router(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/health",
+            get(health_check)
+        )
+        .nest(
+            "/user",
+            Router::new()
+                .route(
+                    "/",
+                    get(me).put(update_me)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/login",
+                    post(login)
+                )
+                .route(
+                    "/login/two_factor",
+                    post(login_two_factor)
+                )
+                .route(
+                    "/sign_up",
+                    post(sign_up)
+                )
+                .route(
+                    "/refresh",
+                    post(refresh)
+                )
+                .route(
+                    "/verify_email",
+                    post(verify_email)
+                )
+                .route(
+                    "/verify_email/resend",
+                    post(resend_verification_email)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/forgot_password",
+                    post(forgot_password)
+                )
+                .route(
+                    "/reset_password",
+                    post(reset_password)
+                )
+                .route(
+                    "/two_factor",
+                    get(get_two_factor_status)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/two_factor/enroll",
+                    post(enroll_two_factor)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/two_factor/confirm",
+                    post(confirm_two_factor)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/two_factor/disable",
+                    post(disable_two_factor)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/two_factor/recovery_codes",
+                    post(regenerate_recovery_codes)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/logout",
+                    post(logout)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/logout_all",
+                    post(logout_all)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/sessions",
+                    get(sessions)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/sessions/:session_id",
+                    delete(delete_session)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/blocks",
+                    get(get_blocked_users)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/mutes",
+                    get(get_muted_users)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+        )
+        .nest(
+            "/oauth",
+            Router::new()
+                .route(
+                    "/providers",
+                    get(get_providers)
+                )
+                .route(
+                    "/identities",
+                    get(get_identities)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/identities/:provider",
+                    delete(unlink_identity)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/:provider/authorize",
+                    post(authorize)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/:provider/callback",
+                    post(callback)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+        )
         .nest(
-            "/api",
+            "/profile",
             Router::new()
                 .route(
-                    "/health",
-                    get(health_check)
+                    "/:display_name",
+                    get(get_user_profile)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/:display_name/follow",
+                    post(follow_user).delete(unfollow_user)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/:display_name/block",
+                    post(block_user).delete(unblock_user)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/:display_name/mute",
+                    post(mute_user).delete(unmute_user)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+        )
+        .nest(
+            "/posts",
+            Router::new()
+                .route(
+                    "/",
+                    get(post_list).post(new_post)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/feed",
+                    get(feed_list)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                )
+                .route(
+                    "/by-slug/:slug",
+                    get(get_post_by_slug)
+                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                 )
                 .nest(
-                    "/user",
+                    "/:id",
                     Router::new()
-                        .route(
-                            "/",
-                            get(me).put(update_me)
-                                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                        .route("/",
+                               get(get_post).put(update_post).delete(delete_post)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                         )
-                        .route(
-                            "/login",
-                            post(login)
+                        .route("/favorite",
+                               post(favorite_post).delete(unfavorite_post)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                         )
-                        .route(
-                            "/sign_up",
-                            post(sign_up)
+                        .route("/report",
+                               post(report_post)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                         )
-                )
-                .nest(
-                    "/profile",
-                    Router::new()
-                        .route(
-                            "/:display_name",
-                            get(get_user_profile)
-                                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                        .route("/events",
+                               get(post_events)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                         )
-                        .route(
-                            "/:display_name/follow",
-                            post(follow_user).delete(unfollow_user)
-                                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                        .route("/revisions",
+                               get(get_post_revisions)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                         )
-                )
-                .nest(
-                    "/posts",
-                    Router::new()
-                        .route(
-                            "/",
-                            get(post_list).post(new_post)
-                                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                        .route("/revisions/diff",
+                               get(diff_post_revisions)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                        )
+                        .route("/revisions/:revision_id",
+                               get(get_post_revision)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                         )
-                        .route(
-                            "/feed",
-                            get(feed_list)
-                                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                        .route("/revisions/:revision_id/restore",
+                               post(restore_post_revision)
+                                   .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                         )
                         .nest(
-                            "/:id",
+                            "/comments",
                             Router::new()
                                 .route("/",
-                                       get(get_post).put(update_post).delete(delete_post)
-                                           .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                                       get(get_comments).post(new_comment))
+                                        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                                .route("/:comment_id",
+                                       put(update_comment).delete(delete_comment)
+                                       .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                                 )
-                                .route("/favorite",
-                                       post(favorite_post).delete(unfavorite_post)
-                                           .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                                .route("/:comment_id/replies",
+                                       get(get_replies).post(reply_comment)
+                                       .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                                 )
-                                .nest(
-                                    "/comments",
-                                    Router::new()
-                                        .route("/",
-                                               get(get_comments).post(new_comment))
-                                                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
-                                        .route("/:comment_id",
-                                               delete(delete_comment)
-                                               .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
-                                        )
+                                .route("/:comment_id/revisions",
+                                       get(get_comment_revisions)
+                                       .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                                )
+                                .route("/:comment_id/report",
+                                       post(report_comment)
+                                       .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
                                 )
                         )
                 )
-                .route(
-                    "/tags",
-                    get(get_tags)
+        )
+        .nest(
+            "/notifications",
+            Router::new()
+                .route("/",
+                       get(get_notifications)
+                )
+                .route("/unread-count",
+                       get(get_unread_count)
+                )
+                .route("/read",
+                       post(mark_all_read)
+                )
+                .route("/preferences",
+                       get(get_preferences).put(update_preferences)
+                )
+                .route("/:notification_id/read",
+                       post(mark_read)
+                )
+                .route("/stream-ticket",
+                       post(stream_ticket)
+                )
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                .route("/events",
+                       get(notification_events)
                 )
         )
-        .fallback_service(get(|req| async move {
-            match ServeDir::new(&opt.static_dir).oneshot(req).await {
-                Ok(res) => {
-                    let status = res.status();
-                    match status {
-                        StatusCode::NOT_FOUND => {
-                            let index_path = PathBuf::from(&opt.static_dir).join("index.html");
-                            let index_content = match fs::read_to_string(index_path).await {
-                                Ok(index_content) => index_content,
-                                Err(_) => {
-                                    return Response::builder()
-                                        .status(StatusCode::NOT_FOUND)
-                                        .body(boxed(Body::from("index file not found")))
-                                        .unwrap()
-                                }
-                            };
-
-                            Response::builder()
-                                .status(StatusCode::OK)
-                                .body(boxed(Body::from(index_content)))
-                                .unwrap()
-                        }
-                        _ => res.map(boxed),
-                    }
-                }
-                Err(err) => Response::builder()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .body(boxed(Body::from(format!("error: {err}"))))
-                    .expect("error response"),
-            }
-        }))
-        .layer(CorsLayer::permissive())
-        .layer(ServiceBuilder::new().layer(TraceLayer::new_for_http()))
-        .with_state(app_state)
+        .nest(
+            "/moderation",
+            Router::new()
+                .route("/posts/:id/hide",
+                       post(moderation::hide_post)
+                )
+                .route("/posts/:id/unhide",
+                       post(moderation::unhide_post)
+                )
+                .route("/posts/:id/delete",
+                       post(moderation::delete_post)
+                )
+                .route("/comments/:comment_id/hide",
+                       post(moderation::hide_comment)
+                )
+                .route("/comments/:comment_id/unhide",
+                       post(moderation::unhide_comment)
+                )
+                .route("/comments/:comment_id/delete",
+                       post(moderation::delete_comment)
+                )
+                .route("/reports",
+                       get(get_report_queue)
+                )
+                .route("/reports/:target/:target_id/resolve",
+                       post(resolve_reports)
+                )
+                .route("/reports/:target/:target_id/dismiss",
+                       post(dismiss_reports)
+                )
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+        )
+        .nest(
+            "/admin",
+            Router::new()
+                .route("/users/:display_name/role",
+                       put(moderation::change_role)
+                )
+                .route("/audit-log",
+                       get(moderation::get_audit_log)
+                )
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+        )
+        .nest(
+            "/media",
+            Router::new()
+                .route("/",
+                       get(get_media).post(upload_media)
+                )
+                .route("/:media_id",
+                       delete(delete_media)
+                )
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+                .layer(DefaultBodyLimit::max(app_state.env.upload_max_size + MULTIPART_OVERHEAD))
+        )
+        .route(
+            "/search",
+            get(search)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+        )
+        .route(
+            "/tags",
+            get(get_tags)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth))
+        )
 }
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use sqlx::{Pool, Postgres};
+
+/// Publishes scheduled posts once their `publish_at` has passed, checking every `interval`.
+///
+/// Runs for the lifetime of the server; failures are logged and retried on the next tick.
+pub async fn publish_scheduled_posts(db: Pool<Postgres>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match publish_due_posts(&db).await {
+            Ok(0) => {}
+            Ok(count) => log::info!("Published {count} scheduled posts"),
+            Err(err) => log::warn!("Failed to publish scheduled posts: {err}"),
+        }
+    }
+}
+
+pub async fn publish_due_posts(db: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+            UPDATE posts
+            SET status = 'published', published_at = COALESCE(published_at, NOW())
+            WHERE status = 'scheduled' AND publish_at <= NOW()
+        "#
+    )
+        .execute(db)
+        .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,231 @@
+use chrono::{DateTime, Utc};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::markdown::escape;
+
+const SITE_NAME: &str = "Negatiview";
+/// Everything but the characters RFC 3986 leaves unreserved.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// What crawlers and link previews are told about a page the client renders.
+pub struct PageMeta {
+    pub title: String,
+    pub description: String,
+    pub canonical_url: String,
+    pub image_url: Option<String>,
+    pub kind: PageKind,
+}
+
+pub enum PageKind {
+    Article {
+        author: String,
+        tags: Vec<String>,
+        published_time: DateTime<Utc>,
+        modified_time: DateTime<Utc>,
+    },
+    Profile {
+        display_name: String,
+    },
+}
+
+/// Looks up the page a client route shows, for the routes worth previewing: `/posts/:slug` and
+/// `/profile/:display_name`. Anything else, or a post or user that can't be seen signed out, has
+/// none.
+pub async fn page_meta(data: &AppState, path: &str) -> Result<Option<PageMeta>, AppError> {
+    let segments: Vec<String> = path
+        .trim_matches('/')
+        .split('/')
+        .map(|segment| percent_decode_str(segment).decode_utf8_lossy().into_owned())
+        .collect();
+
+    match segments.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["posts", slug] => post_meta(data, slug).await,
+        ["profile", display_name] => profile_meta(data, display_name).await,
+        _ => Ok(None),
+    }
+}
+
+async fn post_meta(data: &AppState, slug: &str) -> Result<Option<PageMeta>, AppError> {
+    let post = sqlx::query!(
+        r#"
+            SELECT
+                posts.slug,
+                posts.title,
+                posts.description,
+                posts.tags,
+                posts.created_at,
+                posts.updated_at,
+                posts.published_at,
+                author.display_name,
+                author.profile_image_url
+            FROM posts
+            INNER JOIN users AS author ON author.id = posts.user_id
+            WHERE (
+                posts.slug = $1
+                OR posts.id = (SELECT post_id FROM post_slug_redirects WHERE slug = $1)
+            )
+                AND posts.status = 'published' AND posts.hidden_at IS NULL
+        "#,
+        slug,
+    )
+        .fetch_optional(&data.db)
+        .await?;
+
+    Ok(post.map(|post| PageMeta {
+        title: post.title,
+        description: post.description,
+        // Renamed posts are still found by their old slugs, but point crawlers at the current one.
+        canonical_url: format!("{}/posts/{}", data.env.app_url, encode_segment(&post.slug)),
+        image_url: post.profile_image_url,
+        kind: PageKind::Article {
+            author: post.display_name,
+            tags: post.tags,
+            published_time: post.published_at.unwrap_or(post.created_at),
+            modified_time: post.updated_at,
+        },
+    }))
+}
+
+async fn profile_meta(data: &AppState, display_name: &str) -> Result<Option<PageMeta>, AppError> {
+    let user = sqlx::query!(
+        "SELECT display_name, biography, profile_image_url FROM users WHERE display_name = $1",
+        display_name,
+    )
+        .fetch_optional(&data.db)
+        .await?;
+
+    Ok(user.map(|user| PageMeta {
+        title: user.display_name.clone(),
+        description: user
+            .biography
+            .filter(|biography| !biography.trim().is_empty())
+            .unwrap_or_else(|| format!("Posts by {} on {SITE_NAME}", user.display_name)),
+        canonical_url: format!("{}/profile/{}", data.env.app_url, encode_segment(&user.display_name)),
+        image_url: user.profile_image_url,
+        kind: PageKind::Profile {
+            display_name: user.display_name,
+        },
+    }))
+}
+
+/// Puts the page's `<title>`, description, canonical URL, Open Graph and Twitter card tags into
+/// the client's `index.html`, in place of its own title.
+pub fn inject(index_html: &str, meta: &PageMeta) -> String {
+    let title = format!("{} | {SITE_NAME}", meta.title);
+
+    let mut tags = vec![
+        format!("<title>{}</title>", escape(&title)),
+        meta_tag("name", "description", &meta.description),
+        format!("<link rel=\"canonical\" href=\"{}\" />", escape(&meta.canonical_url)),
+        meta_tag("property", "og:site_name", SITE_NAME),
+        meta_tag("property", "og:title", &meta.title),
+        meta_tag("property", "og:description", &meta.description),
+        meta_tag("property", "og:url", &meta.canonical_url),
+        meta_tag("name", "twitter:card", "summary"),
+        meta_tag("name", "twitter:title", &meta.title),
+        meta_tag("name", "twitter:description", &meta.description),
+    ];
+    if let Some(image_url) = &meta.image_url {
+        tags.push(meta_tag("property", "og:image", image_url));
+        tags.push(meta_tag("name", "twitter:image", image_url));
+    }
+    match &meta.kind {
+        PageKind::Article { author, tags: post_tags, published_time, modified_time } => {
+            tags.push(meta_tag("property", "og:type", "article"));
+            tags.push(meta_tag("property", "article:author", author));
+            tags.push(meta_tag("property", "article:published_time", &published_time.to_rfc3339()));
+            tags.push(meta_tag("property", "article:modified_time", &modified_time.to_rfc3339()));
+            tags.extend(post_tags.iter().map(|tag| meta_tag("property", "article:tag", tag)));
+        }
+        PageKind::Profile { display_name } => {
+            tags.push(meta_tag("property", "og:type", "profile"));
+            tags.push(meta_tag("property", "profile:username", display_name));
+            // Lets feed readers find the author's feed from their profile.
+            tags.push(format!(
+                "<link rel=\"alternate\" type=\"application/atom+xml\" title=\"{}\" href=\"/feeds/author/{}.atom\" />",
+                escape(&format!("{display_name} (Atom)")),
+                encode_segment(display_name),
+            ));
+        }
+    }
+    let tags = tags.join("\n    ");
+
+    match (index_html.find("<title>"), index_html.find("</title>")) {
+        (Some(start), Some(end)) if start < end => {
+            format!("{}{tags}{}", &index_html[..start], &index_html[end + "</title>".len()..])
+        }
+        _ => index_html.replacen("</head>", &format!("  {tags}\n  </head>"), 1),
+    }
+}
+
+fn meta_tag(attribute: &str, name: &str, content: &str) -> String {
+    format!("<meta {attribute}=\"{name}\" content=\"{}\" />", escape(content))
+}
+
+/// Percent-encodes a display name, slug or tag for use as one segment of a URL path.
+pub fn encode_segment(segment: &str) -> String {
+    utf8_percent_encode(segment, PATH_SEGMENT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX_HTML: &str =
+        "<html>\n  <head>\n    <title>Negatiview</title>\n  </head>\n  <body><div id=\"app\"></div></body>\n</html>";
+
+    fn profile_meta(display_name: &str) -> PageMeta {
+        PageMeta {
+            title: format!("<script>alert(1)</script> & \"{display_name}\""),
+            description: "\"><script>alert(2)</script>".to_string(),
+            canonical_url: "https://example.com/profile/a?b=1&c=\"2\"".to_string(),
+            image_url: None,
+            kind: PageKind::Profile {
+                display_name: display_name.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn inject_escapes_titles_and_descriptions() {
+        let html = inject(INDEX_HTML, &profile_meta("a\"b<c"));
+
+        assert!(!html.contains("<script>"), "{html}");
+        for expected in [
+            format!("<title>&lt;script&gt;alert(1)&lt;/script&gt; &amp; &quot;a&quot;b&lt;c&quot; | {SITE_NAME}</title>"),
+            "<meta name=\"description\" content=\"&quot;&gt;&lt;script&gt;alert(2)&lt;/script&gt;\" />".to_string(),
+            "<link rel=\"canonical\" href=\"https://example.com/profile/a?b=1&amp;c=&quot;2&quot;\" />".to_string(),
+            "<meta property=\"profile:username\" content=\"a&quot;b&lt;c\" />".to_string(),
+            "href=\"/feeds/author/a%22b%3Cc.atom\"".to_string(),
+        ] {
+            assert!(html.contains(&expected), "{expected} in {html}");
+        }
+    }
+
+    #[test]
+    fn inject_replaces_the_title() {
+        let html = inject(INDEX_HTML, &profile_meta("alice"));
+
+        assert_eq!(html.matches("<title>").count(), 1, "{html}");
+        assert!(!html.contains("<title>Negatiview</title>"), "{html}");
+        assert!(html.contains("<div id=\"app\"></div>"), "{html}");
+    }
+
+    #[test]
+    fn inject_adds_tags_to_a_page_without_a_title() {
+        let html = inject("<html><head></head><body></body></html>", &profile_meta("alice"));
+
+        let title = html.find("<title>").expect("a title is added");
+        assert!(title < html.find("</head>").unwrap(), "{html}");
+    }
+
+    #[test]
+    fn encode_segment_escapes_everything_but_unreserved_characters() {
+        assert_eq!(encode_segment("Rust-lang_1.0~"), "Rust-lang_1.0~");
+        assert_eq!(encode_segment("a b/c?d#e%f"), "a%20b%2Fc%3Fd%23e%25f");
+        assert_eq!(encode_segment("..\\"), "..%5C");
+        assert_eq!(encode_segment("café"), "caf%C3%A9");
+    }
+}
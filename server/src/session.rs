@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::{header, HeaderMap};
+use axum::http::request::Parts;
+use chrono::{DateTime, Utc};
+use redis::aio::Connection;
+use redis::{AsyncCommands, RedisResult};
+use uuid::Uuid;
+
+use crate::config::AppState;
+
+/// Device details recorded when a session starts.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_owned());
+
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let ip_address = peer
+            .map(|peer| client_address(peer, &parts.headers, &state.env.trusted_proxies))
+            .map(|address| address.to_string());
+
+        Ok(ClientInfo {
+            user_agent,
+            ip_address,
+        })
+    }
+}
+
+/// Where a request comes from. Each trusted proxy appends the address it was connected from to
+/// `X-Forwarded-For`, so that is followed back from the peer for as long as the hops are trusted
+/// proxies. Anything further left was written by the client and can't be believed.
+fn client_address(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let forwarded_for: Vec<&str> = headers
+        .get_all("X-Forwarded-For")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+
+    let mut address = peer;
+    for hop in forwarded_for.into_iter().rev() {
+        if !trusted_proxies.contains(&address) {
+            break;
+        }
+        match hop.parse::<IpAddr>() {
+            Ok(hop) => address = hop,
+            Err(_) => break,
+        }
+    }
+
+    address
+}
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub access_token_uuid: Option<Uuid>,
+    pub refresh_token_uuid: Option<Uuid>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl Session {
+    fn from_hash(id: Uuid, hash: HashMap<String, String>) -> Option<Session> {
+        let uuid = |field: &str| hash.get(field).and_then(|value| Uuid::parse_str(value).ok());
+        let timestamp = |field: &str| {
+            hash.get(field)
+                .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+                .map(|value| value.with_timezone(&Utc))
+        };
+
+        Some(Session {
+            id,
+            user_id: uuid("user_id")?,
+            access_token_uuid: uuid("access_token_uuid"),
+            refresh_token_uuid: uuid("refresh_token_uuid"),
+            user_agent: hash.get("user_agent").cloned(),
+            ip_address: hash.get("ip_address").cloned(),
+            created_at: timestamp("created_at")?,
+            last_seen_at: timestamp("last_seen_at")?,
+        })
+    }
+}
+
+fn session_key(session_id: Uuid) -> String {
+    format!("session:{session_id}")
+}
+
+pub fn token_session_key(token_uuid: Uuid) -> String {
+    format!("{token_uuid}:session_id")
+}
+
+fn user_sessions_key(user_id: Uuid) -> String {
+    format!("user:{user_id}:sessions")
+}
+
+/// Records a new session for `user_id` and returns its id.
+pub async fn start(
+    redis_client: &mut Connection,
+    user_id: Uuid,
+    client_info: &ClientInfo,
+    ttl: usize,
+) -> RedisResult<Uuid> {
+    let session_id = Uuid::new_v4();
+    let now = Utc::now().to_rfc3339();
+
+    let mut fields = vec![
+        ("user_id", user_id.to_string()),
+        ("created_at", now.clone()),
+        ("last_seen_at", now),
+    ];
+    if let Some(user_agent) = &client_info.user_agent {
+        fields.push(("user_agent", user_agent.clone()));
+    }
+    if let Some(ip_address) = &client_info.ip_address {
+        fields.push(("ip_address", ip_address.clone()));
+    }
+
+    redis::pipe()
+        .atomic()
+        .hset_multiple(session_key(session_id), &fields)
+        .expire(session_key(session_id), ttl)
+        .sadd(user_sessions_key(user_id), session_id.to_string())
+        .expire(user_sessions_key(user_id), ttl)
+        .query_async::<_, ()>(redis_client)
+        .await?;
+
+    Ok(session_id)
+}
+
+/// Binds a freshly issued token pair to a session, extending the session's lifetime.
+pub async fn attach_tokens(
+    redis_client: &mut Connection,
+    session_id: Uuid,
+    access_token_uuid: Uuid,
+    refresh_token_uuid: Uuid,
+    ttl: usize,
+) -> RedisResult<()> {
+    redis::pipe()
+        .atomic()
+        .hset_multiple(
+            session_key(session_id),
+            &[
+                ("access_token_uuid", access_token_uuid.to_string()),
+                ("refresh_token_uuid", refresh_token_uuid.to_string()),
+            ],
+        )
+        .expire(session_key(session_id), ttl)
+        .set_ex(token_session_key(access_token_uuid), session_id.to_string(), ttl)
+        .set_ex(token_session_key(refresh_token_uuid), session_id.to_string(), ttl)
+        .query_async(redis_client)
+        .await
+}
+
+pub async fn find_by_token(redis_client: &mut Connection, token_uuid: Uuid) -> RedisResult<Option<Uuid>> {
+    let session_id = redis_client
+        .get::<_, Option<String>>(token_session_key(token_uuid))
+        .await?;
+
+    Ok(session_id.and_then(|session_id| Uuid::parse_str(&session_id).ok()))
+}
+
+pub async fn find(redis_client: &mut Connection, session_id: Uuid) -> RedisResult<Option<Session>> {
+    let hash = redis_client
+        .hgetall::<_, HashMap<String, String>>(session_key(session_id))
+        .await?;
+
+    Ok(Session::from_hash(session_id, hash))
+}
+
+/// Lists the live sessions of a user, most recently used first.
+pub async fn list(redis_client: &mut Connection, user_id: Uuid) -> RedisResult<Vec<Session>> {
+    let session_ids = redis_client
+        .smembers::<_, Vec<String>>(user_sessions_key(user_id))
+        .await?;
+
+    let mut sessions = Vec::new();
+    for session_id in session_ids {
+        let Ok(session_id) = Uuid::parse_str(&session_id) else {
+            continue;
+        };
+
+        match find(redis_client, session_id).await? {
+            Some(session) => sessions.push(session),
+            None => {
+                redis_client
+                    .srem::<_, _, ()>(user_sessions_key(user_id), session_id.to_string())
+                    .await?
+            }
+        }
+    }
+
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.last_seen_at));
+    Ok(sessions)
+}
+
+/// Updates the last-seen time of the session the access token belongs to.
+pub async fn touch(redis_client: &mut Connection, access_token_uuid: Uuid) -> RedisResult<()> {
+    if let Some(session_id) = find_by_token(redis_client, access_token_uuid).await? {
+        redis_client
+            .hset::<_, _, _, ()>(session_key(session_id), "last_seen_at", Utc::now().to_rfc3339())
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Forgets a session. Its tokens must be revoked separately.
+pub async fn end(redis_client: &mut Connection, user_id: Uuid, session_id: Uuid) -> RedisResult<()> {
+    redis::pipe()
+        .atomic()
+        .del(session_key(session_id))
+        .srem(user_sessions_key(user_id), session_id.to_string())
+        .query_async(redis_client)
+        .await
+}
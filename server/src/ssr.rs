@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::extract::Query;
+use axum::http::{Request, StatusCode, Uri};
+use axum::Router;
+use client::middlewares::prefetch::{PrefetchedResponses, STATE_ELEMENT_ID};
+use client::routes::{App, AppProps, AppRoute};
+use tower::ServiceExt;
+use yew::ServerRenderer;
+use yew_router::Routable;
+
+use crate::error::AppError;
+
+/// How long rendering a page may take before the client is left to render it.
+const RENDER_TIMEOUT: Duration = Duration::from_secs(5);
+/// Where `index.html` has the client app rendered.
+const ROOT_ELEMENT: &str = "<div id=\"app\"></div>";
+
+/// Renders the client app as it will first show the page at `uri` into `index_html`, along with the
+/// responses it was rendered from for the client to hydrate it with. When that fails,
+/// `index_html` is returned as is, for the client to render the page from scratch.
+///
+/// `api` answers the page's requests as it would the client's, signed out.
+pub async fn render(api: Router, uri: &Uri, index_html: String) -> String {
+    match try_render(api, uri, &index_html).await {
+        Ok(rendered) => rendered,
+        Err(err) => {
+            log::warn!("Failed to render {uri}, leaving it to the client: {err:?}");
+            index_html
+        }
+    }
+}
+
+async fn try_render(api: Router, uri: &Uri, index_html: &str) -> Result<String, AppError> {
+    if !index_html.contains(ROOT_ELEMENT) {
+        return Err(AppError::Internal("index.html has no element for the app".to_string()));
+    }
+
+    let path = uri.path().to_string();
+    let Query(query) = Query::<HashMap<String, String>>::try_from_uri(uri).unwrap_or_default();
+    let prefetched = match AppRoute::recognize(&path) {
+        Some(route) => prefetch(api, &route).await,
+        None => PrefetchedResponses::new(),
+    };
+    let state = serde_json::to_string(&prefetched)
+        .map_err(|err| AppError::Internal(format!("Failed to serialize prefetched responses: {err}")))?
+        // Keeps the JSON from closing the script it's embedded in.
+        .replace('<', "\\u003c");
+
+    let renderer = ServerRenderer::<App>::with_props(move || AppProps {
+        path: Some(path),
+        query,
+        prefetched,
+    });
+    // Rendering runs in its own task; a component panicking there only fails that task, and one
+    // that never finishes is aborted rather than left running in the background.
+    let mut rendering = tokio::spawn(renderer.render());
+    let body = match tokio::time::timeout(RENDER_TIMEOUT, &mut rendering).await {
+        Ok(rendered) => rendered.map_err(|err| AppError::Internal(format!("Rendering panicked: {err}")))?,
+        Err(_) => {
+            rendering.abort();
+            return Err(AppError::Internal("Rendering timed out".to_string()));
+        }
+    };
+
+    Ok(index_html
+        .replacen(ROOT_ELEMENT, &format!("<div id=\"app\">{body}</div>"), 1)
+        .replacen(
+            "</head>",
+            &format!("  <script id=\"{STATE_ELEMENT_ID}\" type=\"application/json\">{state}</script>\n  </head>"),
+            1,
+        ))
+}
+
+/// Makes the API requests the route's page makes as it first renders. Responses other than a
+/// success are left out, for the client to request again and handle itself.
+async fn prefetch(api: Router, route: &AppRoute) -> PrefetchedResponses {
+    let mut responses = PrefetchedResponses::new();
+    for url in route.prefetch_urls() {
+        let request = match Request::get(&url).body(Body::empty()) {
+            Ok(request) => request,
+            Err(err) => {
+                log::warn!("Failed to build prefetch request for {url}: {err}");
+                continue;
+            }
+        };
+        let response = match api.clone().oneshot(request).await {
+            Ok(response) if response.status() == StatusCode::OK => response,
+            _ => continue,
+        };
+        let body = match hyper::body::to_bytes(response.into_body()).await {
+            Ok(body) => body,
+            Err(err) => {
+                log::warn!("Failed to read prefetched {url}: {err}");
+                continue;
+            }
+        };
+        match serde_json::from_slice(&body) {
+            Ok(value) => {
+                responses.insert(url, value);
+            }
+            Err(err) => log::warn!("Prefetched {url} is not JSON: {err}"),
+        }
+    }
+
+    responses
+}
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::body::Bytes;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::{ClientOptions, ObjectStore};
+use tokio::fs;
+
+use crate::config::{Config, StorageBackend};
+use crate::error::AppError;
+
+/// Extensions of the files stored, and the content type each is served with.
+pub const CONTENT_TYPES: [(&str, &str); 2] = [("jpg", "image/jpeg"), ("png", "image/png")];
+
+/// Where uploaded files are kept. Keys are relative paths such as `<id>/thumbnail.jpg`; the
+/// content type a file is served with follows from its extension.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), AppError>;
+
+    /// Succeeds when there is nothing at `key`.
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+
+    /// Where browsers fetch the file at `key` from.
+    fn url(&self, key: &str) -> String;
+}
+
+/// The storage selected by `STORAGE_BACKEND`.
+pub fn from_config(config: &Config) -> Arc<dyn Storage> {
+    match config.storage_backend {
+        StorageBackend::Local => Arc::new(LocalStorage {
+            dir: PathBuf::from(&config.upload_dir),
+            base_url: config.media_url.clone(),
+        }),
+        StorageBackend::S3 => Arc::new(S3Storage::new(config)),
+    }
+}
+
+/// Keeps files under `dir`, which the router serves at `/media`.
+pub struct LocalStorage {
+    dir: PathBuf,
+    base_url: String,
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), AppError> {
+        let path = self.dir.join(key);
+        let written = async {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(&path, bytes).await
+        }
+            .await;
+
+        written.map_err(|err| AppError::Internal(format!("Failed to write {}: {err}", path.display())))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let path = self.dir.join(key);
+        match fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(AppError::Internal(format!("Failed to delete {}: {err}", path.display()))),
+        }
+
+        // Files are grouped in a directory per upload; it goes once it's empty.
+        if let Some(parent) = path.parent().filter(|parent| *parent != self.dir) {
+            let _ = fs::remove_dir(parent).await;
+        }
+
+        Ok(())
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{key}", self.base_url)
+    }
+}
+
+/// Keeps files in an S3 bucket, or any service speaking the same API. Path-style requests are used
+/// and plain HTTP is allowed so that a local stand-in such as MinIO works.
+pub struct S3Storage {
+    store: AmazonS3,
+    base_url: String,
+}
+
+impl S3Storage {
+    pub fn new(config: &Config) -> Self {
+        let bucket = config
+            .s3_bucket
+            .as_ref()
+            .unwrap_or_else(|| panic!("S3_BUCKET must be set when STORAGE_BACKEND is s3"));
+
+        let client_options = CONTENT_TYPES
+            .iter()
+            .fold(ClientOptions::new(), |options, (extension, content_type)| {
+                options.with_content_type_for_suffix(*extension, *content_type)
+            });
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(bucket)
+            .with_region(&config.s3_region)
+            .with_allow_http(true)
+            .with_virtual_hosted_style_request(false)
+            .with_client_options(client_options);
+        if let Some(endpoint) = &config.s3_endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+        if let (Some(access_key_id), Some(secret_access_key)) =
+            (&config.s3_access_key_id, &config.s3_secret_access_key)
+        {
+            builder = builder
+                .with_access_key_id(access_key_id)
+                .with_secret_access_key(secret_access_key);
+        }
+
+        S3Storage {
+            store: builder
+                .build()
+                .unwrap_or_else(|err| panic!("Invalid S3 configuration: {err}")),
+            base_url: config.media_url.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), AppError> {
+        self.store
+            .put(&Path::from(key), bytes)
+            .await
+            .map_err(|err| AppError::Internal(format!("Failed to upload {key}: {err}")))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match self.store.delete(&Path::from(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(err) => Err(AppError::Internal(format!("Failed to delete {key}: {err}"))),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{key}", self.base_url)
+    }
+}
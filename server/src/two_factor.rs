@@ -0,0 +1,365 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use rand_core::{OsRng, RngCore};
+use redis::AsyncCommands;
+use sqlx::PgPool;
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::handlers::user::get_hashed_password;
+use crate::validation::ValidationErrors;
+
+/// Shown as the account's issuer in authenticator apps.
+const ISSUER: &str = "negatiview";
+const DIGITS: usize = 6;
+const STEP: u64 = 30;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+/// Recovery codes avoid characters that are easily mistaken for one another.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+const RECOVERY_CODE_LENGTH: usize = 10;
+/// How long a user has to enter their code after their password, in seconds.
+const PENDING_TOKEN_TTL: usize = 300;
+/// Wrong codes allowed per pending token before the user has to enter their password again.
+const MAX_ATTEMPTS: i64 = 5;
+/// Wrong codes allowed per user, however many times their password was entered, before codes are
+/// refused for `LOCKOUT_WINDOW`.
+const MAX_FAILURES: i64 = 10;
+/// In seconds, counted from the latest wrong code.
+const LOCKOUT_WINDOW: usize = 900;
+
+fn pending_token_key(token: Uuid) -> String {
+    format!("two_factor_pending:{token}")
+}
+
+fn attempts_key(token: Uuid) -> String {
+    format!("two_factor_attempts:{token}")
+}
+
+fn failures_key(user_id: Uuid) -> String {
+    format!("two_factor_failures:{user_id}")
+}
+
+fn locked_out() -> AppError {
+    AppError::TooManyRequests(format!(
+        "Too many wrong codes; try again in {} minutes",
+        LOCKOUT_WINDOW / 60,
+    ))
+}
+
+/// The error for a code that doesn't check out, reported on the `code` field.
+pub fn invalid_code() -> AppError {
+    let mut errors = ValidationErrors::default();
+    errors.add("code", "is invalid");
+    errors.into()
+}
+
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// The `otpauth://` URI authenticator apps are set up with.
+pub fn provisioning_uri(secret: &str, email: &str) -> Result<String, AppError> {
+    Ok(totp(secret, email)?.get_url())
+}
+
+/// `provisioning_uri` as a QR code to scan.
+pub fn qr_code_svg(provisioning_uri: &str) -> Result<String, AppError> {
+    let code = QrCode::new(provisioning_uri)
+        .map_err(|err| AppError::Internal(format!("Failed to encode QR code: {err}")))?;
+
+    Ok(code.render::<svg::Color>().min_dimensions(200, 200).build())
+}
+
+pub async fn is_enabled(db: &PgPool, user_id: Uuid) -> Result<bool, AppError> {
+    let enabled = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM user_totp WHERE user_id = $1 AND enabled_at IS NOT NULL) AS "enabled!""#,
+        user_id,
+    )
+        .fetch_one(db)
+        .await?;
+
+    Ok(enabled)
+}
+
+/// Checks `code` like [`verify`], counting wrong ones against the user: past `MAX_FAILURES` in a
+/// row, codes are refused without being checked until `LOCKOUT_WINDOW` has passed, which keeps a
+/// six-digit code from being guessed however many times the password is entered.
+pub async fn check(data: &AppState, user_id: Uuid, code: &str) -> Result<(), AppError> {
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    let failures = redis_client
+        .get::<_, Option<i64>>(failures_key(user_id))
+        .await?
+        .unwrap_or_default();
+    if failures >= MAX_FAILURES {
+        return Err(locked_out());
+    }
+
+    if verify(&data.db, user_id, code).await? {
+        redis_client.del::<_, ()>(failures_key(user_id)).await?;
+        return Ok(());
+    }
+
+    let (failures,): (i64,) = redis::pipe()
+        .atomic()
+        .incr(failures_key(user_id), 1)
+        .expire(failures_key(user_id), LOCKOUT_WINDOW)
+        .ignore()
+        .query_async(&mut redis_client)
+        .await?;
+    if failures >= MAX_FAILURES {
+        return Err(locked_out());
+    }
+
+    Err(invalid_code())
+}
+
+/// Checks a code from the user's authenticator app or, failing that, one of their recovery codes,
+/// which is then used up.
+pub async fn verify(db: &PgPool, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+    let code = normalize(code);
+
+    if code.len() == DIGITS && code.chars().all(|c| c.is_ascii_digit()) {
+        let secret = sqlx::query_scalar!(
+            "SELECT secret FROM user_totp WHERE user_id = $1 AND enabled_at IS NOT NULL",
+            user_id,
+        )
+            .fetch_optional(db)
+            .await?;
+
+        return match secret {
+            Some(secret) => accept_totp(db, user_id, &secret, &code).await,
+            None => Ok(false),
+        };
+    }
+
+    use_recovery_code(db, user_id, &code).await
+}
+
+/// Whether `code` is right for `secret`, allowing for a step of clock drift either way. A code is
+/// only accepted once, and never after a later one, so one that was overheard can't be replayed.
+pub async fn accept_totp(db: &PgPool, user_id: Uuid, secret: &str, code: &str) -> Result<bool, AppError> {
+    let totp = totp(secret, "")?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| AppError::Internal(format!("System clock error: {err}")))?
+        .as_secs();
+
+    let step = [now.saturating_sub(STEP), now, now + STEP]
+        .into_iter()
+        .find(|&time| totp.generate(time) == normalize(code))
+        .map(|time| (time / STEP) as i64);
+
+    let Some(step) = step else {
+        return Ok(false);
+    };
+
+    let accepted = sqlx::query_scalar!(
+        r#"
+            UPDATE user_totp
+            SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            RETURNING user_id
+        "#,
+        user_id,
+        step,
+    )
+        .fetch_optional(db)
+        .await?
+        .is_some();
+
+    Ok(accepted)
+}
+
+async fn use_recovery_code(db: &PgPool, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+    if code.len() != RECOVERY_CODE_LENGTH {
+        return Ok(false);
+    }
+
+    let candidates = sqlx::query!(
+        "SELECT id, code_hash FROM user_recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        user_id,
+    )
+        .fetch_all(db)
+        .await?;
+
+    let matching = candidates.into_iter().find(|candidate| {
+        PasswordHash::new(&candidate.code_hash)
+            .map(|hash| Argon2::default().verify_password(code.as_bytes(), &hash).is_ok())
+            .unwrap_or(false)
+    });
+
+    let Some(matching) = matching else {
+        return Ok(false);
+    };
+
+    let used = sqlx::query_scalar!(
+        "UPDATE user_recovery_codes SET used_at = NOW() WHERE id = $1 AND used_at IS NULL RETURNING id",
+        matching.id,
+    )
+        .fetch_optional(db)
+        .await?
+        .is_some();
+
+    Ok(used)
+}
+
+/// Replaces the user's recovery codes with new ones, returning them for the user to write down.
+/// Only their hashes are kept.
+pub async fn replace_recovery_codes(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+) -> Result<Vec<String>, AppError> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| random_recovery_code()).collect();
+    let hashes = codes
+        .iter()
+        .map(|code| get_hashed_password(&normalize(code)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    sqlx::query!("DELETE FROM user_recovery_codes WHERE user_id = $1", user_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query!(
+        r#"
+            INSERT INTO user_recovery_codes (user_id, code_hash)
+            SELECT $1, * FROM UNNEST($2::text[])
+        "#,
+        user_id,
+        &hashes,
+    )
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(codes)
+}
+
+/// Issues the token a user who got their password right exchanges, along with a code, for a
+/// signed-in session.
+pub async fn issue_pending_token(data: &AppState, user_id: Uuid) -> Result<Uuid, AppError> {
+    let token = Uuid::new_v4();
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    redis_client
+        .set_ex::<_, _, ()>(pending_token_key(token), user_id.to_string(), PENDING_TOKEN_TTL)
+        .await?;
+
+    Ok(token)
+}
+
+/// Checks `code` for the user `token` was issued to and, if it is right, returns them and spends
+/// the token. Too many wrong codes spend it as well, besides counting toward the user's lockout.
+pub async fn redeem_pending_token(data: &AppState, token: Uuid, code: &str) -> Result<Uuid, AppError> {
+    let expired = || AppError::Unauthorized("Your sign-in has expired; log in again".to_string());
+
+    let mut redis_client = data.redis_client.get_async_connection().await?;
+    let user_id = redis_client
+        .get::<_, Option<String>>(pending_token_key(token))
+        .await?
+        .and_then(|user_id| Uuid::parse_str(&user_id).ok())
+        .ok_or_else(expired)?;
+
+    match check(data, user_id, code).await {
+        Ok(()) => {
+            redis_client
+                .del::<_, ()>(&[pending_token_key(token), attempts_key(token)])
+                .await?;
+            return Ok(user_id);
+        }
+        Err(AppError::TooManyRequests(message)) => {
+            redis_client
+                .del::<_, ()>(&[pending_token_key(token), attempts_key(token)])
+                .await?;
+            return Err(AppError::TooManyRequests(message));
+        }
+        Err(AppError::Validation(_)) => {}
+        Err(err) => return Err(err),
+    }
+
+    let (attempts,): (i64,) = redis::pipe()
+        .atomic()
+        .incr(attempts_key(token), 1)
+        .expire(attempts_key(token), PENDING_TOKEN_TTL)
+        .ignore()
+        .query_async(&mut redis_client)
+        .await?;
+    if attempts >= MAX_ATTEMPTS {
+        redis_client
+            .del::<_, ()>(&[pending_token_key(token), attempts_key(token)])
+            .await?;
+        return Err(expired());
+    }
+
+    Err(invalid_code())
+}
+
+fn totp(secret: &str, account_name: &str) -> Result<TOTP, AppError> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|err| AppError::Internal(format!("Invalid TOTP secret: {err:?}")))?;
+
+    TOTP::new(Algorithm::SHA1, DIGITS, 1, STEP, secret, Some(ISSUER.to_string()), account_name.to_string())
+        .map_err(|err| AppError::Internal(format!("Invalid TOTP parameters: {err}")))
+}
+
+fn random_recovery_code() -> String {
+    let code: String = (0..RECOVERY_CODE_LENGTH)
+        .map(|_| RECOVERY_CODE_ALPHABET[OsRng.next_u32() as usize % RECOVERY_CODE_ALPHABET.len()] as char)
+        .collect();
+    let (first, second) = code.split_at(RECOVERY_CODE_LENGTH / 2);
+
+    format!("{first}-{second}")
+}
+
+/// Codes are accepted however they were copied: with or without spaces, dashes or capitals.
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_ignores_spaces_dashes_and_case() {
+        assert_eq!(normalize(" AbCde-FgHjk "), "abcdefghjk");
+        assert_eq!(normalize("123 456"), "123456");
+        assert_eq!(normalize("abcde\t-\nfghjk"), "abcdefghjk");
+    }
+
+    #[test]
+    fn recovery_codes_are_two_dashed_halves_of_the_alphabet() {
+        for _ in 0..100 {
+            let code = random_recovery_code();
+
+            let (first, second) = code.split_once('-').expect("recovery codes have a dash");
+            assert_eq!(first.len(), RECOVERY_CODE_LENGTH / 2, "{code}");
+            assert_eq!(second.len(), RECOVERY_CODE_LENGTH - RECOVERY_CODE_LENGTH / 2, "{code}");
+            assert!(
+                first.bytes().chain(second.bytes()).all(|c| RECOVERY_CODE_ALPHABET.contains(&c)),
+                "{code}",
+            );
+        }
+    }
+
+    #[test]
+    fn recovery_codes_survive_normalizing() {
+        let code = random_recovery_code();
+
+        assert_eq!(normalize(&code).len(), RECOVERY_CODE_LENGTH);
+        assert_eq!(normalize(&code.to_uppercase()), normalize(&code));
+    }
+
+    #[test]
+    fn recovery_codes_differ() {
+        let codes: std::collections::HashSet<String> =
+            (0..RECOVERY_CODE_COUNT).map(|_| random_recovery_code()).collect();
+
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+    }
+}
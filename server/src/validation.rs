@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use axum::async_trait;
+use axum::body::HttpBody;
+use axum::extract::{FromRequest, Json};
+use axum::http::Request;
+use axum::BoxError;
+use serde::de::DeserializeOwned;
+
+use crate::dtos::Wrapper;
+use crate::error::AppError;
+
+pub const EMAIL_MAX_LENGTH: usize = 254;
+pub const PASSWORD_MIN_LENGTH: usize = 8;
+pub const PASSWORD_MAX_LENGTH: usize = 128;
+pub const DISPLAY_NAME_MIN_LENGTH: usize = 3;
+pub const DISPLAY_NAME_MAX_LENGTH: usize = 32;
+pub const BIOGRAPHY_MAX_LENGTH: usize = 1000;
+pub const URL_MAX_LENGTH: usize = 2048;
+pub const TITLE_MAX_LENGTH: usize = 200;
+pub const DESCRIPTION_MAX_LENGTH: usize = 500;
+pub const BODY_MAX_LENGTH: usize = 50_000;
+pub const TAGS_MAX_COUNT: usize = 10;
+pub const TAG_MAX_LENGTH: usize = 32;
+pub const COMMENT_MAX_LENGTH: usize = 5000;
+pub const REASON_MAX_LENGTH: usize = 500;
+pub const REPORT_DETAILS_MAX_LENGTH: usize = 1000;
+
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+impl<T: Validate> Validate for Wrapper<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.data.validate()
+    }
+}
+
+/// Messages collected per field, serialized as the `errors` object of a 422 response.
+#[derive(Debug, Default)]
+pub struct ValidationErrors(HashMap<String, Vec<String>>);
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.0.entry(field.to_string()).or_default().push(message.into());
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn required(&mut self, field: &str, value: &str) -> bool {
+        if value.trim().is_empty() {
+            self.add(field, "can't be blank");
+            return false;
+        }
+        true
+    }
+
+    pub fn max_length(&mut self, field: &str, value: &str, max: usize) {
+        if value.chars().count() > max {
+            self.add(field, format!("must be at most {max} characters"));
+        }
+    }
+
+    pub fn email(&mut self, field: &str, value: &str) {
+        if !self.required(field, value) {
+            return;
+        }
+        self.max_length(field, value, EMAIL_MAX_LENGTH);
+
+        let is_valid = match value.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+                    && !value.chars().any(char::is_whitespace)
+            }
+            None => false,
+        };
+        if !is_valid {
+            self.add(field, "is not a valid email address");
+        }
+    }
+
+    pub fn password(&mut self, field: &str, value: &str) {
+        let length = value.chars().count();
+        if length < PASSWORD_MIN_LENGTH {
+            self.add(field, format!("must be at least {PASSWORD_MIN_LENGTH} characters"));
+        }
+        if length > PASSWORD_MAX_LENGTH {
+            self.add(field, format!("must be at most {PASSWORD_MAX_LENGTH} characters"));
+        }
+        if !value.chars().any(char::is_alphabetic) || !value.chars().any(|c| c.is_ascii_digit()) {
+            self.add(field, "must contain at least one letter and one digit");
+        }
+    }
+
+    /// Display names appear in `/profile/:display_name` URLs, so they are limited to characters
+    /// that never need escaping.
+    pub fn display_name(&mut self, field: &str, value: &str) {
+        if !self.required(field, value) {
+            return;
+        }
+
+        let length = value.chars().count();
+        if !(DISPLAY_NAME_MIN_LENGTH..=DISPLAY_NAME_MAX_LENGTH).contains(&length) {
+            self.add(
+                field,
+                format!("must be between {DISPLAY_NAME_MIN_LENGTH} and {DISPLAY_NAME_MAX_LENGTH} characters"),
+            );
+        }
+        if !value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+            self.add(field, "may only contain letters, digits, '_', '-' and '.'");
+        }
+    }
+
+    pub fn url(&mut self, field: &str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+        self.max_length(field, value, URL_MAX_LENGTH);
+        if !(value.starts_with("http://") || value.starts_with("https://")) {
+            self.add(field, "must be an http or https URL");
+        }
+    }
+
+    pub fn tags(&mut self, field: &str, tags: &[String]) {
+        if tags.len() > TAGS_MAX_COUNT {
+            self.add(field, format!("must have at most {TAGS_MAX_COUNT} tags"));
+        }
+        for tag in tags {
+            if tag.trim().is_empty() {
+                self.add(field, "can't contain blank tags");
+            } else if tag.chars().count() > TAG_MAX_LENGTH {
+                self.add(field, format!("'{tag}' must be at most {TAG_MAX_LENGTH} characters"));
+            } else if tag.chars().any(char::is_whitespace) {
+                self.add(field, format!("'{tag}' can't contain whitespace"));
+            }
+        }
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(errors.0)
+    }
+}
+
+/// Like [`Json`], but runs [`Validate`] on the payload and reports malformed bodies as 422s in the
+/// same shape as field errors.
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S, B> FromRequest<S, B> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await.map_err(|rejection| {
+            let mut errors = ValidationErrors::default();
+            errors.add("request", rejection.body_text());
+            AppError::from(errors)
+        })?;
+
+        value.validate()?;
+
+        Ok(ValidatedJson(value))
+    }
+}